@@ -0,0 +1,46 @@
+use dioxus::prelude::*;
+use gloo_timers::future::TimeoutFuture;
+use wasm_bindgen_futures::spawn_local;
+use webext_api::{BadgeConfig, Browser, api::Action};
+
+const IN_FLIGHT: (&str, &str) = ("…", "#6b7280");
+const SUCCEEDED: (&str, &str) = ("✓", "#16a34a");
+const FAILED: (&str, &str) = ("!", "#dc2626");
+
+// how long the outcome stays on the badge
+const CLEAR_AFTER_MS: u32 = 5_000;
+
+/// A summary in progress, shown on the action badge of the tab it is for, so it is visible without the popup open:
+/// `…` while the server works on it, then `✓` or `!` for a few seconds.
+pub(crate) struct Progress {
+	action: Action,
+	tab_id: Option<u32>,
+}
+
+impl Progress {
+	pub(crate) async fn start(browser: &Browser, tab_id: Option<u32>) -> Self {
+		let progress = Self { action: browser.action(), tab_id };
+		progress.show(IN_FLIGHT).await;
+		progress
+	}
+
+	/// Shows the outcome, and clears it again once [`CLEAR_AFTER_MS`] have passed.
+	pub(crate) fn finish(self, succeeded: bool) {
+		let outcome = if succeeded { SUCCEEDED } else { FAILED };
+		spawn_local(async move {
+			self.show(outcome).await;
+			TimeoutFuture::new(CLEAR_AFTER_MS).await;
+			// unless another summary for the same tab has taken the badge over in the meantime
+			if self.action.get_badge_text(self.tab_id).await.is_ok_and(|text| text == outcome.0) {
+				self.show(("", outcome.1)).await;
+			}
+		});
+	}
+
+	async fn show(&self, (text, color): (&str, &str)) {
+		let badge = BadgeConfig { text: Some(text.to_owned()), tab_id: self.tab_id, background_color: Some(color.to_owned()), ..Default::default() };
+		if let Err(e) = self.action.set_badge_text(badge).await {
+			warn!("could not update the badge: {}", e);
+		}
+	}
+}
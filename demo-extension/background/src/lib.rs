@@ -1,79 +1,381 @@
-use common::{ExtMessage, ServerSummarizeRequest, ServerSummarizeResponse};
+use common::{
+	AppError, CONFIG_KEY, Config, Envelope, ExtMessage, HISTORY_KEY, HistoryEntry, SUMMARIZE_PORT, ServerErrorResponse, ServerSummarizeChunk,
+	ServerSummarizeRequest, ServerSummarizeResponse, Source, SummaryOrigin, push_history,
+};
 use dioxus::prelude::*;
-use js_sys::Function;
-use wasm_bindgen::JsCast;
+use futures::{StreamExt, lock::Mutex};
+use gloo_timers::future::TimeoutFuture;
+use std::{cell::Cell, time::Duration};
 use wasm_bindgen::prelude::*;
-use web_extensions_sys::chrome;
-use webext_api::error::ExtensionError;
-
-async fn listener() -> Result<(), ExtensionError> {
-	info!("handling summary call");
-	let summary = handle_summarize_request().await?;
-	info!("sending response back to the popup");
-	let message = serde_wasm_bindgen::to_value(&ExtMessage::SummarizeResponse(summary))?;
-	chrome().runtime().send_message(None, &message, None).await?;
+use wasm_bindgen_futures::spawn_local;
+use webext_api::{
+	Browser, InstallReason, InstalledDetails, TabCreateProperties, TabInfo,
+	api::{Port, Tabs},
+	error::ExtensionError,
+	retry::RetryPolicy,
+};
+
+use crate::badge::Progress;
+
+mod badge;
+mod menu;
+mod notify;
+pub mod offline;
+mod pdf;
+mod queue;
+mod telemetry;
+
+// the server used while the options leave the URL empty
+const DEFAULT_SERVER_URL: &str = env!("SERVER_URL");
+
+#[wasm_bindgen]
+pub fn main() {
+	dioxus::logger::initialize_default();
+	info!("background script initialized with default server URL: {}", DEFAULT_SERVER_URL);
+	if let Err(e) = start_listener() {
+		error!("could not start the summarize listener: {}", e);
+	}
+	if let Err(e) = webext_api::init().and_then(|browser| menu::register(&browser)) {
+		error!("could not set up the context menu: {}", e);
+	}
+	if let Err(e) = webext_api::init().and_then(|browser| telemetry::register(&browser)) {
+		error!("could not set up error reporting: {}", e);
+	}
+	if let Err(e) = webext_api::init().and_then(|browser| notify::register(&browser)) {
+		error!("could not set up notifications: {}", e);
+	}
+	if let Err(e) = webext_api::init().and_then(|browser| register_onboarding(&browser)) {
+		error!("could not set up the onboarding page: {}", e);
+	}
+	match webext_api::init() {
+		Ok(browser) => queue::register(&browser),
+		Err(e) => error!("could not resume the summary queue: {}", e),
+	}
+}
+
+// opens the onboarding page in a new tab on a fresh install; an update keeps the user's settings, so it only gets logged
+fn register_onboarding(browser: &Browser) -> Result<(), ExtensionError> {
+	let runtime = browser.runtime();
+	let tabs = browser.tabs();
+	browser
+		.runtime()
+		.on_installed()?
+		.add_listener(move |details: InstalledDetails| match details.reason {
+			InstallReason::Install => {
+				let tabs = tabs.clone();
+				let page = runtime.get_url("onboarding.html");
+				spawn_local(async move {
+					if let Err(e) = async { tabs.create(&TabCreateProperties::url(page?)).await }.await {
+						error!("could not open the onboarding page: {}", e);
+					}
+				});
+			},
+			InstallReason::Update => info!("updated from version {}", details.previous_version.unwrap_or_default()),
+			_ => {},
+		})?
+		.detach();
 	Ok(())
 }
 
-fn start_listener() {
-	let closure = Closure::<dyn FnMut(JsValue, JsValue, Function)>::new(|message: JsValue, _sender: JsValue, _send_response: Function| {
-		if let Ok(ExtMessage::SummarizeRequest) = serde_wasm_bindgen::from_value(message) {
-			info!("spawning wasm local async fn");
-			wasm_bindgen_futures::spawn_local(async move {
-				info!("starting actual listener");
-				if let Err(e) = listener().await {
-					error!("{}", e.to_string());
+// the popup opens a `SUMMARIZE_PORT` port and sends `SummarizeRequest` over it; chunks, the full summary or an error come back the same way
+fn start_listener() -> Result<(), ExtensionError> {
+	let browser = webext_api::init()?;
+	let runtime = browser.runtime();
+	runtime
+		.on_message::<Envelope>()?
+		.add_listener_with_response({
+			let browser = browser.clone();
+			move |request: Envelope, _sender| {
+				let browser = browser.clone();
+				async move {
+					let payload = match request.clone().open() {
+						Ok(ExtMessage::ToggleOverlay(summary)) => toggle_overlay(&browser.tabs(), summary).await.map_or_else(ExtMessage::Error, |()| ExtMessage::Ack),
+						Ok(ExtMessage::ClearQueue) => {
+							queue::clear(&browser).await;
+							ExtMessage::Ack
+						},
+						Ok(_) => return Ok(None),
+						Err(e) => ExtMessage::Error(e),
+					};
+					Ok(Some(request.reply(Source::Background, payload)))
 				}
+			}
+		})?
+		.detach();
+	runtime
+		.on_connect()?
+		.add_listener(move |port: Port| {
+			if port.name() != SUMMARIZE_PORT {
+				return;
+			}
+			let browser = browser.clone();
+			let reply_port = port.clone();
+			let handle = port.on_message::<Envelope>().and_then(|on_message| {
+				on_message.add_listener(move |request: Envelope| {
+					let (browser, port) = (browser.clone(), reply_port.clone());
+					spawn_local(async move {
+						let result = match request.clone().open() {
+							Ok(ExtMessage::SummarizeRequest) => stream_summary(&browser, &port, &request, false).await,
+							Ok(ExtMessage::SummarizeSelectionRequest) => stream_summary(&browser, &port, &request, true).await,
+							Ok(_) => return,
+							Err(e) => Err(e),
+						};
+						if let Err(e) = result {
+							error!("{}", e);
+							telemetry::report(&browser, &e).await;
+							// the popup may have closed already, in which case nobody is left to tell
+							let _ = port.post_message(&request.reply(Source::Background, ExtMessage::Error(e)));
+						}
+					});
+				})
 			});
-		}
-	});
-	chrome().runtime().on_message().add_listener(closure.as_ref().unchecked_ref());
-	closure.forget();
+			// removed once either side disconnects, instead of waiting on the garbage collector
+			match (handle, port.on_disconnect().and_then(|on_disconnect| on_disconnect.once())) {
+				(Ok(handle), Ok(disconnected)) => spawn_local(async move {
+					disconnected.await;
+					drop(handle);
+				}),
+				(Err(e), _) | (_, Err(e)) => error!("Failed to listen on the summarize port: {}", e),
+			}
+		})?
+		.detach();
+	Ok(())
 }
 
-const SERVER_URL: &str = env!("SERVER_URL");
+pub(crate) async fn active_tab(tabs: &Tabs) -> Result<(u32, TabInfo), AppError> {
+	let tab = tabs.get_active().await.map_err(|e| AppError::ExtensionError(e.to_string()))?;
+	let tab_id = tab.id.ok_or_else(|| AppError::ExtensionError("No tab id".to_owned()))?;
+	Ok((tab_id, tab))
+}
 
-#[wasm_bindgen]
-pub fn main() {
-	dioxus::logger::initialize_default();
-	info!("background script initialized with server URL: {}", SERVER_URL);
-	start_listener();
+// sends `payload` to the tab's content script and waits for its answer
+async fn ask_tab(tabs: &Tabs, tab_id: u32, payload: ExtMessage) -> Result<ExtMessage, AppError> {
+	let request = Envelope::request(Source::Background, payload);
+	info!("sending request {} to tab {}", request.id, tab_id);
+	let response: Envelope = tabs.send_message(tab_id, &request).await.map_err(|_| AppError::ContentScriptError)?;
+	response.response_to(&request)
 }
 
-async fn call_summarize_api(req: ServerSummarizeRequest) -> Result<ServerSummarizeResponse, ExtensionError> {
-	let url = format!("{}/api/summarize", SERVER_URL);
-	let client = reqwest::Client::new();
-	let response = client
-		.post(&url)
-		.json(&req)
-		.send()
-		.await
-		.map_err(|e| ExtensionError::ApiError(format!("Request failed: {}", e)))?;
+// the popup can't reach the page itself, so it asks the background to pass the summary on to the active tab's content script
+async fn toggle_overlay(tabs: &Tabs, summary: String) -> Result<(), AppError> {
+	let (tab_id, _) = active_tab(tabs).await?;
+	ask_tab(tabs, tab_id, ExtMessage::ToggleOverlay(summary)).await.map(drop)
+}
 
-	if !response.status().is_success() {
-		let status = response.status();
-		let body = response.text().await.unwrap_or_default();
-		return Err(ExtensionError::ApiError(format!("Server error {}: {}", status, body)));
+async fn page_text(tabs: &Tabs, tab_id: u32) -> Result<String, AppError> {
+	let ExtMessage::PageContent(text) = ask_tab(tabs, tab_id, ExtMessage::GetPageContent).await? else {
+		return Err(AppError::ContentScriptError);
+	};
+	if text.trim().is_empty() {
+		return Err(AppError::NoContent);
 	}
+	Ok(text)
+}
 
-	response
-		.json::<ServerSummarizeResponse>()
-		.await
-		.map_err(|e| ExtensionError::ApiError(format!("Failed to parse response: {}", e)))
+// the page's text, or for a PDF the document's, which the content script can't read
+async fn tab_text(tabs: &Tabs, config: &Config, tab_id: u32, url: &str) -> Result<String, AppError> {
+	if pdf::is_pdf_url(url) {
+		return pdf::document_text(config, url).await?.ok_or(AppError::NoContent);
+	}
+	match page_text(tabs, tab_id).await {
+		// PDFs served from URLs that don't say so, like `/pdf/2401.01234`, only show up as a page without content
+		Err(e @ (AppError::ContentScriptError | AppError::NoContent)) => pdf::document_text(config, url).await?.ok_or(e),
+		result => result,
+	}
 }
 
-async fn handle_summarize_request() -> Result<String, ExtensionError> {
-	info!("sending get content request to the content script");
-	let browser = webext_api::init()?;
-	let tab = browser.tabs().get_active().await?;
-	let tab_id = tab.id.ok_or_else(|| ExtensionError::ApiError("No tab id".to_string()))?;
-	info!("sending to tab {}", tab_id);
-	let text: String = browser.tabs().send_message(tab_id, &ExtMessage::GetPageContent).await?;
-	info!("checking response is empty");
+async fn selection_text(tabs: &Tabs, tab_id: u32) -> Result<String, AppError> {
+	let ExtMessage::Selection(text) = ask_tab(tabs, tab_id, ExtMessage::GetSelection).await? else {
+		return Err(AppError::ContentScriptError);
+	};
 	if text.trim().is_empty() {
-		return Err(ExtensionError::ApiError("text is empty".to_string()));
+		return Err(AppError::NoSelection);
 	}
-	info!("sending content to server at {}", SERVER_URL);
-	let summary_res = call_summarize_api(ServerSummarizeRequest { text }).await?;
-	Ok(summary_res.summary)
+	Ok(text)
+}
+
+// held while the history is read, changed and written back, so summaries finishing together don't drop each other's entries
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+// the popup's history view picks the new entry up through `storage.onChanged`
+pub(crate) async fn save_to_history(browser: &Browser, entry: HistoryEntry) -> Result<(), ExtensionError> {
+	let _guard = HISTORY_LOCK.lock().await;
+	let local = browser.storage().local();
+	let mut history = local.get::<Vec<HistoryEntry>>(HISTORY_KEY).await?.unwrap_or_default();
+	push_history(&mut history, entry);
+	local.set(HISTORY_KEY, &history).await
+}
+
+pub(crate) async fn load_config(browser: &Browser) -> Config {
+	browser.storage().sync().get::<Config>(CONFIG_KEY).await.ok().flatten().unwrap_or_default()
+}
+
+// a POST to the configured server's `path`, authenticated with the configured token; the config is read for every request, so a
+// server URL changed in the options applies to the next one
+pub(crate) fn server_request(config: &Config, path: &str) -> reqwest::RequestBuilder {
+	let request = reqwest::Client::new().post(format!("{}{}", config.server_url_or(DEFAULT_SERVER_URL), path));
+	// without a token the server decides: it answers `MissingConfiguration` if it requires one
+	if config.auth_token.trim().is_empty() { request } else { request.bearer_auth(config.auth_token.trim()) }
+}
+
+// the error in an unsuccessful response of the server
+pub(crate) async fn server_error(response: reqwest::Response) -> AppError {
+	let status = response.status();
+	let body = response.text().await.unwrap_or_default();
+	match serde_json::from_str::<ServerErrorResponse>(&body) {
+		Ok(ServerErrorResponse { app_error: Some(error), .. }) => error,
+		Ok(ServerErrorResponse { error, .. }) => AppError::ServerError(error),
+		Err(_) => AppError::ServerError(format!("{} {}", status, body)),
+	}
+}
+
+/// What [`summarize`] reports while the summary is on its way.
+pub(crate) enum SummaryEvent {
+	/// The next piece of the summary.
+	Delta(String),
+	/// The request failed and is sent again, see [`ExtMessage::SummarizeRetrying`].
+	Retrying { attempt: u32, max_attempts: u32 },
+}
+
+// how often the summary is requested before the server counts as unreachable, a few seconds apart
+fn server_retries() -> RetryPolicy {
+	RetryPolicy::new(3).initial_delay(Duration::from_secs(1)).max_delay(Duration::from_secs(8))
+}
+
+// posts `text` to the server and collects the streamed summary, handing every piece to `on_event` as it arrives
+pub(crate) async fn summarize(
+	config: &Config,
+	text: String,
+	mut on_event: impl FnMut(SummaryEvent) -> Result<(), AppError>,
+) -> Result<ServerSummarizeResponse, AppError> {
+	info!("streaming a summary from {}", config.server_url_or(DEFAULT_SERVER_URL));
+	let response = send_with_retries(config, &ServerSummarizeRequest { text, style: config.summary_style }, &mut on_event).await?;
+
+	let mut summary = String::new();
+	let mut cached = false;
+	let mut buffer = Vec::new();
+	let mut bytes = response.bytes_stream();
+	while let Some(chunk) = bytes.next().await {
+		buffer.extend_from_slice(&chunk.map_err(|_| AppError::Network)?);
+		while let Some(end) = buffer.iter().position(|&byte| byte == b'\n') {
+			let line: Vec<u8> = buffer.drain(..=end).collect();
+			match serde_json::from_slice::<ServerSummarizeChunk>(&line) {
+				Ok(ServerSummarizeChunk::Cached) => cached = true,
+				Ok(ServerSummarizeChunk::Delta(delta)) => {
+					summary.push_str(&delta);
+					on_event(SummaryEvent::Delta(delta))?;
+				},
+				Ok(ServerSummarizeChunk::Error(e)) => return Err(AppError::ServerError(e)),
+				Err(e) => warn!("skipping malformed stream line: {}", e),
+			}
+		}
+	}
+	Ok(ServerSummarizeResponse { summary: summary.trim().to_owned(), cached })
+}
+
+// sends `req` until the server accepts it, waiting a jittered, exponentially growing delay between attempts while the server can't be
+// reached or fails on its side (5xx); a request it rejects (4xx) would only be rejected again, so that error is returned right away
+async fn send_with_retries(
+	config: &Config,
+	req: &ServerSummarizeRequest,
+	on_event: &mut impl FnMut(SummaryEvent) -> Result<(), AppError>,
+) -> Result<reqwest::Response, AppError> {
+	let policy = server_retries();
+	let mut attempt = 1;
+	loop {
+		let result = server_request(config, "/api/summarize/stream").json(req).send().await;
+		let retryable = result.as_ref().map_or(true, |response| response.status().is_server_error());
+		if !retryable || attempt == policy.max_attempts {
+			let response = result.map_err(|_| AppError::Network)?;
+			return if response.status().is_success() { Ok(response) } else { Err(server_error(response).await) };
+		}
+		match result {
+			Ok(response) => warn!("the server failed with {}, retrying", response.status()),
+			Err(e) => warn!("could not reach the server, retrying: {}", e),
+		}
+		// somewhere between half and all of the backoff, so extensions that lost the server together don't all come back at once
+		let delay = policy.delay(attempt).mul_f64(0.5 + js_sys::Math::random() / 2.0);
+		attempt += 1;
+		on_event(SummaryEvent::Retrying { attempt, max_attempts: policy.max_attempts })?;
+		TimeoutFuture::new(delay.as_millis() as u32).await;
+	}
+}
+
+// the server's summary, or an extractive one computed right here when the server can't be reached
+pub(crate) async fn summarize_or_offline(
+	config: &Config,
+	text: String,
+	on_event: impl FnMut(SummaryEvent) -> Result<(), AppError>,
+) -> Result<(String, SummaryOrigin), AppError> {
+	match summarize(config, text.clone(), on_event).await {
+		Ok(ServerSummarizeResponse { summary, cached }) => Ok((summary, if cached { SummaryOrigin::Cache } else { SummaryOrigin::Server })),
+		Err(AppError::Network) => {
+			warn!("the server is unreachable, summarizing offline");
+			let summary = offline::summarize(&text, config.summary_style);
+			if summary.is_empty() { Err(AppError::Network) } else { Ok((summary, SummaryOrigin::Offline)) }
+		},
+		Err(e) => Err(e),
+	}
+}
+
+// `text` cut to at most `chars` characters, with an ellipsis if anything was cut
+pub(crate) fn truncate(text: String, chars: usize) -> String {
+	if text.chars().count() > chars { format!("{}…", text.chars().take(chars).collect::<String>()) } else { text }
+}
+
+// the site rules from the options page, checked before the page's content is even read
+pub(crate) fn check_site(config: &Config, tab: &TabInfo) -> Result<(), AppError> {
+	if config.site_rules.allows(tab.url.as_deref().unwrap_or_default()) { Ok(()) } else { Err(AppError::DisabledOnSite) }
+}
+
+// summarizes the active tab's page, or only the text selected on it
+async fn stream_summary(browser: &Browser, port: &Port, request: &Envelope, selection: bool) -> Result<(), AppError> {
+	let (tab_id, tab) = active_tab(&browser.tabs()).await?;
+	let config = load_config(browser).await;
+	check_site(&config, &tab)?;
+	let progress = Progress::start(browser, Some(tab_id)).await;
+	let result = stream_tab_summary(browser, port, request, &config, tab_id, tab, selection).await;
+	progress.finish(result.is_ok());
+	result
+}
+
+async fn stream_tab_summary(
+	browser: &Browser,
+	port: &Port,
+	request: &Envelope,
+	config: &Config,
+	tab_id: u32,
+	tab: TabInfo,
+	selection: bool,
+) -> Result<(), AppError> {
+	let text = if selection {
+		selection_text(&browser.tabs(), tab_id).await?
+	} else {
+		tab_text(&browser.tabs(), config, tab_id, tab.url.as_deref().unwrap_or_default()).await?
+	};
+	// the popup closes as soon as it loses focus, which disconnects the port; the summary is finished anyway and announced with a
+	// notification instead
+	let popup_closed = Cell::new(false);
+	let post = |payload: ExtMessage| {
+		if !popup_closed.get() && port.post_message(&request.reply(Source::Background, payload)).is_err() {
+			popup_closed.set(true);
+		}
+	};
+	let (summary, origin) = summarize_or_offline(config, text, |event| {
+		post(match event {
+			SummaryEvent::Delta(delta) => ExtMessage::SummarizeChunk(delta),
+			SummaryEvent::Retrying { attempt, max_attempts } => ExtMessage::SummarizeRetrying { attempt, max_attempts },
+		});
+		Ok(())
+	})
+	.await?;
+	let entry = HistoryEntry::new(tab.url.unwrap_or_default(), tab.title.unwrap_or_default(), summary.clone());
+	if let Err(e) = save_to_history(browser, entry.clone()).await {
+		warn!("could not save the summary to the history: {}", e);
+	}
+	post(ExtMessage::SummarizeResponse { summary, origin });
+	if popup_closed.get() {
+		notify::summary_ready(browser, config, tab_id, &entry).await;
+	}
+	Ok(())
 }
@@ -0,0 +1,102 @@
+use common::{AppError, Config, HistoryEntry, markdown::plain_text};
+use dioxus::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+use webext_api::{Browser, ContextMenuConfig, MenuContext, OnClickData, TabInfo, error::ExtensionError};
+
+use crate::{active_tab, badge::Progress, check_site, load_config, queue, save_to_history, summarize_or_offline, telemetry, truncate};
+
+const SUMMARIZE_SELECTION: &str = "summarize-selection";
+const SUMMARIZE_WINDOW: &str = "summarize-window";
+
+// long enough to get the gist from the action's tooltip, the full summary is in the history
+const TOOLTIP_CHARS: usize = 280;
+
+/// Adds "Summarize selection" to the context menu of selected text. The summary skips content extraction, is saved to the
+/// history like any other and is previewed in the action's tooltip, with the badge showing its progress. Pages and the action
+/// get "Summarize all tabs in this window", which hands the window's tabs to the [queue](crate::queue).
+pub(crate) fn register(browser: &Browser) -> Result<(), ExtensionError> {
+	let menus = browser.context_menus();
+	let i18n = browser.i18n();
+	// menu items outlive the service worker, so they are only (re)created when the extension is installed or updated
+	browser
+		.runtime()
+		.on_installed()?
+		.add_listener({
+			let menus = menus.clone();
+			move |_details| {
+				// the browser puts the selected text in place of `%s`
+				let title = i18n.get_message("summarizeSelection", &[]).ok().flatten().unwrap_or_else(|| "Summarize \"%s\"".to_owned());
+				let selection_item = ContextMenuConfig::build(SUMMARIZE_SELECTION, title).menu_contexts(&[MenuContext::Selection]).build();
+				let title = i18n.get_message("summarizeWindow", &[]).ok().flatten().unwrap_or_else(|| "Summarize all tabs in this window".to_owned());
+				let window_item = ContextMenuConfig::build(SUMMARIZE_WINDOW, title).menu_contexts(&[MenuContext::Page, MenuContext::Action]).build();
+				let menus = menus.clone();
+				spawn_local(async move {
+					if let Err(e) = async {
+						menus.remove_all().await?;
+						menus.create(selection_item).await?;
+						menus.create(window_item).await
+					}
+					.await
+					{
+						error!("could not create the context menu: {}", e);
+					}
+				});
+			}
+		})?
+		.detach();
+
+	let browser = browser.clone();
+	menus
+		.on_clicked()?
+		.add_listener(move |data: OnClickData| {
+			if data.menu_item_id == SUMMARIZE_WINDOW {
+				let browser = browser.clone();
+				spawn_local(async move { queue::summarize_window(&browser).await });
+				return;
+			}
+			let Some(selection) = data.selection_text.filter(|_| data.menu_item_id == SUMMARIZE_SELECTION) else {
+				return;
+			};
+			let browser = browser.clone();
+			spawn_local(async move {
+				// the menu was clicked on the active tab
+				let tab = active_tab(&browser.tabs()).await.ok();
+				let tab_id = tab.as_ref().map(|(tab_id, _)| *tab_id);
+				let config = load_config(&browser).await;
+				if let Some(Err(e)) = tab.as_ref().map(|(_, tab)| check_site(&config, tab)) {
+					show_tooltip(&browser, tab_id, Err(e)).await;
+					return;
+				}
+				let progress = Progress::start(&browser, tab_id).await;
+				let result = summarize_selection(&browser, &config, tab.as_ref().map(|(_, tab)| tab), selection).await;
+				progress.finish(result.is_ok());
+				show_tooltip(&browser, tab_id, result).await;
+			});
+		})?
+		.detach();
+	Ok(())
+}
+
+async fn summarize_selection(browser: &Browser, config: &Config, tab: Option<&TabInfo>, selection: String) -> Result<String, AppError> {
+	let (summary, _) = summarize_or_offline(config, selection, |_| Ok(())).await?;
+	let (url, title) = tab.map(|tab| (tab.url.clone().unwrap_or_default(), tab.title.clone().unwrap_or_default())).unwrap_or_default();
+	if let Err(e) = save_to_history(browser, HistoryEntry::new(url, title, summary.clone())).await {
+		warn!("could not save the summary to the history: {}", e);
+	}
+	Ok(summary)
+}
+
+// the summary, or what went wrong, in the action's tooltip; the browser resets it when the tab navigates
+async fn show_tooltip(browser: &Browser, tab_id: Option<u32>, result: Result<String, AppError>) {
+	let tooltip = match result {
+		Ok(summary) => plain_text(&summary),
+		Err(e) => {
+			error!("could not summarize the selection: {}", e);
+			telemetry::report(browser, &e).await;
+			e.to_string()
+		},
+	};
+	if let Err(e) = browser.action().set_title(&truncate(tooltip, TOOLTIP_CHARS), tab_id).await {
+		warn!("could not show the summary on the action: {}", e);
+	}
+}
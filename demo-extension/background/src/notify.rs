@@ -0,0 +1,75 @@
+use common::{Config, HistoryEntry, markdown::plain_text};
+use dioxus::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+use webext_api::{Browser, NotificationOptions, Permission, error::ExtensionError};
+
+use crate::truncate;
+
+// notification ids carry the summarized tab, so a click still finds it after the service worker was restarted
+const ID_PREFIX: &str = "summary-";
+
+// about what fits into a notification on every platform
+const MESSAGE_CHARS: usize = 180;
+
+const ICON: &str = "assets/android-chrome-192x192.png";
+
+/// Opens the summary of a clicked notification in the side panel, or the popup where there is none. `notifications` is an optional
+/// permission, granted on the onboarding page, so the listener is also added once it is granted.
+pub(crate) fn register(browser: &Browser) -> Result<(), ExtensionError> {
+	if browser.supports_notifications() {
+		return listen(browser);
+	}
+	let permissions = browser.permissions();
+	let browser = browser.clone();
+	permissions
+		.on_added()?
+		.add_listener(move |added| {
+			if added.permissions.contains(&Permission::Notifications)
+				&& let Err(e) = listen(&browser)
+			{
+				error!("could not listen to notification clicks: {}", e);
+			}
+		})?
+		.detach();
+	Ok(())
+}
+
+fn listen(browser: &Browser) -> Result<(), ExtensionError> {
+	let notifications = browser.notifications();
+	let browser = browser.clone();
+	notifications
+		.on_clicked()?
+		.add_listener(move |id| {
+			let Some(tab_id) = id.strip_prefix(ID_PREFIX).and_then(|tab_id| tab_id.parse().ok()) else {
+				return;
+			};
+			let browser = browser.clone();
+			spawn_local(async move {
+				// the click is a user gesture, which opening the side panel needs
+				let opened = if browser.supports_side_panel() { browser.side_panel().open(Some(tab_id)).await } else { browser.action().open_popup(None).await };
+				if let Err(e) = opened {
+					warn!("could not show the summary: {}", e);
+				}
+				let _ = browser.notifications().clear(&id).await;
+			});
+		})?
+		.detach();
+	Ok(())
+}
+
+/// Tells the user that the summary of `tab_id` is ready after the popup that asked for it was closed, if they want notifications
+/// and granted the permission for them.
+pub(crate) async fn summary_ready(browser: &Browser, config: &Config, tab_id: u32, entry: &HistoryEntry) {
+	if !config.enable_notifications || !browser.supports_notifications() {
+		return;
+	}
+	let title = if entry.title.is_empty() { entry.url.clone() } else { entry.title.clone() };
+	let result = async {
+		let mut options = NotificationOptions::basic(browser.runtime().get_url(ICON)?, title, truncate(plain_text(&entry.summary), MESSAGE_CHARS));
+		options.context_message = browser.i18n().get_message("summaryReady", &[]).ok().flatten();
+		browser.notifications().create(Some(&format!("{ID_PREFIX}{tab_id}")), &options).await
+	};
+	if let Err(e) = result.await {
+		warn!("could not show the summary notification: {}", e);
+	}
+}
@@ -0,0 +1,98 @@
+use std::collections::HashSet;
+
+use common::SummaryStyle;
+
+// TextRank's damping factor, as in PageRank
+const DAMPING: f64 = 0.85;
+const MAX_ITERATIONS: usize = 100;
+const CONVERGED: f64 = 1e-6;
+
+// the graph is quadratic in sentences, so very long pages are ranked on their beginning only
+const MAX_SENTENCES: usize = 400;
+
+// sentences shorter than this are mostly headings, captions or navigation
+const MIN_SENTENCE_WORDS: usize = 4;
+
+const STOP_WORDS: &[&str] = &[
+	"a", "about", "after", "all", "also", "an", "and", "any", "are", "as", "at", "be", "been", "but", "by", "can", "could", "did", "do", "does", "for", "from",
+	"had", "has", "have", "he", "her", "his", "how", "if", "in", "into", "is", "it", "its", "more", "most", "not", "of", "on", "one", "or", "other", "our",
+	"out", "she", "so", "some", "such", "than", "that", "the", "their", "them", "then", "there", "these", "they", "this", "those", "to", "up", "was", "we",
+	"were", "what", "when", "which", "who", "will", "with", "would", "you", "your", "der", "die", "das", "und", "ist", "ein", "eine", "den", "dem", "des", "mit",
+	"von", "auf", "für", "nicht", "sich", "auch", "als", "wie", "bei",
+];
+
+/// An extractive summary of `text`, computed in the extension when the server can't be reached: the sentences are ranked with
+/// TextRank, PageRank over a graph linking sentences by the words they share, and the best ones are kept in page order, as bullets
+/// or a paragraph depending on `style`. Empty if `text` has no sentence worth keeping.
+pub fn summarize(text: &str, style: SummaryStyle) -> String {
+	let sentences: Vec<&str> = split_sentences(text).filter(|sentence| sentence.split_whitespace().count() >= MIN_SENTENCE_WORDS).take(MAX_SENTENCES).collect();
+	let keep = match style {
+		SummaryStyle::Bullets => 5,
+		SummaryStyle::Paragraph => 3,
+	};
+	let mut picked: Vec<usize> = {
+		let scores = rank(&sentences.iter().map(|sentence| words(sentence)).collect::<Vec<_>>());
+		let mut by_score: Vec<usize> = (0..sentences.len()).collect();
+		by_score.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+		by_score.truncate(keep);
+		by_score
+	};
+	picked.sort_unstable();
+	let picked = picked.into_iter().map(|i| sentences[i]);
+	match style {
+		SummaryStyle::Bullets => picked.map(|sentence| format!("- {sentence}")).collect::<Vec<_>>().join("\n"),
+		SummaryStyle::Paragraph => picked.collect::<Vec<_>>().join(" "),
+	}
+}
+
+// sentences end at `.`, `!` or `?` followed by whitespace, and at line breaks, which separate headings and list items
+fn split_sentences(text: &str) -> impl Iterator<Item = &str> {
+	text.lines().flat_map(|line| {
+		let mut sentences = Vec::new();
+		let mut start = 0;
+		let mut chars = line.char_indices().peekable();
+		while let Some((i, c)) = chars.next() {
+			if matches!(c, '.' | '!' | '?') && chars.peek().is_none_or(|(_, next)| next.is_whitespace()) {
+				sentences.push(&line[start..i + c.len_utf8()]);
+				start = i + c.len_utf8();
+			}
+		}
+		sentences.push(&line[start..]);
+		sentences.into_iter().map(str::trim).filter(|sentence| !sentence.is_empty())
+	})
+}
+
+fn words(sentence: &str) -> HashSet<String> {
+	sentence
+		.split(|c: char| !c.is_alphanumeric())
+		.map(str::to_lowercase)
+		.filter(|word| word.chars().count() > 2 && !STOP_WORDS.contains(&word.as_str()))
+		.collect()
+}
+
+// the TextRank score of every sentence, its edges weighted by the words two sentences share relative to their lengths
+fn rank(sentences: &[HashSet<String>]) -> Vec<f64> {
+	let n = sentences.len();
+	let similarity = |a: &HashSet<String>, b: &HashSet<String>| {
+		let norm = (a.len() as f64).ln() + (b.len() as f64).ln();
+		if norm <= 0.0 { 0.0 } else { a.intersection(b).count() as f64 / norm }
+	};
+	let weights: Vec<Vec<f64>> = (0..n).map(|i| (0..n).map(|j| if i == j { 0.0 } else { similarity(&sentences[i], &sentences[j]) }).collect()).collect();
+	let out_weights: Vec<f64> = weights.iter().map(|row| row.iter().sum()).collect();
+
+	let mut scores = vec![1.0; n];
+	for _ in 0..MAX_ITERATIONS {
+		let next: Vec<f64> = (0..n)
+			.map(|i| {
+				let incoming: f64 = (0..n).filter(|&j| out_weights[j] > 0.0).map(|j| weights[j][i] / out_weights[j] * scores[j]).sum();
+				(1.0 - DAMPING) + DAMPING * incoming
+			})
+			.collect();
+		let change: f64 = next.iter().zip(&scores).map(|(next, score)| (next - score).abs()).sum();
+		scores = next;
+		if change < CONVERGED {
+			break;
+		}
+	}
+	scores
+}
@@ -0,0 +1,42 @@
+use common::{AppError, Config, ServerPdfTextResponse};
+use dioxus::prelude::*;
+use reqwest::header::CONTENT_TYPE;
+
+use crate::{server_error, server_request};
+
+const PDF_MIME: &str = "application/pdf";
+
+/// Whether `url` names a PDF by its extension, as most links to PDFs do.
+pub(crate) fn is_pdf_url(url: &str) -> bool {
+	url.split(['?', '#']).next().is_some_and(|path| path.to_ascii_lowercase().ends_with(".pdf"))
+}
+
+/// The text of the PDF at `url`, or `None` if it isn't one or can't be downloaded.
+///
+/// The browser shows PDFs in its own viewer, where content scripts don't run, so the background downloads the document itself,
+/// which the manifest's `<all_urls>` host permission allows, and has the server extract its text.
+pub(crate) async fn document_text(config: &Config, url: &str) -> Result<Option<String>, AppError> {
+	let Some(bytes) = download(url).await else {
+		return Ok(None);
+	};
+	info!("extracting the text of a {} byte PDF", bytes.len());
+	let response = server_request(config, "/api/extract/pdf").header(CONTENT_TYPE, PDF_MIME).body(bytes).send().await.map_err(|_| AppError::Network)?;
+	if !response.status().is_success() {
+		return Err(server_error(response).await);
+	}
+	let ServerPdfTextResponse { text } = response.json().await.map_err(|e| AppError::ServerError(e.to_string()))?;
+	if text.trim().is_empty() {
+		// scanned documents without a text layer
+		return Err(AppError::NoContent);
+	}
+	Ok(Some(text))
+}
+
+async fn download(url: &str) -> Option<Vec<u8>> {
+	let response = reqwest::get(url).await.ok().filter(|response| response.status().is_success())?;
+	let content_type = response.headers().get(CONTENT_TYPE).and_then(|value| value.to_str().ok()).unwrap_or_default();
+	if !content_type.starts_with(PDF_MIME) && !is_pdf_url(url) {
+		return None;
+	}
+	response.bytes().await.ok().map(Vec::from)
+}
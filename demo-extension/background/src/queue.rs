@@ -0,0 +1,151 @@
+use common::{AppError, Config, Envelope, ExtMessage, HistoryEntry, QUEUE_KEY, QueueStatus, QueuedTab, Source};
+use dioxus::prelude::*;
+use std::cell::{Cell, RefCell};
+use wasm_bindgen_futures::spawn_local;
+use webext_api::{Browser, TabQuery};
+
+use crate::{badge::Progress, check_site, load_config, save_to_history, summarize_or_offline, tab_text, telemetry};
+
+// how many tabs are summarized at once; any more would only wait on the same server
+const MAX_CONCURRENT: usize = 3;
+
+thread_local! {
+	// the queue as this service worker knows it, mirrored to `storage.session` after every change
+	static QUEUE: RefCell<Vec<QueuedTab>> = const { RefCell::new(Vec::new()) };
+	// workers currently taking tabs off the queue
+	static WORKERS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Picks the queue back up after the service worker was stopped: tabs that were being summarized at the time start over.
+pub(crate) fn register(browser: &Browser) {
+	let browser = browser.clone();
+	spawn_local(async move {
+		let stored = match browser.storage().session().get::<Vec<QueuedTab>>(QUEUE_KEY).await {
+			Ok(stored) => stored.unwrap_or_default(),
+			Err(e) => {
+				warn!("could not read the summary queue: {}", e);
+				return;
+			},
+		};
+		if stored.iter().all(|tab| tab.status.is_finished()) {
+			QUEUE.set(stored);
+			return;
+		}
+		QUEUE.set(
+			stored.into_iter().map(|tab| if tab.status == QueueStatus::Summarizing { QueuedTab { status: QueueStatus::Waiting, ..tab } } else { tab }).collect(),
+		);
+		info!("resuming the summary queue");
+		publish(&browser).await;
+		start_workers(&browser);
+	});
+}
+
+/// Queues every tab of the current window that has a page to summarize and the site rules allow. Tabs already waiting or being
+/// summarized keep their place; once everything queued before is finished, the old queue is replaced.
+pub(crate) async fn summarize_window(browser: &Browser) {
+	let tabs = match browser.tabs().query(&TabQuery { current_window: Some(true), ..Default::default() }).await {
+		Ok(tabs) => tabs,
+		Err(e) => {
+			error!("could not list the window's tabs: {}", e);
+			return;
+		},
+	};
+	let config = load_config(browser).await;
+	let queued = tabs
+		.into_iter()
+		.filter_map(|tab| {
+			let tab_id = tab.id?;
+			// browser pages and the extension's own can't be read by the content script
+			let url = tab.url.clone().filter(|url| url.starts_with("http://") || url.starts_with("https://"))?;
+			check_site(&config, &tab).ok()?;
+			Some(QueuedTab { tab_id, url, title: tab.title.unwrap_or_default(), status: QueueStatus::Waiting })
+		})
+		.collect::<Vec<_>>();
+	info!("queueing {} tabs for summaries", queued.len());
+	QUEUE.with_borrow_mut(|queue| {
+		if queue.iter().all(|tab| tab.status.is_finished()) {
+			queue.clear();
+		}
+		for tab in queued {
+			match queue.iter_mut().find(|queued| queued.tab_id == tab.tab_id) {
+				Some(queued) if queued.status.is_finished() => *queued = tab,
+				Some(_) => {},
+				None => queue.push(tab),
+			}
+		}
+	});
+	publish(browser).await;
+	start_workers(browser);
+}
+
+/// Forgets the queue, unless some of it is still being worked on.
+pub(crate) async fn clear(browser: &Browser) {
+	if QUEUE.with_borrow(|queue| queue.iter().all(|tab| tab.status.is_finished())) {
+		QUEUE.take();
+		publish(browser).await;
+	}
+}
+
+fn start_workers(browser: &Browser) {
+	let waiting = QUEUE.with_borrow(|queue| queue.iter().filter(|tab| tab.status == QueueStatus::Waiting).count());
+	for _ in WORKERS.get()..MAX_CONCURRENT.min(WORKERS.get() + waiting) {
+		WORKERS.set(WORKERS.get() + 1);
+		let browser = browser.clone();
+		spawn_local(async move {
+			work(&browser).await;
+			WORKERS.set(WORKERS.get() - 1);
+		});
+	}
+}
+
+// summarizes waiting tabs one after the other until none are left
+async fn work(browser: &Browser) {
+	while let Some(tab) = QUEUE.with_borrow_mut(|queue| {
+		let tab = queue.iter_mut().find(|tab| tab.status == QueueStatus::Waiting)?;
+		tab.status = QueueStatus::Summarizing;
+		Some(tab.clone())
+	}) {
+		publish(browser).await;
+		// read for every tab, so settings changed during a long run apply to the rest of it
+		let config = load_config(browser).await;
+		let status = match summarize_tab(browser, &config, &tab).await {
+			Ok(()) => QueueStatus::Done,
+			Err(e) => {
+				warn!("could not summarize tab {}: {}", tab.tab_id, e);
+				telemetry::report(browser, &e).await;
+				QueueStatus::Failed(e)
+			},
+		};
+		QUEUE.with_borrow_mut(|queue| {
+			if let Some(queued) = queue.iter_mut().find(|queued| queued.tab_id == tab.tab_id) {
+				queued.status = status;
+			}
+		});
+		publish(browser).await;
+	}
+}
+
+async fn summarize_tab(browser: &Browser, config: &Config, tab: &QueuedTab) -> Result<(), AppError> {
+	let progress = Progress::start(browser, Some(tab.tab_id)).await;
+	let result = async {
+		let text = tab_text(&browser.tabs(), config, tab.tab_id, &tab.url).await?;
+		let (summary, _) = summarize_or_offline(config, text, |_| Ok(())).await?;
+		save_to_history(browser, HistoryEntry::new(tab.url.clone(), tab.title.clone(), summary)).await.map_err(|e| AppError::ExtensionError(e.to_string()))
+	}
+	.await;
+	progress.finish(result.is_ok());
+	result
+}
+
+// stores the queue for pages opened later and for a restarted service worker, and broadcasts it to the pages already open
+async fn publish(browser: &Browser) {
+	let queue = QUEUE.with_borrow(Clone::clone);
+	if let Err(e) = browser.storage().session().set(QUEUE_KEY, &queue).await {
+		warn!("could not store the summary queue: {}", e);
+	}
+	let message = Envelope::request(Source::Background, ExtMessage::QueueProgress(queue));
+	// fails when no popup or side panel is open to receive it
+	if let Err(e) = browser.runtime().send_message::<_, Option<Envelope>>(&message).await {
+		debug!("nobody received the queue's progress: {}", e);
+	}
+}
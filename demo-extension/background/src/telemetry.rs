@@ -0,0 +1,107 @@
+use chrono::Utc;
+use common::{AppError, ERROR_REPORTS_KEY, ErrorReport, ServerErrorReports};
+use dioxus::prelude::*;
+use serde::Deserialize;
+use wasm_bindgen_futures::spawn_local;
+use webext_api::{AlarmInfo, Browser, error::ExtensionError};
+
+use crate::{load_config, server_error, server_request};
+
+// reports are sent once this many are queued, or by the periodic alarm
+const BATCH_SIZE: usize = 10;
+
+// the oldest reports are dropped beyond this while the server can't be reached
+const MAX_QUEUED: usize = 100;
+
+const FLUSH_ALARM: &str = "flush-error-reports";
+const FLUSH_PERIOD_MINUTES: f64 = 15.0;
+
+#[derive(Deserialize)]
+struct Manifest {
+	version: String,
+}
+
+/// Sends the queued reports every [`FLUSH_PERIOD_MINUTES`], so a handful of failures doesn't wait for a full batch forever.
+pub(crate) fn register(browser: &Browser) -> Result<(), ExtensionError> {
+	let alarms = browser.alarms();
+	let browser = browser.clone();
+	alarms
+		.on_alarm()?
+		.add_listener(move |alarm| {
+			if alarm.name == FLUSH_ALARM {
+				let browser = browser.clone();
+				spawn_local(async move { flush(&browser).await });
+			}
+		})?
+		.detach();
+	spawn_local(async move {
+		// creating it again replaces it, which just restarts the period
+		let schedule = AlarmInfo { delay_in_minutes: None, period_in_minutes: Some(FLUSH_PERIOD_MINUTES) };
+		if let Err(e) = alarms.create(FLUSH_ALARM, schedule).await {
+			warn!("could not schedule sending error reports: {}", e);
+		}
+	});
+	Ok(())
+}
+
+/// Queues `error` for the server if the user opted into reporting, sending the queue once it holds a full batch. Errors that
+/// only reflect the user's own choices, like a site they turned summaries off for, aren't worth reporting.
+pub(crate) async fn report(browser: &Browser, error: &AppError) {
+	if matches!(error, AppError::DisabledOnSite | AppError::NoSelection) || !load_config(browser).await.report_errors {
+		return;
+	}
+	let report = ErrorReport {
+		error: error.clone(),
+		extension_version: browser.runtime().get_manifest::<Manifest>().map(|manifest| manifest.version).unwrap_or_default(),
+		browser: format!("{:?}", browser.browser_type()),
+		occurred_at: Utc::now(),
+	};
+	let local = browser.storage().local();
+	let queued = async {
+		let mut reports = local.get::<Vec<ErrorReport>>(ERROR_REPORTS_KEY).await?.unwrap_or_default();
+		reports.push(report);
+		let overflow = reports.len().saturating_sub(MAX_QUEUED);
+		reports.drain(..overflow);
+		local.set(ERROR_REPORTS_KEY, &reports).await?;
+		Ok::<_, ExtensionError>(reports.len())
+	};
+	match queued.await {
+		Ok(queued) if queued >= BATCH_SIZE => flush(browser).await,
+		Ok(_) => {},
+		Err(e) => warn!("could not queue an error report: {}", e),
+	}
+}
+
+// posts the queued reports, keeping them for the next attempt if that fails; a user who opted out in the meantime gets them discarded
+async fn flush(browser: &Browser) {
+	let local = browser.storage().local();
+	let reports = match local.get::<Vec<ErrorReport>>(ERROR_REPORTS_KEY).await {
+		Ok(Some(reports)) if !reports.is_empty() => reports,
+		Ok(_) => return,
+		Err(e) => {
+			warn!("could not read the queued error reports: {}", e);
+			return;
+		},
+	};
+	let config = load_config(browser).await;
+	let handled = reports.len();
+	if config.report_errors {
+		match server_request(&config, "/api/telemetry/errors").json(&ServerErrorReports { reports }).send().await {
+			Ok(response) if response.status().is_success() => info!("sent {} error reports", handled),
+			Ok(response) => {
+				warn!("the server rejected the error reports: {}", server_error(response).await);
+				return;
+			},
+			Err(_) => return,
+		}
+	}
+	let result = async {
+		// reports queued while the batch was on its way stay for the next one
+		let mut reports = local.get::<Vec<ErrorReport>>(ERROR_REPORTS_KEY).await?.unwrap_or_default();
+		reports.drain(..handled.min(reports.len()));
+		local.set(ERROR_REPORTS_KEY, &reports).await
+	};
+	if let Err(e) = result.await {
+		warn!("could not update the queued error reports: {}", e);
+	}
+}
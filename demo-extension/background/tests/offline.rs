@@ -0,0 +1,28 @@
+use background::offline::summarize;
+use common::SummaryStyle;
+
+const ARTICLE: &str = "Rust is a systems programming language focused on safety and performance.
+The Rust compiler checks memory safety at compile time without a garbage collector.
+Many developers like the weather in spring.
+WebAssembly lets Rust code run in the browser at near native performance.
+Browser extensions can ship Rust compiled to WebAssembly for heavy computation.
+Subscribe to our newsletter!";
+
+#[test]
+fn keeps_the_central_sentences_in_page_order() {
+	let summary = summarize(ARTICLE, SummaryStyle::Paragraph);
+	assert!(!summary.contains("weather") && !summary.contains("newsletter"), "{summary}");
+	let positions: Vec<usize> = ["systems", "WebAssembly lets", "extensions"].iter().map(|part| summary.find(part).expect(&summary)).collect();
+	assert!(positions.is_sorted(), "{summary}");
+}
+
+#[test]
+fn bullets_get_one_sentence_per_line() {
+	let summary = summarize(ARTICLE, SummaryStyle::Bullets);
+	assert!(summary.lines().count() <= 5 && summary.lines().all(|line| line.starts_with("- ")), "{summary}");
+}
+
+#[test]
+fn text_without_sentences_gives_nothing() {
+	assert_eq!(summarize("Home\nAbout\n", SummaryStyle::Bullets), "");
+}
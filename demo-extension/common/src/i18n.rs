@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use dioxus::prelude::*;
+use serde::Deserialize;
+use wasm_bindgen::{JsCast, prelude::*};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Response, window};
+use webext_api::{Browser, api::I18n};
+
+use crate::{AppError, Language};
+
+/// A `_locales/<locale>/messages.json` file.
+type Catalog = HashMap<String, Message>;
+
+#[derive(Deserialize)]
+struct Message {
+	message: String,
+	#[serde(default)]
+	placeholders: HashMap<String, Placeholder>,
+}
+
+#[derive(Deserialize)]
+struct Placeholder {
+	content: String,
+}
+
+impl Message {
+	// the same substitution `i18n.getMessage` does: `$NAME$` becomes the placeholder's content, then `$1`…`$9` the
+	// substitutions and `$$` a single `$`
+	fn format(&self, substitutions: &[&str]) -> String {
+		let mut named = String::with_capacity(self.message.len());
+		let mut rest = self.message.as_str();
+		while let Some(start) = rest.find('$') {
+			named.push_str(&rest[..start]);
+			let after = &rest[start + 1..];
+			let placeholder = after
+				.find('$')
+				.map(|end| &after[..end])
+				.filter(|name| !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '@'))
+				.and_then(|name| self.placeholders.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, placeholder)| (name.len(), placeholder)));
+			if let Some((len, placeholder)) = placeholder {
+				named.push_str(&placeholder.content);
+				rest = &after[len + 1..];
+			} else {
+				named.push('$');
+				rest = after;
+			}
+		}
+		named.push_str(rest);
+
+		let mut formatted = String::with_capacity(named.len());
+		let mut chars = named.chars().peekable();
+		while let Some(c) = chars.next() {
+			if c != '$' {
+				formatted.push(c);
+				continue;
+			}
+			match chars.peek().copied() {
+				Some('$') => {
+					chars.next();
+					formatted.push('$');
+				},
+				Some(digit @ '1'..='9') => {
+					chars.next();
+					formatted.push_str(substitutions.get(digit as usize - '1' as usize).copied().unwrap_or_default());
+				},
+				_ => formatted.push('$'),
+			}
+		}
+		formatted
+	}
+}
+
+async fn load_catalog(url: &str) -> Result<Catalog, JsValue> {
+	let window = window().ok_or("no window")?;
+	let response: Response = JsFuture::from(window.fetch_with_str(url)).await?.dyn_into()?;
+	let text = JsFuture::from(response.text()?).await?.as_string().unwrap_or_default();
+	serde_json::from_str(&text).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// The UI strings of the extension's pages, in the [`Language`] the user picked.
+///
+/// `i18n.getMessage` always answers in the browser's UI language, so for an explicit choice the messages are read from the
+/// packaged `_locales/<locale>/messages.json` instead. Reading a message subscribes the component to language changes.
+#[derive(Clone, Copy)]
+pub struct Locale {
+	browser: CopyValue<I18n>,
+	// `None` when following the browser
+	overrides: Resource<Option<Catalog>>,
+}
+
+impl Locale {
+	/// The message called `name`, or `name` itself if no locale defines it.
+	pub fn t(&self, name: &str) -> String {
+		self.t_with(name, &[])
+	}
+
+	/// The message called `name` with `$1`…`$9` replaced by `substitutions`.
+	pub fn t_with(&self, name: &str, substitutions: &[&str]) -> String {
+		if let Some(Some(catalog)) = &*self.overrides.read()
+			&& let Some(message) = catalog.get(name)
+		{
+			return message.format(substitutions);
+		}
+		self.browser.read().get_message(name, substitutions).ok().flatten().unwrap_or_else(|| name.to_owned())
+	}
+
+	/// `error`'s message, as [`AppError`]'s `Display` words it in English.
+	pub fn error(&self, error: &AppError) -> String {
+		match error {
+			AppError::MissingConfiguration => self.t("errorMissingConfiguration"),
+			AppError::Network => self.t("errorNetwork"),
+			AppError::ServerError(details) => self.t_with("errorServer", &[details.as_str()]),
+			AppError::NoContent => self.t("errorNoContent"),
+			AppError::ContentScriptError => self.t("errorContentScript"),
+			AppError::ExtensionError(details) => self.t_with("errorExtension", &[details.as_str()]),
+			AppError::RateLimited { .. } => self.t("errorRateLimited"),
+			AppError::IncompatibleVersion { .. } => self.t("errorIncompatibleVersion"),
+			AppError::DisabledOnSite => self.t("errorDisabledOnSite"),
+			AppError::NoSelection => self.t("errorNoSelection"),
+		}
+	}
+}
+
+/// Provides the [`Locale`] for `language` to this component and its children, which get it through [`use_locale`].
+pub fn use_locale_provider(browser: &Browser, language: Memo<Language>) -> Locale {
+	let runtime = use_hook(|| browser.runtime());
+	let overrides = use_resource(move || {
+		let runtime = runtime.clone();
+		let language = language();
+		async move {
+			let locale = language.locale()?;
+			let url = runtime.get_url(&format!("_locales/{locale}/messages.json")).ok()?;
+			// falls back to the browser's choice if the file can't be read
+			load_catalog(&url).await.inspect_err(|e| error!("could not load the `{}` messages: {:?}", locale, e)).ok()
+		}
+	});
+	let browser = use_hook(|| CopyValue::new(browser.i18n()));
+	use_context_provider(|| Locale { browser, overrides })
+}
+
+/// The [`Locale`] an ancestor provided through [`use_locale_provider`].
+pub fn use_locale() -> Locale {
+	use_context()
+}
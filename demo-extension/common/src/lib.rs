@@ -1,8 +1,16 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use uuid::Uuid;
+use webext_api::match_pattern::MatchPattern;
 
-#[cfg(feature = "server")]
-use dioxus::prelude::*;
+#[cfg(feature = "web")]
+pub mod i18n;
+pub mod markdown;
+#[cfg(feature = "web")]
+pub mod queue;
+#[cfg(feature = "web")]
+pub mod theme;
 
 #[derive(Serialize, Deserialize, Debug, Error, Clone, PartialEq)]
 pub enum AppError {
@@ -18,44 +26,435 @@ pub enum AppError {
 	ContentScriptError,
 	#[error("An internal extension error occurred: {0}")]
 	ExtensionError(String),
+	#[error("Too many summaries were requested in a short time. Please wait a moment before trying again.")]
+	RateLimited { retry_after_secs: u64 },
+	#[error("The extension was updated while this page was open. Please reload the page and try again.")]
+	IncompatibleVersion { expected: u32, found: u32 },
+	#[error("Summaries are turned off for this site.")]
+	DisabledOnSite,
+	#[error("Nothing is selected on this page. Select some text and try again.")]
+	NoSelection,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+/// The name of the port the popup opens to the background to receive a summary as it streams in.
+pub const SUMMARIZE_PORT: &str = "summarize";
+
+/// The `storage.sync` key the options page keeps the [`Config`] under.
+pub const CONFIG_KEY: &str = "config";
+
+/// The `storage.local` key holding the summary history, newest first.
+pub const HISTORY_KEY: &str = "history";
+
+/// The `storage.local` key holding the [`ErrorReport`]s that haven't been sent yet, oldest first.
+pub const ERROR_REPORTS_KEY: &str = "error_reports";
+
+/// The `storage.session` key holding the tabs of the last "Summarize all tabs in this window", as [`QueuedTab`]s in the order
+/// they were queued. Session storage lasts until the browser closes, so the queue outlives the background's service worker.
+pub const QUEUE_KEY: &str = "summary_queue";
+
+/// How many summaries the history keeps; the oldest are dropped beyond this.
+pub const MAX_HISTORY_ENTRIES: usize = 50;
+
+/// A summary the background generated, as stored under [`HISTORY_KEY`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+	pub id: Uuid,
+	pub url: String,
+	pub title: String,
+	pub created_at: DateTime<Utc>,
+	pub summary: String,
+}
+
+impl HistoryEntry {
+	pub fn new(url: String, title: String, summary: String) -> Self {
+		Self { id: Uuid::new_v4(), url, title, created_at: Utc::now(), summary }
+	}
+}
+
+/// Puts `entry` at the front of `history` and drops whatever no longer fits in [`MAX_HISTORY_ENTRIES`].
+pub fn push_history(history: &mut Vec<HistoryEntry>, entry: HistoryEntry) {
+	history.insert(0, entry);
+	history.truncate(MAX_HISTORY_ENTRIES);
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SummaryStyle {
+	#[default]
+	Bullets,
+	Paragraph,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Theme {
+	Light,
+	Dark,
+	/// Follows the operating system's light or dark preference.
+	#[default]
+	System,
+}
+
+/// The language of the popup and options page.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Language {
+	/// The browser's UI language, or English if the extension has no messages for it.
+	#[default]
+	Auto,
+	En,
+	De,
+}
+
+impl Language {
+	/// The `_locales` directory holding this language's messages, or `None` to leave the choice to the browser.
+	pub fn locale(self) -> Option<&'static str> {
+		match self {
+			Self::Auto => None,
+			Self::En => Some("en"),
+			Self::De => Some("de"),
+		}
+	}
+}
+
+/// How [`SiteRules::patterns`] are read.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SiteMode {
+	/// Summarize every site except the listed ones.
+	#[default]
+	Deny,
+	/// Summarize the listed sites only.
+	Allow,
+}
+
+/// The sites the extension summarizes, as a list of match patterns.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct SiteRules {
+	pub mode: SiteMode,
+	pub patterns: Vec<MatchPattern>,
+}
+
+impl SiteRules {
+	/// Whether the page at `url` may be summarized.
+	pub fn allows(&self, url: &str) -> bool {
+		let listed = self.patterns.iter().any(|pattern| pattern.matches(url));
+		match self.mode {
+			SiteMode::Deny => !listed,
+			SiteMode::Allow => listed,
+		}
+	}
+
+	/// Turns summaries on or off for `url`'s whole site. Listing a site adds the pattern for its host, unlisting it drops every
+	/// pattern matching `url`, broader ones like `*://*.example.com/*` included. Returns `false` for URLs without a host, which
+	/// can't be listed.
+	pub fn set_allowed(&mut self, url: &str, allowed: bool) -> bool {
+		if self.allows(url) == allowed {
+			return true;
+		}
+		if allowed == (self.mode == SiteMode::Allow) {
+			let Some(site) = MatchPattern::for_site(url) else {
+				return false;
+			};
+			self.patterns.push(site);
+		} else {
+			self.patterns.retain(|pattern| !pattern.matches(url));
+		}
+		true
+	}
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
 pub struct Config {
 	pub server_url: String,
 	pub auth_token: String,
+	pub summary_style: SummaryStyle,
+	pub enable_notifications: bool,
+	pub theme: Theme,
+	pub language: Language,
+	pub site_rules: SiteRules,
+	/// Whether failures are reported to the server, see [`ErrorReport`].
+	pub report_errors: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl Default for Config {
+	fn default() -> Self {
+		Self {
+			server_url: String::new(),
+			auth_token: String::new(),
+			summary_style: SummaryStyle::default(),
+			enable_notifications: true,
+			theme: Theme::default(),
+			language: Language::default(),
+			site_rules: SiteRules::default(),
+			report_errors: false,
+		}
+	}
+}
+
+/// Why a settings file could not be imported with [`Config::merged_with_json`].
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum ConfigImportError {
+	#[error("The file is not valid JSON: {0}")]
+	InvalidJson(String),
+	#[error("The file does not contain any settings.")]
+	NoSettings,
+	#[error("The file contains an invalid setting: {0}")]
+	InvalidSetting(String),
+}
+
+impl Config {
+	/// The server summaries are requested from: the URL set in the options, or `default`, the one the extension was built with,
+	/// while that is empty. A trailing `/` is dropped, so request paths can be appended as they are.
+	pub fn server_url_or<'a>(&'a self, default: &'a str) -> &'a str {
+		let url = self.server_url.trim().trim_end_matches('/');
+		if url.is_empty() { default } else { url }
+	}
+
+	/// The settings as pretty-printed JSON for a settings file. The auth token is left out, it has no business in a downloads folder.
+	pub fn to_export_json(&self) -> String {
+		let mut settings = serde_json::to_value(self).expect("a config always serializes");
+		if let Some(settings) = settings.as_object_mut() {
+			settings.remove("auth_token");
+		}
+		serde_json::to_string_pretty(&settings).expect("a config always serializes")
+	}
+
+	/// `self` with the settings of a settings file applied on top. Settings the file leaves out keep their current value and
+	/// unknown keys are ignored, but a file with no known setting, or with one of the wrong type, is rejected as a whole.
+	pub fn merged_with_json(&self, json: &str) -> Result<Self, ConfigImportError> {
+		let imported = serde_json::from_str::<serde_json::Value>(json).map_err(|e| ConfigImportError::InvalidJson(e.to_string()))?;
+		let serde_json::Value::Object(imported) = imported else {
+			return Err(ConfigImportError::NoSettings);
+		};
+		let mut merged = serde_json::to_value(self).expect("a config always serializes");
+		let settings = merged.as_object_mut().expect("a config serializes to an object");
+		let mut applied = 0;
+		for (key, value) in imported {
+			if let Some(setting) = settings.get_mut(&key) {
+				*setting = value;
+				applied += 1;
+			}
+		}
+		if applied == 0 {
+			return Err(ConfigImportError::NoSettings);
+		}
+		serde_json::from_value(merged).map_err(|e| ConfigImportError::InvalidSetting(e.to_string()))
+	}
+}
+
+/// How far the summary of a tab queued by "Summarize all tabs in this window" got.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum QueueStatus {
+	Waiting,
+	Summarizing,
+	/// Summarized and saved to the history.
+	Done,
+	Failed(AppError),
+}
+
+impl QueueStatus {
+	pub fn is_finished(&self) -> bool {
+		matches!(self, Self::Done | Self::Failed(_))
+	}
+}
+
+/// A tab in the queue under [`QUEUE_KEY`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct QueuedTab {
+	pub tab_id: u32,
+	pub url: String,
+	pub title: String,
+	pub status: QueueStatus,
+}
+
+/// The version of the [`Envelope`] format and the [`ExtMessage`] payloads it carries. Bump it whenever a payload changes
+/// shape, so that a page still running the old content script during an update rejects new messages instead of misreading them.
+pub const PROTOCOL_VERSION: u32 = 6;
+
+/// The part of the extension a message was sent from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, strum::Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Source {
+	Background,
+	Popup,
+	Options,
+	SidePanel,
+	ContentScript,
+}
+
+/// Every message the extension's parts exchange, wrapped with the protocol version, a correlation id and where it came from.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Envelope {
+	pub version: u32,
+	pub id: Uuid,
+	/// The `id` of the request this message answers, if it is a response.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub reply_to: Option<Uuid>,
+	pub source: Source,
+	payload: Payload,
+}
+
+// a payload this build can't decode still lets the rest of the envelope through, so `Envelope::open` can tell a message from
+// another version apart from a malformed one
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+enum Payload {
+	Known(ExtMessage),
+	Unknown(serde_json::Value),
+}
+
+impl Envelope {
+	/// A new request with a fresh id.
+	pub fn request(source: Source, payload: ExtMessage) -> Self {
+		Self { version: PROTOCOL_VERSION, id: Uuid::new_v4(), reply_to: None, source, payload: Payload::Known(payload) }
+	}
+
+	/// A response to `self`, which the sender matches up through [`Self::is_reply_to`].
+	pub fn reply(&self, source: Source, payload: ExtMessage) -> Self {
+		Self { reply_to: Some(self.id), ..Self::request(source, payload) }
+	}
+
+	pub fn is_reply_to(&self, request: &Self) -> bool {
+		self.reply_to == Some(request.id)
+	}
+
+	/// The payload, provided the message was sent by a part of the extension speaking the same [`PROTOCOL_VERSION`]. The version is
+	/// checked before the payload, which another version may have filled with a message this one doesn't know.
+	pub fn open(self) -> Result<ExtMessage, AppError> {
+		if self.version != PROTOCOL_VERSION {
+			return Err(AppError::IncompatibleVersion { expected: PROTOCOL_VERSION, found: self.version });
+		}
+		match self.payload {
+			Payload::Known(payload) => Ok(payload),
+			Payload::Unknown(payload) => Err(AppError::ExtensionError(format!("received a message that isn't part of the protocol: {payload}"))),
+		}
+	}
+
+	/// Opens `self` as the response to `request`, turning an `Error` payload into `Err`.
+	pub fn response_to(self, request: &Self) -> Result<ExtMessage, AppError> {
+		if !self.is_reply_to(request) {
+			return Err(AppError::ExtensionError(format!("received a response to another request than {}", request.id)));
+		}
+		match self.open()? {
+			ExtMessage::Error(e) => Err(e),
+			payload => Ok(payload),
+		}
+	}
+}
+
+/// Where a summary came from, which the popup notes under it unless it is fresh from the server.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryOrigin {
+	#[default]
+	Server,
+	/// The server's cache of earlier summaries.
+	Cache,
+	/// Computed in the extension because the server could not be reached.
+	Offline,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum ExtMessage {
 	SummarizeRequest,
-	SummarizeResponse(String),
+	/// Like `SummarizeRequest`, but for the text selected on the page only.
+	SummarizeSelectionRequest,
+	/// The next piece of a summary that is still being generated.
+	SummarizeChunk(String),
+	/// The server couldn't be reached or failed, and the background is about to send the request again, as attempt `attempt` of
+	/// `max_attempts`.
+	SummarizeRetrying {
+		attempt: u32,
+		max_attempts: u32,
+	},
+	/// The complete summary, sent once streaming has finished.
+	SummarizeResponse {
+		summary: String,
+		origin: SummaryOrigin,
+	},
 	GetPageContent,
+	/// The content script's answer to `GetPageContent`.
+	PageContent(String),
+	GetSelection,
+	/// The content script's answer to `GetSelection`, empty if nothing is selected.
+	Selection(String),
+	/// Answers a request that needs no other answer, like `ToggleOverlay`.
+	Ack,
+	/// Shows the summary in a panel on the page, or hides the panel if it already shows it. Sent by the popup to the background,
+	/// which forwards it to the active tab's content script.
+	ToggleOverlay(String),
+	/// The tabs queued by "Summarize all tabs in this window", broadcast by the background whenever one of them changes status.
+	QueueProgress(Vec<QueuedTab>),
+	/// Asks the background to forget a finished queue, which it answers with `Ack` and an empty `QueueProgress`.
+	ClearQueue,
 	Error(AppError),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ServerSummarizeRequest {
 	pub text: String,
+	#[serde(default)]
+	pub style: SummaryStyle,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ServerSummarizeResponse {
 	pub summary: String,
+	/// Set when the server answered from its cache instead of asking the model again.
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub cached: bool,
+}
+
+/// The answer of `/api/extract/pdf`, which takes the bytes of a PDF as its body.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ServerPdfTextResponse {
+	pub text: String,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ServerErrorResponse {
 	pub error: String,
+	/// Set when the failure maps onto an error the extension shows differently, e.g. a missing auth token.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub app_error: Option<AppError>,
+}
+
+impl From<AppError> for ServerErrorResponse {
+	fn from(error: AppError) -> Self {
+		Self { error: error.to_string(), app_error: Some(error) }
+	}
+}
+
+/// A failure the user ran into, collected once they opt in with [`Config::report_errors`] so maintainers can see how the
+/// extension fails in the wild. It holds the error and where it happened, never the page's content or URL.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ErrorReport {
+	pub error: AppError,
+	pub extension_version: String,
+	pub browser: String,
+	pub occurred_at: DateTime<Utc>,
+}
+
+/// The body of `/api/telemetry/errors`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ServerErrorReports {
+	pub reports: Vec<ErrorReport>,
 }
 
-#[cfg(feature = "server")]
-#[server(endpoint = "/api/summarize")]
-pub async fn summarize(req: ServerSummarizeRequest) -> Result<ServerSummarizeResponse, ServerFnError> {
-	dioxus::logger::tracing::info!("Received text to summarize: {:?}", req.text);
-	let summary = format!(
-		"This is a hardcoded summary for the text: '{}...'",
-		req.text.chars().take(20).collect::<String>()
-	);
-	Ok(ServerSummarizeResponse { summary })
+/// One line of the newline-delimited JSON body of `/api/summarize/stream`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerSummarizeChunk {
+	/// Comes first when the summary is replayed from the server's cache.
+	Cached,
+	Delta(String),
+	Error(String),
 }
@@ -0,0 +1,250 @@
+//! The small subset of Markdown the server's summaries use, rendered to elements rather than HTML: headings, bullet and numbered
+//! lists, paragraphs, `**bold**`, `*emphasis*`, `` `code` `` and links. The text is never parsed by the browser, so whatever a
+//! model (or a page it quotes) puts in a summary shows up as text, and links only keep `http` and `https` targets.
+
+use dioxus::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Block {
+	/// A `#` heading, its level clamped to 1 to 3.
+	Heading(u8, Vec<Inline>),
+	Paragraph(Vec<Inline>),
+	List {
+		ordered: bool,
+		items: Vec<Vec<Inline>>,
+	},
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inline {
+	Text(String),
+	Strong(Vec<Inline>),
+	Emphasis(Vec<Inline>),
+	Code(String),
+	Link { href: String, children: Vec<Inline> },
+}
+
+/// Splits `text` into blocks. Anything that isn't part of the subset, including HTML, stays text.
+pub fn parse(text: &str) -> Vec<Block> {
+	let mut blocks = Vec::new();
+	let mut paragraph: Option<String> = None;
+	// the items of the list being read, the last one possibly continuing on the next line
+	let mut list: Option<(bool, Vec<String>)> = None;
+
+	fn end(blocks: &mut Vec<Block>, paragraph: &mut Option<String>, list: &mut Option<(bool, Vec<String>)>) {
+		if let Some(paragraph) = paragraph.take() {
+			blocks.push(Block::Paragraph(parse_inline(&paragraph)));
+		}
+		if let Some((ordered, items)) = list.take() {
+			blocks.push(Block::List { ordered, items: items.iter().map(|item| parse_inline(item)).collect() });
+		}
+	}
+
+	for line in text.lines().map(str::trim) {
+		if line.is_empty() {
+			end(&mut blocks, &mut paragraph, &mut list);
+		} else if let Some((level, heading)) = heading(line) {
+			end(&mut blocks, &mut paragraph, &mut list);
+			blocks.push(Block::Heading(level, parse_inline(heading)));
+		} else if let Some((ordered, item)) = list_item(line) {
+			match &mut list {
+				Some((current, items)) if paragraph.is_none() && *current == ordered => items.push(item.to_owned()),
+				_ => {
+					end(&mut blocks, &mut paragraph, &mut list);
+					list = Some((ordered, vec![item.to_owned()]));
+				},
+			}
+		} else if let Some(wrapped) = list.as_mut().and_then(|(_, items)| items.last_mut()).or(paragraph.as_mut()) {
+			// a wrapped line continues the list item or paragraph above it
+			wrapped.push(' ');
+			wrapped.push_str(line);
+		} else {
+			paragraph = Some(line.to_owned());
+		}
+	}
+	end(&mut blocks, &mut paragraph, &mut list);
+	blocks
+}
+
+fn heading(line: &str) -> Option<(u8, &str)> {
+	let level = line.chars().take_while(|&c| c == '#').count();
+	let rest = line[level..].strip_prefix(' ')?;
+	(1..=6).contains(&level).then(|| (level.min(3) as u8, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+// `- item`, `* item` and `+ item` are bullets, `1. item` and `1) item` numbered
+fn list_item(line: &str) -> Option<(bool, &str)> {
+	if let Some(item) = line.strip_prefix(['-', '*', '+']).and_then(|rest| rest.strip_prefix(' ')) {
+		return Some((false, item.trim_start()));
+	}
+	let digits = line.chars().take_while(char::is_ascii_digit).count();
+	let item = line[digits..].strip_prefix(['.', ')'])?.strip_prefix(' ')?;
+	(1..=9).contains(&digits).then_some((true, item.trim_start()))
+}
+
+/// Splits one line of text into its inline spans. Markers without a closing counterpart stay text.
+pub fn parse_inline(text: &str) -> Vec<Inline> {
+	let mut spans = Vec::new();
+	let mut plain = String::new();
+	let mut rest = text;
+	while let Some(c) = rest.chars().next() {
+		let parsed = match c {
+			'\\' => rest[1..].chars().next().filter(char::is_ascii_punctuation).map(|escaped| (vec![Inline::Text(escaped.to_string())], 1 + escaped.len_utf8())),
+			'`' => delimited(rest, "`").map(|(code, len)| (vec![Inline::Code(code.to_owned())], len)),
+			'*' if rest.starts_with("**") => delimited(rest, "**").map(|(inner, len)| (vec![Inline::Strong(parse_inline(inner))], len)),
+			'*' => delimited(rest, "*").map(|(inner, len)| (vec![Inline::Emphasis(parse_inline(inner))], len)),
+			// `_` only opens emphasis at the start of a word, so snake_case names stay as they are
+			'_' if !plain.ends_with(char::is_alphanumeric) => delimited(rest, "_").map(|(inner, len)| (vec![Inline::Emphasis(parse_inline(inner))], len)),
+			'[' => link(rest),
+			_ => None,
+		};
+		let Some((parsed, len)) = parsed else {
+			plain.push(c);
+			rest = &rest[c.len_utf8()..];
+			continue;
+		};
+		for span in parsed {
+			match span {
+				Inline::Text(text) => plain.push_str(&text),
+				span => {
+					if !plain.is_empty() {
+						spans.push(Inline::Text(std::mem::take(&mut plain)));
+					}
+					spans.push(span);
+				},
+			}
+		}
+		rest = &rest[len..];
+	}
+	if !plain.is_empty() {
+		spans.push(Inline::Text(plain));
+	}
+	spans
+}
+
+// the non-empty text between `marker` at the start of `text` and its next occurrence, and the length of both with the markers
+fn delimited<'a>(text: &'a str, marker: &str) -> Option<(&'a str, usize)> {
+	let inner = &text[marker.len()..];
+	let end = inner.find(marker).filter(|&end| end > 0)?;
+	Some((&inner[..end], marker.len() * 2 + end))
+}
+
+// `[text](href)`; a link to anything but a web page, like a `javascript:` URL, is replaced by its text
+fn link(text: &str) -> Option<(Vec<Inline>, usize)> {
+	let label_end = text.find("](")?;
+	// parentheses inside the target, as in Wikipedia links, are balanced
+	let mut depth = 0;
+	let href_len = text[label_end + 2..].find(|c| {
+		match c {
+			'(' => depth += 1,
+			')' if depth == 0 => return true,
+			')' => depth -= 1,
+			_ => {},
+		}
+		false
+	})?;
+	let children = parse_inline(&text[1..label_end]);
+	let href = text[label_end + 2..label_end + 2 + href_len].trim();
+	let is_web = ["http://", "https://"].iter().any(|scheme| href.get(..scheme.len()).is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme)));
+	let spans = if is_web { vec![Inline::Link { href: href.to_owned(), children }] } else { children };
+	Some((spans, label_end + 3 + href_len))
+}
+
+/// `text` without its Markdown, for places that can only show plain text, like a tooltip: one line per heading, paragraph and list
+/// item, bullets as `•`.
+pub fn plain_text(text: &str) -> String {
+	fn flatten(spans: &[Inline], into: &mut String) {
+		for span in spans {
+			match span {
+				Inline::Text(text) | Inline::Code(text) => into.push_str(text),
+				Inline::Strong(children) | Inline::Emphasis(children) | Inline::Link { children, .. } => flatten(children, into),
+			}
+		}
+	}
+	let mut lines = Vec::new();
+	for block in parse(text) {
+		match block {
+			Block::Heading(_, spans) | Block::Paragraph(spans) => {
+				let mut line = String::new();
+				flatten(&spans, &mut line);
+				lines.push(line);
+			},
+			Block::List { ordered, items } => {
+				for (i, item) in items.iter().enumerate() {
+					let mut line = if ordered { format!("{}. ", i + 1) } else { "• ".to_owned() };
+					flatten(item, &mut line);
+					lines.push(line);
+				}
+			},
+		}
+	}
+	lines.join("\n")
+}
+
+/// Renders `text` as Markdown, see the [module documentation](self) for what is supported.
+#[component]
+pub fn Markdown(text: String) -> Element {
+	rsx! {
+		div { class: "space-y-2",
+			for block in parse(&text) {
+				{render_block(block)}
+			}
+		}
+	}
+}
+
+fn render_block(block: Block) -> Element {
+	match block {
+		Block::Heading(1, spans) => rsx! {
+			h3 { class: "text-base font-semibold text-gray-800 dark:text-gray-100", {render_inline(spans)} }
+		},
+		Block::Heading(_, spans) => rsx! {
+			h4 { class: "text-sm font-semibold text-gray-800 dark:text-gray-100", {render_inline(spans)} }
+		},
+		Block::Paragraph(spans) => rsx! {
+			p { {render_inline(spans)} }
+		},
+		Block::List { ordered: false, items } => rsx! {
+			ul { class: "pl-5 space-y-1 list-disc",
+				for item in items {
+					li { {render_inline(item)} }
+				}
+			}
+		},
+		Block::List { ordered: true, items } => rsx! {
+			ol { class: "pl-5 space-y-1 list-decimal",
+				for item in items {
+					li { {render_inline(item)} }
+				}
+			}
+		},
+	}
+}
+
+fn render_inline(spans: Vec<Inline>) -> Element {
+	rsx! {
+		for span in spans {
+			match span {
+				Inline::Text(text) => rsx! { "{text}" },
+				Inline::Strong(children) => rsx! {
+					strong { {render_inline(children)} }
+				},
+				Inline::Emphasis(children) => rsx! {
+					em { {render_inline(children)} }
+				},
+				Inline::Code(code) => rsx! {
+					code { class: "px-1 text-xs bg-gray-200 dark:bg-gray-700 rounded", "{code}" }
+				},
+				Inline::Link { href, children } => rsx! {
+					a {
+						class: "text-blue-600 hover:underline",
+						href,
+						target: "_blank",
+						rel: "noopener noreferrer",
+						{render_inline(children)}
+					}
+				},
+			}
+		}
+	}
+}
@@ -0,0 +1,92 @@
+use dioxus::prelude::*;
+use futures::StreamExt;
+
+use crate::{Envelope, ExtMessage, QUEUE_KEY, QueueStatus, QueuedTab, Source, i18n::use_locale};
+
+/// The progress of "Summarize all tabs in this window", one line per queued tab, or nothing while no tabs were queued. It starts
+/// from the queue in `storage.session` and follows the background's [`ExtMessage::QueueProgress`] broadcasts from there. `source`
+/// is the page it is shown on.
+#[component]
+pub fn QueueProgress(source: Source) -> Element {
+	let locale = use_locale();
+	let browser = use_hook(|| webext_api::init().expect("the queue is only shown inside an extension"));
+	let mut queue = use_signal(Vec::<QueuedTab>::new);
+	use_future({
+		to_owned![browser];
+		move || {
+			to_owned![browser];
+			async move {
+				// listening before the stored queue is read, so nothing broadcast in between is missed
+				let Ok(mut broadcasts) = browser.runtime().on_message_stream::<Envelope>() else {
+					return;
+				};
+				match browser.storage().session().get::<Vec<QueuedTab>>(QUEUE_KEY).await {
+					Ok(stored) => queue.set(stored.unwrap_or_default()),
+					Err(e) => warn!("could not read the summary queue: {}", e),
+				}
+				while let Some((envelope, _)) = broadcasts.next().await {
+					if let Ok(ExtMessage::QueueProgress(tabs)) = envelope.open() {
+						queue.set(tabs);
+					}
+				}
+			}
+		}
+	});
+	let finished = use_memo(move || queue.read().iter().filter(|tab| tab.status.is_finished()).count());
+
+	if queue.read().is_empty() {
+		return rsx! {};
+	}
+
+	rsx! {
+		section { class: "mt-4 p-3 border border-gray-200 dark:border-gray-700 rounded-md text-sm text-gray-700 dark:text-gray-200",
+			div { class: "flex items-center justify-between mb-2",
+				h2 { class: "font-semibold", {locale.t_with("queueProgress", &[&finished().to_string(), &queue.read().len().to_string()])} }
+				if finished() == queue.read().len() {
+					button {
+						class: "text-xs text-blue-600 hover:underline bg-transparent border-none cursor-pointer",
+						// the background clears the queue and broadcasts the empty one to every open page
+						onclick: move |_| {
+								let runtime = browser.runtime();
+								async move {
+										if let Err(e) = runtime.send_message::<_, Envelope>(&Envelope::request(source, ExtMessage::ClearQueue)).await {
+												warn!("could not clear the summary queue: {}", e);
+										}
+								}
+						},
+						{locale.t("clearQueue")}
+					}
+				}
+			}
+			ul { class: "space-y-1",
+				for tab in queue() {
+					li { key: "{tab.tab_id}", class: "flex items-center gap-2",
+						match &tab.status {
+								QueueStatus::Waiting => rsx! {
+									span { class: "w-4 text-center text-gray-400", title: locale.t("queueWaiting"), "…" }
+								},
+								QueueStatus::Summarizing => rsx! {
+									span { class: "w-4 flex justify-center", title: locale.t("summarizing"),
+										span { class: "animate-spin rounded-full h-3 w-3 border-b-2 border-blue-600" }
+									}
+								},
+								QueueStatus::Done => rsx! {
+									span { class: "w-4 text-center text-green-600", title: locale.t("queueDone"), "✓" }
+								},
+								QueueStatus::Failed(error) => rsx! {
+									span { class: "w-4 text-center text-red-600 font-bold", title: locale.error(error), "!" }
+								},
+						}
+						span { class: "flex-1 truncate", title: "{tab.url}",
+							if tab.title.is_empty() {
+								"{tab.url}"
+							} else {
+								"{tab.title}"
+							}
+						}
+					}
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,45 @@
+use dioxus::prelude::*;
+use futures::{StreamExt, channel::mpsc::unbounded};
+use wasm_bindgen::{JsCast, prelude::*};
+use web_sys::{MediaQueryList, window};
+
+use crate::Theme;
+
+const DARK_QUERY: &str = "(prefers-color-scheme: dark)";
+
+fn dark_query() -> Option<MediaQueryList> {
+	window().and_then(|window| window.match_media(DARK_QUERY).ok().flatten())
+}
+
+/// Keeps the `dark` class on the page's root element in step with `theme`, which the stylesheets' `dark:` variant keys off.
+/// [`Theme::System`] follows the operating system's preference, including when it changes while the page is open.
+pub fn use_theme(theme: Memo<Theme>) {
+	let mut prefers_dark = use_signal(|| dark_query().is_some_and(|query| query.matches()));
+
+	use_future(move || async move {
+		let Some(query) = dark_query() else {
+			return;
+		};
+		let (sender, mut changes) = unbounded();
+		let listener = Closure::<dyn FnMut()>::new(move || drop(sender.unbounded_send(())));
+		if query.add_event_listener_with_callback("change", listener.as_ref().unchecked_ref()).is_err() {
+			return;
+		}
+		// the listener lives as long as this future, which lives as long as the page
+		while changes.next().await.is_some() {
+			prefers_dark.set(query.matches());
+		}
+		drop(listener);
+	});
+
+	use_effect(move || {
+		let dark = match theme() {
+			Theme::Light => false,
+			Theme::Dark => true,
+			Theme::System => prefers_dark(),
+		};
+		if let Some(root) = window().and_then(|window| window.document()).and_then(|document| document.document_element()) {
+			let _ = root.class_list().toggle_with_force("dark", dark);
+		}
+	});
+}
@@ -0,0 +1,34 @@
+use common::{Config, ConfigImportError, SummaryStyle, Theme};
+
+fn configured() -> Config {
+	Config { server_url: "https://summaries.example".to_owned(), auth_token: "secret".to_owned(), ..Config::default() }
+}
+
+#[test]
+fn export_leaves_out_the_auth_token() {
+	let exported = configured().to_export_json();
+	assert!(exported.contains("https://summaries.example"));
+	assert!(!exported.contains("auth_token") && !exported.contains("secret"), "{exported}");
+}
+
+#[test]
+fn exported_settings_import_back_keeping_the_token() {
+	let exported = Config { theme: Theme::Dark, summary_style: SummaryStyle::Paragraph, ..configured() }.to_export_json();
+	let imported = configured().merged_with_json(&exported).unwrap();
+	assert_eq!(imported, Config { theme: Theme::Dark, summary_style: SummaryStyle::Paragraph, ..configured() });
+}
+
+#[test]
+fn partial_files_only_change_what_they_contain() {
+	let imported = configured().merged_with_json(r#"{ "theme": "light", "unknown_setting": 1 }"#).unwrap();
+	assert_eq!(imported, Config { theme: Theme::Light, ..configured() });
+}
+
+#[test]
+fn invalid_files_are_rejected() {
+	assert!(matches!(configured().merged_with_json("{ theme: dark"), Err(ConfigImportError::InvalidJson(_))));
+	assert_eq!(configured().merged_with_json("[1, 2]"), Err(ConfigImportError::NoSettings));
+	assert_eq!(configured().merged_with_json(r#"{ "something": "else" }"#), Err(ConfigImportError::NoSettings));
+	assert!(matches!(configured().merged_with_json(r#"{ "theme": "sepia" }"#), Err(ConfigImportError::InvalidSetting(_))));
+	assert!(matches!(configured().merged_with_json(r#"{ "enable_notifications": "yes" }"#), Err(ConfigImportError::InvalidSetting(_))));
+}
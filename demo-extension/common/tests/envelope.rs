@@ -0,0 +1,27 @@
+use common::{AppError, Envelope, ExtMessage, PROTOCOL_VERSION, Source};
+use serde_json::json;
+
+fn envelope(version: u32, payload: serde_json::Value) -> Envelope {
+	let message = json!({ "version": version, "id": "67e55044-10b1-426f-9247-bb680e5fe0c8", "source": "background", "payload": payload });
+	serde_json::from_value(message).unwrap()
+}
+
+#[test]
+fn requests_round_trip() {
+	let request = Envelope::request(Source::Popup, ExtMessage::SummarizeRequest);
+	let received: Envelope = serde_json::from_value(serde_json::to_value(&request).unwrap()).unwrap();
+	assert_eq!(received.id, request.id);
+	assert!(matches!(received.open(), Ok(ExtMessage::SummarizeRequest)));
+}
+
+#[test]
+fn messages_from_a_newer_version_are_incompatible_even_when_unknown() {
+	let opened = envelope(PROTOCOL_VERSION + 1, json!({ "SummarizeVideoRequest": { "start": 0 } })).open();
+	assert!(matches!(opened, Err(AppError::IncompatibleVersion { expected: PROTOCOL_VERSION, found }) if found == PROTOCOL_VERSION + 1), "{opened:?}");
+}
+
+#[test]
+fn unknown_messages_of_the_same_version_are_errors() {
+	let opened = envelope(PROTOCOL_VERSION, json!("SummarizeVideoRequest")).open();
+	assert!(matches!(opened, Err(AppError::ExtensionError(_))), "{opened:?}");
+}
@@ -0,0 +1,47 @@
+use common::markdown::{Block, Inline, parse, parse_inline, plain_text};
+
+fn text(text: &str) -> Inline {
+	Inline::Text(text.to_owned())
+}
+
+#[test]
+fn summaries_split_into_headings_lists_and_paragraphs() {
+	let blocks = parse("## Rust 2024\n\n- **Editions** are opt-in\n- let chains\n  are stable\n\nThat's all.");
+	assert_eq!(
+		blocks,
+		vec![
+			Block::Heading(2, vec![text("Rust 2024")]),
+			Block::List { ordered: false, items: vec![vec![Inline::Strong(vec![text("Editions")]), text(" are opt-in")], vec![text("let chains are stable")]] },
+			Block::Paragraph(vec![text("That's all.")]),
+		]
+	);
+	assert_eq!(
+		parse("1. first\n2) second\n- other"),
+		vec![
+			Block::List { ordered: true, items: vec![vec![text("first")], vec![text("second")]] },
+			Block::List { ordered: false, items: vec![vec![text("other")]] },
+		]
+	);
+}
+
+#[test]
+fn inline_markers_need_a_closing_counterpart() {
+	assert_eq!(
+		parse_inline("*a* `b` _c_"),
+		vec![Inline::Emphasis(vec![text("a")]), text(" "), Inline::Code("b".to_owned()), text(" "), Inline::Emphasis(vec![text("c")])]
+	);
+	assert_eq!(parse_inline("2 * 3 and snake_case_name"), vec![text("2 * 3 and snake_case_name")]);
+	assert_eq!(parse_inline(r"\*literal\*"), vec![text("*literal*")]);
+}
+
+#[test]
+fn html_and_script_links_stay_text() {
+	assert_eq!(parse_inline("<img src=x onerror=alert(1)>"), vec![text("<img src=x onerror=alert(1)>")]);
+	assert_eq!(parse_inline("[click](javascript:alert(1))"), vec![text("click")]);
+	assert_eq!(parse_inline("[docs](https://docs.example/)"), vec![Inline::Link { href: "https://docs.example/".to_owned(), children: vec![text("docs")] }]);
+}
+
+#[test]
+fn plain_text_drops_the_markup() {
+	assert_eq!(plain_text("# Title\n- **one**\n- [two](https://two.example/)"), "Title\n• one\n• two");
+}
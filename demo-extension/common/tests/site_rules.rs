@@ -0,0 +1,43 @@
+use common::{Config, ConfigImportError, SiteMode, SiteRules};
+use webext_api::match_pattern::MatchPattern;
+
+fn rules(mode: SiteMode, patterns: &[&str]) -> SiteRules {
+	SiteRules { mode, patterns: patterns.iter().map(|pattern| MatchPattern::parse(pattern).unwrap()).collect() }
+}
+
+#[test]
+fn deny_lists_block_matching_sites_only() {
+	let rules = rules(SiteMode::Deny, &["*://*.bank.example/*"]);
+	assert!(!rules.allows("https://online.bank.example/login"));
+	assert!(rules.allows("https://news.example/article"));
+	assert!(SiteRules::default().allows("https://news.example/article"));
+}
+
+#[test]
+fn allow_lists_permit_matching_sites_only() {
+	let rules = rules(SiteMode::Allow, &["https://docs.example/*"]);
+	assert!(rules.allows("https://docs.example/guide"));
+	assert!(!rules.allows("http://docs.example/guide"));
+	assert!(!rules.allows("https://news.example/article"));
+}
+
+#[test]
+fn toggling_a_site_adds_or_drops_its_patterns() {
+	let mut deny = rules(SiteMode::Deny, &["*://*.news.example/*"]);
+	assert!(deny.set_allowed("https://www.news.example/a", true));
+	assert!(deny.patterns.is_empty());
+	assert!(deny.set_allowed("https://www.news.example/a", false));
+	assert_eq!(deny, rules(SiteMode::Deny, &["*://www.news.example/*"]));
+
+	let mut allow = rules(SiteMode::Allow, &[]);
+	assert!(allow.set_allowed("https://docs.example/guide", true));
+	assert!(allow.allows("http://docs.example/other"));
+	assert!(!allow.set_allowed("file:///home/user/notes.html", true));
+}
+
+#[test]
+fn imported_patterns_are_validated() {
+	let imported = Config::default().merged_with_json(r#"{ "site_rules": { "mode": "allow", "patterns": ["*://docs.example/*"] } }"#).unwrap();
+	assert_eq!(imported.site_rules, rules(SiteMode::Allow, &["*://docs.example/*"]));
+	assert!(matches!(Config::default().merged_with_json(r#"{ "site_rules": { "patterns": ["docs.example"] } }"#), Err(ConfigImportError::InvalidSetting(_))));
+}
@@ -0,0 +1,381 @@
+use std::collections::HashMap;
+
+use scraper::{ElementRef, Html, Node, Selector};
+
+/// The readable part of a page, as found by [`extract_article`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Article {
+	pub title: Option<String>,
+	pub byline: Option<String>,
+	/// Paragraphs of the article body, separated by blank lines.
+	pub text: String,
+}
+
+impl Article {
+	/// The title, byline and body joined into the plain text the summarizer is sent.
+	pub fn to_plain_text(&self) -> String {
+		let mut out = String::new();
+		if let Some(title) = &self.title {
+			out.push_str(title);
+			out.push('\n');
+		}
+		if let Some(byline) = &self.byline {
+			out.push_str(byline);
+			out.push('\n');
+		}
+		if !out.is_empty() {
+			out.push('\n');
+		}
+		out.push_str(&self.text);
+		out
+	}
+}
+
+// tags that never hold article text
+const IGNORED_TAGS: &[&str] = &[
+	"script", "style", "noscript", "template", "iframe", "svg", "canvas", "object", "embed", "button", "input", "select", "textarea", "nav", "aside", "footer",
+	"form", "dialog",
+];
+// tags whose text is scored as a paragraph
+const PARAGRAPH_TAGS: &[&str] = &["p", "pre", "td", "blockquote"];
+// tags that end a line of text when the article is flattened
+const BLOCK_TAGS: &[&str] = &[
+	"address",
+	"article",
+	"blockquote",
+	"br",
+	"dd",
+	"div",
+	"dl",
+	"dt",
+	"figcaption",
+	"figure",
+	"h1",
+	"h2",
+	"h3",
+	"h4",
+	"h5",
+	"h6",
+	"header",
+	"hr",
+	"li",
+	"main",
+	"ol",
+	"p",
+	"pre",
+	"section",
+	"table",
+	"td",
+	"th",
+	"tr",
+	"ul",
+];
+// class/id fragments of page furniture: menus, comment threads, share bars, cookie banners...
+const UNLIKELY: &[&str] = &[
+	"ad-break",
+	"agegate",
+	"banner",
+	"breadcrumb",
+	"combx",
+	"comment",
+	"community",
+	"cookie",
+	"cover-wrap",
+	"disqus",
+	"footer",
+	"gdpr",
+	"header",
+	"legends",
+	"menu",
+	"newsletter",
+	"pager",
+	"pagination",
+	"popup",
+	"related",
+	"remark",
+	"replies",
+	"rss",
+	"share",
+	"shoutbox",
+	"sidebar",
+	"skyscraper",
+	"social",
+	"sponsor",
+	"subscribe",
+	"supplemental",
+];
+// ...unless they also look like the content itself, e.g. `main-content-header`
+const MAYBE_CANDIDATE: &[&str] = &["article", "body", "column", "content", "main", "story"];
+const POSITIVE: &[&str] = &["article", "blog", "body", "content", "entry", "h-entry", "hentry", "main", "page", "post", "story", "text"];
+const NEGATIVE: &[&str] = &[
+	"-ad-",
+	"banner",
+	"combx",
+	"comment",
+	"com-",
+	"contact",
+	"foot",
+	"gdpr",
+	"masthead",
+	"media",
+	"meta",
+	"outbrain",
+	"promo",
+	"related",
+	"scroll",
+	"share",
+	"shoutbox",
+	"sidebar",
+	"skyscraper",
+	"sponsor",
+	"shopping",
+	"tags",
+	"tool",
+	"widget",
+];
+const UNLIKELY_ROLES: &[&str] = &["alert", "alertdialog", "banner", "complementary", "contentinfo", "dialog", "menu", "menubar", "navigation"];
+const TITLE_SEPARATORS: &[&str] = &[" | ", " - ", " – ", " — ", " :: ", " / ", " » "];
+
+// paragraphs shorter than this are too short to say anything about their container
+const MIN_PARAGRAPH_CHARS: usize = 25;
+const MAX_BYLINE_CHARS: usize = 100;
+
+/// Finds the main article of an HTML document the way Firefox's reader view does: paragraphs score their
+/// ancestors by length and comma count, class names and link density push containers up or down, the best
+/// container is joined with related siblings, and boilerplate inside it (navigation, share bars, link lists) is dropped.
+///
+/// Pages with no scorable paragraphs fall back to the text of `<body>`, so the result is empty only for empty pages.
+pub fn extract_article(html: &str) -> Article {
+	let document = Html::parse_document(html);
+	let title = title(&document);
+	let byline = byline(&document);
+
+	let body = select_first(&document, "body");
+	let mut scores: HashMap<_, f64> = HashMap::new();
+	for paragraph in document.select(&selector(&PARAGRAPH_TAGS.join(","))) {
+		if is_unlikely(paragraph) {
+			continue;
+		}
+		let length = text_length(paragraph);
+		if length < MIN_PARAGRAPH_CHARS {
+			continue;
+		}
+		let commas = paragraph.text().map(|text| text.matches([',', '，']).count()).sum::<usize>();
+		let score = 1.0 + commas as f64 + (length as f64 / 100.0).min(3.0);
+		// the parent gets the full score, the grandparent half and the great-grandparent a sixth
+		for (level, ancestor) in paragraph.ancestors().filter_map(ElementRef::wrap).take(3).enumerate() {
+			if ancestor.value().name() == "body" || ancestor.value().name() == "html" {
+				break;
+			}
+			let entry = scores.entry(ancestor.id()).or_insert_with(|| initial_score(ancestor));
+			let divider = match level {
+				0 => 1.0,
+				1 => 2.0,
+				_ => 6.0,
+			};
+			*entry += score / divider;
+		}
+	}
+
+	let top = scores
+		.iter()
+		.filter_map(|(&id, &score)| document.tree.get(id).and_then(ElementRef::wrap).map(|element| (element, score * (1.0 - link_density(element)))))
+		.max_by(|(_, a), (_, b)| a.total_cmp(b));
+
+	let mut text = String::new();
+	match top {
+		Some((top, top_score)) => {
+			// siblings are often the rest of the article, split up by an ad slot or a figure
+			let threshold = (top_score * 0.2).max(10.0);
+			let siblings = top.parent().map_or_else(|| vec![top], |parent| parent.children().filter_map(ElementRef::wrap).collect());
+			for sibling in siblings {
+				let include = sibling == top
+					|| scores.get(&sibling.id()).is_some_and(|&score| score * (1.0 - link_density(sibling)) >= threshold)
+					|| (sibling.value().name() == "p" && text_length(sibling) > 80 && link_density(sibling) < 0.25);
+				if include && !is_unlikely(sibling) {
+					push_text(sibling, &mut text);
+					end_block(&mut text);
+				}
+			}
+		},
+		None => {
+			if let Some(body) = body {
+				push_text(body, &mut text);
+			}
+		},
+	}
+
+	// the article's own heading repeats the title, which `to_plain_text` already puts first
+	let mut text = text.trim();
+	if let Some(rest) = title.as_deref().and_then(|title| text.strip_prefix(title))
+		&& (rest.is_empty() || rest.starts_with("\n\n"))
+	{
+		text = rest.trim_start();
+	}
+	Article { title, byline, text: text.to_owned() }
+}
+
+fn selector(selectors: &str) -> Selector {
+	Selector::parse(selectors).expect("the extractor's selectors are valid")
+}
+
+fn select_first<'a>(document: &'a Html, selectors: &str) -> Option<ElementRef<'a>> {
+	document.select(&selector(selectors)).next()
+}
+
+fn normalized_text(element: ElementRef<'_>) -> String {
+	element.text().collect::<String>().split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn meta_content(document: &Html, selectors: &str) -> Option<String> {
+	select_first(document, selectors)
+		.and_then(|meta| meta.value().attr("content"))
+		.map(|content| content.split_whitespace().collect::<Vec<_>>().join(" "))
+		.filter(|content| !content.is_empty())
+}
+
+fn title(document: &Html) -> Option<String> {
+	let full = select_first(document, "head title").map(normalized_text).filter(|title| !title.is_empty());
+	let Some(full) = full else {
+		return meta_content(document, r#"meta[property="og:title"], meta[name="twitter:title"]"#)
+			.or_else(|| select_first(document, "h1").map(normalized_text).filter(|heading| !heading.is_empty()));
+	};
+	// "Article headline | Site name": keep the headline when it is long enough to stand on its own
+	for separator in TITLE_SEPARATORS {
+		if let Some((headline, _site)) = full.rsplit_once(separator)
+			&& headline.split_whitespace().count() >= 3
+		{
+			return Some(headline.to_owned());
+		}
+	}
+	Some(full)
+}
+
+fn byline(document: &Html) -> Option<String> {
+	let from_meta = meta_content(document, r#"meta[name="author"], meta[property="article:author"]"#).filter(|author| !author.starts_with("http"));
+	from_meta
+		.or_else(|| {
+			document
+				.select(&selector(r#"[rel="author"], [itemprop~="author"], .byline, .author"#))
+				.map(normalized_text)
+				.find(|byline| !byline.is_empty() && byline.chars().count() <= MAX_BYLINE_CHARS)
+		})
+		.filter(|byline| byline.chars().count() <= MAX_BYLINE_CHARS)
+}
+
+fn class_and_id(element: ElementRef<'_>) -> String {
+	let value = element.value();
+	format!("{} {}", value.attr("class").unwrap_or_default(), value.id().unwrap_or_default()).to_lowercase()
+}
+
+fn is_hidden(element: ElementRef<'_>) -> bool {
+	let value = element.value();
+	let style = value.attr("style").unwrap_or_default().replace(' ', "").to_lowercase();
+	value.attr("hidden").is_some() || value.attr("aria-hidden") == Some("true") || style.contains("display:none") || style.contains("visibility:hidden")
+}
+
+fn is_boilerplate(element: ElementRef<'_>) -> bool {
+	let value = element.value();
+	if IGNORED_TAGS.contains(&value.name()) || is_hidden(element) {
+		return true;
+	}
+	if value.attr("role").is_some_and(|role| UNLIKELY_ROLES.contains(&role)) {
+		return true;
+	}
+	let names = class_and_id(element);
+	value.name() != "article"
+		&& value.name() != "main"
+		&& UNLIKELY.iter().any(|unlikely| names.contains(unlikely))
+		&& !MAYBE_CANDIDATE.iter().any(|maybe| names.contains(maybe))
+}
+
+// an element is unlikely to be content if it or anything around it is page furniture
+fn is_unlikely(element: ElementRef<'_>) -> bool {
+	is_boilerplate(element) || element.ancestors().filter_map(ElementRef::wrap).any(is_boilerplate)
+}
+
+fn class_weight(element: ElementRef<'_>) -> f64 {
+	let names = class_and_id(element);
+	let mut weight = 0.0;
+	if POSITIVE.iter().any(|positive| names.contains(positive)) {
+		weight += 25.0;
+	}
+	if NEGATIVE.iter().any(|negative| names.contains(negative)) {
+		weight -= 25.0;
+	}
+	weight
+}
+
+fn initial_score(element: ElementRef<'_>) -> f64 {
+	let tag_score = match element.value().name() {
+		"div" | "article" | "main" => 5.0,
+		"pre" | "td" | "blockquote" => 3.0,
+		"address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "form" => -3.0,
+		"h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
+		_ => 0.0,
+	};
+	tag_score + class_weight(element)
+}
+
+fn text_length(element: ElementRef<'_>) -> usize {
+	element.text().map(|text| text.split_whitespace().map(|word| word.chars().count() + 1).sum::<usize>()).sum()
+}
+
+/// The share of an element's text that sits inside links; navigation is almost all links, prose almost none.
+fn link_density(element: ElementRef<'_>) -> f64 {
+	let length = text_length(element);
+	if length == 0 {
+		return 0.0;
+	}
+	let linked =
+		element.select(&selector("a")).filter(|link| !link.value().attr("href").is_some_and(|href| href.starts_with('#'))).map(text_length).sum::<usize>();
+	linked as f64 / length as f64
+}
+
+// containers inside the article that are mostly links or marked as furniture, e.g. "read more" lists
+fn is_cluttered(element: ElementRef<'_>) -> bool {
+	if !matches!(element.value().name(), "div" | "section" | "ul" | "ol" | "table" | "header") {
+		return false;
+	}
+	let density = link_density(element);
+	density > 0.5 || (class_weight(element) < 0.0 && density > 0.2)
+}
+
+fn end_block(text: &mut String) {
+	let trimmed = text.trim_end_matches(' ').len();
+	text.truncate(trimmed);
+	if !text.is_empty() && !text.ends_with("\n\n") {
+		text.push_str(if text.ends_with('\n') { "\n" } else { "\n\n" });
+	}
+}
+
+fn push_text(element: ElementRef<'_>, text: &mut String) {
+	if is_boilerplate(element) || is_cluttered(element) {
+		return;
+	}
+	let block = BLOCK_TAGS.contains(&element.value().name());
+	if block {
+		end_block(text);
+	}
+	for child in element.children() {
+		match child.value() {
+			Node::Text(fragment) => {
+				for (i, word) in fragment.split(char::is_whitespace).enumerate() {
+					if i > 0 && !text.is_empty() && !text.ends_with([' ', '\n']) {
+						text.push(' ');
+					}
+					text.push_str(word);
+				}
+			},
+			Node::Element(_) => {
+				if let Some(child) = ElementRef::wrap(child) {
+					push_text(child, text);
+				}
+			},
+			_ => {},
+		}
+	}
+	if block {
+		end_block(text);
+	}
+}
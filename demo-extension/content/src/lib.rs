@@ -1,51 +1,93 @@
-use common::ExtMessage;
+use common::{AppError, Envelope, ExtMessage, Source};
 use dioxus::prelude::*;
 use js_sys::Function;
 use serde_wasm_bindgen::{from_value, to_value};
 use wasm_bindgen::{JsCast, prelude::*};
 use web_extensions_sys::chrome;
-use web_sys::{Element, window};
+use web_sys::{Element, HtmlInputElement, HtmlTextAreaElement, NodeList, window};
+use webext_api::api::Dom;
+
+pub mod extract;
+mod overlay;
 
 fn get_main_content() -> String {
 	let document = window().expect("window").document().expect("document");
-	let body = document.body().expect("body");
+	let html = document.document_element().map(|root| root.outer_html()).unwrap_or_default();
+	let mut content = extract::extract_article(&html).to_plain_text();
 
-	// Clone the body so we don't modify the actual page
-	let cloned_body = body.clone_node_with_deep(true).expect("clone").dyn_into::<Element>().expect("element");
+	// the serialized page doesn't include shadow roots, so text rendered inside web components is collected from the live page
+	if let (Some(body), Ok(browser)) = (document.body(), webext_api::init())
+		&& let Ok(elements) = body.query_selector_all("*")
+	{
+		collect_shadow_text(&browser.dom(), &elements, &mut content);
+	}
+	content
+}
 
-	// Remove unwanted elements from the clone
-	if let Ok(tags) = cloned_body.query_selector_all("script, style, noscript, nav, header, footer, aside, iframe, svg") {
-		for i in 0..tags.length() {
-			if let Some(node) = tags.item(i) {
-				if let Ok(element) = node.dyn_into::<Element>() {
-					element.remove();
-				}
+fn collect_shadow_text(dom: &Dom, elements: &NodeList, content: &mut String) {
+	for i in 0..elements.length() {
+		let Some(element) = elements.item(i).and_then(|node| node.dyn_into::<Element>().ok()) else {
+			continue;
+		};
+		if let Ok(Some(shadow_root)) = dom.open_or_closed_shadow_root(&element) {
+			if let Ok(nested) = shadow_root.query_selector_all("*") {
+				collect_shadow_text(dom, &nested, content);
 			}
+			content.push('\n');
+			content.push_str(&shadow_root.text_content().unwrap_or_default());
 		}
 	}
+}
 
-	cloned_body.text_content().unwrap_or_default()
+// `getSelection` doesn't see into form fields, so a selection inside the focused input or textarea is read from the field itself
+fn get_selection() -> String {
+	let window = window().expect("window");
+	let active = window.document().and_then(|document| document.active_element());
+	let field = active.and_then(|active| {
+		let (value, start, end) = if let Some(textarea) = active.dyn_ref::<HtmlTextAreaElement>() {
+			(textarea.value(), textarea.selection_start().ok()??, textarea.selection_end().ok()??)
+		} else {
+			let input = active.dyn_ref::<HtmlInputElement>()?;
+			(input.value(), input.selection_start().ok()??, input.selection_end().ok()??)
+		};
+		// the offsets count UTF-16 code units
+		let selected = String::from_utf16_lossy(&value.encode_utf16().skip(start as usize).take(end.saturating_sub(start) as usize).collect::<Vec<_>>());
+		Some(selected).filter(|selected| !selected.trim().is_empty())
+	});
+	field.or_else(|| window.get_selection().ok().flatten().map(|selection| String::from(selection.to_string()))).unwrap_or_default()
 }
 
 #[wasm_bindgen]
 pub fn main() {
 	dioxus::logger::initialize_default();
+	overlay::remove_stale_host();
 
 	let closure = Closure::<dyn FnMut(JsValue, JsValue, Function) -> bool>::new(|message: JsValue, _sender: JsValue, send_response: Function| {
-		if let Ok(ExtMessage::GetPageContent) = from_value(message) {
-			info!("[content_script] Received GetPageContent request");
-			let content = get_main_content();
-			match to_value(&content) {
-				Ok(js_val) => {
-					if let Err(e) = send_response.call1(&JsValue::UNDEFINED, &js_val) {
-						error!("[content_script] Failed to send response: {:?}", e);
-					}
-				},
-				Err(e) => error!("[content_script] Failed to serialize page content: {}", e.to_string()),
-			}
-			return true; // Keep channel open for sendResponse
+		let Ok(request) = from_value::<Envelope>(message) else {
+			return false;
+		};
+		let payload = match request.clone().open() {
+			Ok(ExtMessage::GetPageContent) => {
+				info!("[content_script] Received GetPageContent request {}", request.id);
+				ExtMessage::PageContent(get_main_content())
+			},
+			Ok(ExtMessage::GetSelection) => ExtMessage::Selection(get_selection()),
+			Ok(ExtMessage::ToggleOverlay(summary)) => match overlay::toggle(summary) {
+				Ok(()) => ExtMessage::Ack,
+				Err(e) => ExtMessage::Error(AppError::ExtensionError(format!("could not show the overlay: {e:?}"))),
+			},
+			Ok(_) => return false,
+			Err(e) => ExtMessage::Error(e),
+		};
+		match to_value(&request.reply(Source::ContentScript, payload)) {
+			Ok(js_val) => {
+				if let Err(e) = send_response.call1(&JsValue::UNDEFINED, &js_val) {
+					error!("[content_script] Failed to send response: {:?}", e);
+				}
+			},
+			Err(e) => error!("[content_script] Failed to serialize the response: {}", e.to_string()),
 		}
-		false
+		false // the response was sent synchronously
 	});
 	chrome().runtime().on_message().add_listener(closure.as_ref().unchecked_ref());
 	closure.forget();
@@ -0,0 +1,124 @@
+use std::cell::RefCell;
+
+use dioxus::{
+	prelude::*,
+	web::{Config, launch::launch_cfg},
+};
+use futures::{
+	StreamExt,
+	channel::mpsc::{UnboundedReceiver, UnboundedSender, unbounded},
+};
+use js_sys::{Array, Reflect};
+use wasm_bindgen::prelude::*;
+use web_sys::{CssStyleSheet, Document, ShadowRoot, ShadowRootInit, ShadowRootMode, window};
+
+const HOST_ID: &str = "dx-summarizer-overlay";
+
+// the page's stylesheets can't reach into the shadow root, and these rules can't leak out of it
+const OVERLAY_CSS: &str = r"
+:host { all: initial; }
+.panel {
+	position: fixed; top: 16px; right: 16px; z-index: 2147483647;
+	width: 360px; max-height: 60vh; overflow: auto; box-sizing: border-box;
+	padding: 16px; border-radius: 8px; border: 1px solid #e5e7eb;
+	background: #fff; color: #374151; box-shadow: 0 10px 25px rgba(0, 0, 0, 0.15);
+	font: 14px/1.5 system-ui, -apple-system, 'Segoe UI', sans-serif;
+}
+.header { display: flex; align-items: center; justify-content: space-between; margin-bottom: 8px; }
+.title { margin: 0; font-size: 16px; font-weight: 700; color: #1f2937; }
+.close { border: none; background: transparent; color: #6b7280; font-size: 20px; line-height: 1; cursor: pointer; }
+.close:hover { color: #111827; }
+.summary { margin: 0; white-space: pre-wrap; }
+";
+
+thread_local! {
+	// set once the overlay app is mounted; every later toggle is sent to it
+	static OVERLAY: RefCell<Option<UnboundedSender<String>>> = const { RefCell::new(None) };
+	// handed to the overlay app when it mounts
+	static PENDING: RefCell<Option<UnboundedReceiver<String>>> = const { RefCell::new(None) };
+}
+
+/// Shows `summary` in a floating panel on the page, or hides the panel if it is already showing that summary.
+///
+/// The first call mounts a Dioxus app into a closed shadow root attached to a host element on the page.
+pub fn toggle(summary: String) -> Result<(), JsValue> {
+	let sent = OVERLAY.with_borrow(|overlay| overlay.as_ref().is_some_and(|sender| sender.unbounded_send(summary.clone()).is_ok()));
+	if sent {
+		return Ok(());
+	}
+
+	let document = window().and_then(|window| window.document()).ok_or_else(|| JsValue::from_str("no document"))?;
+	let root = mount_point(&document)?;
+	let (sender, receiver) = unbounded();
+	sender.unbounded_send(summary).map_err(|e| JsValue::from_str(&e.to_string()))?;
+	OVERLAY.set(Some(sender));
+	PENDING.set(Some(receiver));
+	launch_cfg(Overlay, Config::new().rootelement(root));
+	Ok(())
+}
+
+// a host element appended to the page, with a shadow root holding the overlay's styles and the element Dioxus renders into
+fn mount_point(document: &Document) -> Result<web_sys::Element, JsValue> {
+	let host = document.create_element("div")?;
+	host.set_id(HOST_ID);
+	document.document_element().ok_or_else(|| JsValue::from_str("no document element"))?.append_child(&host)?;
+	let shadow_root = host.attach_shadow(&ShadowRootInit::new(ShadowRootMode::Closed))?;
+	adopt_styles(document, &shadow_root)?;
+	let root = document.create_element("div")?;
+	shadow_root.append_child(&root)?;
+	Ok(root)
+}
+
+fn adopt_styles(document: &Document, shadow_root: &ShadowRoot) -> Result<(), JsValue> {
+	let adopted = CssStyleSheet::new().and_then(|sheet| {
+		sheet.replace_sync(OVERLAY_CSS)?;
+		Reflect::set(shadow_root, &"adoptedStyleSheets".into(), &Array::of1(&sheet))
+	});
+	// Firefox doesn't let content scripts adopt sheets constructed in their own sandbox, so fall back to a `<style>` element there
+	if adopted.is_err() {
+		let style = document.create_element("style")?;
+		style.set_text_content(Some(OVERLAY_CSS));
+		shadow_root.append_child(&style)?;
+	}
+	Ok(())
+}
+
+#[component]
+fn Overlay() -> Element {
+	// `None` while the panel is hidden
+	let mut summary = use_signal(|| None::<String>);
+
+	use_future(move || async move {
+		let Some(mut toggles) = PENDING.take() else {
+			return;
+		};
+		while let Some(next) = toggles.next().await {
+			let hide = summary.peek().as_deref() == Some(next.as_str());
+			summary.set(if hide { None } else { Some(next) });
+		}
+	});
+
+	rsx! {
+		if let Some(summary_text) = summary() {
+			div { class: "panel",
+				div { class: "header",
+					h2 { class: "title", "Page summary" }
+					button {
+						class: "close",
+						title: "Close",
+						onclick: move |_| summary.set(None),
+						"×"
+					}
+				}
+				p { class: "summary", "{summary_text}" }
+			}
+		}
+	}
+}
+
+/// Removes an overlay left behind by a previous copy of the content script, e.g. from before the extension was reloaded.
+pub fn remove_stale_host() {
+	if let Some(host) = window().and_then(|window| window.document()).and_then(|document| document.get_element_by_id(HOST_ID)) {
+		host.remove();
+	}
+}
@@ -0,0 +1,64 @@
+use content::extract::{Article, extract_article};
+
+const NEWS_ARTICLE: &str = include_str!("fixtures/news_article.html");
+const BLOG_POST: &str = include_str!("fixtures/blog_post.html");
+const NO_PARAGRAPHS: &str = include_str!("fixtures/no_paragraphs.html");
+
+#[test]
+fn finds_the_article_body_and_drops_page_furniture() {
+	let article = extract_article(NEWS_ARTICLE);
+	assert!(article.text.starts_with("Developers who build browser extensions"), "{}", article.text);
+	assert!(article.text.contains("catches whole classes of bugs"));
+	for boilerplate in
+		["World", "cookie policy", "Share on Twitter", "Advertisement", "beginner's guide", "gardening", "Great article", "Copyright", "window.analytics"]
+	{
+		assert!(!article.text.contains(boilerplate), "`{boilerplate}` leaked into:\n{}", article.text);
+	}
+}
+
+#[test]
+fn paragraphs_are_separated_by_blank_lines() {
+	let article = extract_article(NEWS_ARTICLE);
+	let paragraphs = article.text.split("\n\n").collect::<Vec<_>>();
+	assert_eq!(paragraphs.len(), 3, "{paragraphs:#?}");
+	assert!(paragraphs.iter().all(|paragraph| !paragraph.contains('\n') && paragraph.trim() == *paragraph));
+}
+
+#[test]
+fn title_drops_the_site_name_and_byline_comes_from_meta() {
+	let article = extract_article(NEWS_ARTICLE);
+	assert_eq!(article.title.as_deref(), Some("Rust compiles to WebAssembly in the browser"));
+	assert_eq!(article.byline.as_deref(), Some("Ada Lovelace"));
+	assert_eq!(article.to_plain_text().matches("Rust compiles to WebAssembly").count(), 1);
+}
+
+#[test]
+fn related_siblings_are_joined_and_link_lists_skipped() {
+	let article = extract_article(BLOG_POST);
+	assert!(article.text.contains("humbling thing to admit"));
+	assert!(article.text.ends_with("anyone starting out today."), "{}", article.text);
+	assert!(!article.text.contains("Archive"));
+	assert!(!article.text.contains("debugging"));
+}
+
+#[test]
+fn short_titles_are_kept_whole_and_bylines_come_from_markup() {
+	let article = extract_article(BLOG_POST);
+	assert_eq!(article.title.as_deref(), Some("Notes"));
+	assert_eq!(article.byline.as_deref(), Some("By Grace Hopper"));
+}
+
+#[test]
+fn pages_without_paragraphs_fall_back_to_the_body() {
+	let article = extract_article(NO_PARAGRAPHS);
+	assert_eq!(article.title, None);
+	assert_eq!(article.text, "Short page\n\nwith a line break");
+}
+
+#[test]
+fn plain_text_puts_the_title_and_byline_first() {
+	let article = Article { title: Some("Title".to_owned()), byline: Some("By Someone".to_owned()), text: "Body.".to_owned() };
+	assert_eq!(article.to_plain_text(), "Title\nBy Someone\n\nBody.");
+	assert_eq!(Article { text: "Body.".to_owned(), ..Article::default() }.to_plain_text(), "Body.");
+	assert_eq!(extract_article("").to_plain_text(), "");
+}
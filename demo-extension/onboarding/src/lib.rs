@@ -0,0 +1,118 @@
+use common::{CONFIG_KEY, Config, i18n::use_locale_provider, theme::use_theme};
+use dioxus::prelude::*;
+use wasm_bindgen::prelude::*;
+use webext_api::{Permission, PermissionSet, hooks::use_ext_storage};
+
+#[wasm_bindgen]
+pub fn main() {
+	dioxus::logger::initialize_default();
+	dioxus::launch(App);
+}
+
+// what the background falls back to while the server URL is left empty
+const DEFAULT_SERVER_URL: &str = env!("SERVER_URL");
+
+const INPUT_CLASS: &str = "w-full px-3 py-2 border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-700 text-gray-900 dark:text-white rounded-md shadow-sm focus:outline-none focus:ring-blue-500 focus:border-blue-500";
+
+/// The page the background opens in a new tab right after the extension is installed: the settings a first summary needs, and
+/// the optional `notifications` permission, which the browser only grants from a click on a page like this one.
+#[component]
+fn App() -> Element {
+	let browser = use_hook(|| webext_api::init().expect("the onboarding page only runs inside an extension"));
+	let mut config = use_ext_storage::<Config>(browser.storage().sync(), CONFIG_KEY);
+	use_theme(use_memo(move || config.read().theme));
+	let locale = use_locale_provider(&browser, use_memo(move || config.read().language));
+	let permissions = use_hook(|| browser.permissions());
+	let runtime = use_hook(|| browser.runtime());
+	let notifications = PermissionSet::permissions(&[Permission::Notifications]);
+	// whether the permission is granted, `None` until the browser answered
+	let mut notifications_granted = use_signal(|| None::<bool>);
+	use_future({
+		to_owned![permissions, notifications];
+		move || {
+			to_owned![permissions, notifications];
+			async move {
+				match permissions.contains(&notifications).await {
+					Ok(granted) => notifications_granted.set(Some(granted)),
+					Err(e) => warn!("could not check the notifications permission: {}", e),
+				}
+			}
+		}
+	});
+
+	rsx! {
+		div { class: "max-w-xl mx-auto mt-10 p-6 bg-white dark:bg-gray-800 rounded-lg shadow-md font-sans",
+			h1 { class: "text-2xl font-bold text-gray-800 dark:text-gray-100 mb-2", {locale.t("onboardingTitle")} }
+			p { class: "mb-6 text-gray-600 dark:text-gray-300", {locale.t("onboardingIntro")} }
+
+			ol { class: "space-y-6 list-decimal pl-5 text-gray-700 dark:text-gray-200",
+				li {
+					label { class: "block text-base font-medium mb-1", r#for: "server_url", {locale.t("serverUrl")} }
+					p { class: "mb-2 text-sm text-gray-500 dark:text-gray-400", {locale.t("onboardingServerHint")} }
+					input {
+						class: INPUT_CLASS,
+						id: "server_url",
+						r#type: "url",
+						placeholder: DEFAULT_SERVER_URL,
+						value: "{config().server_url}",
+						oninput: move |evt| config.write().server_url = evt.value(),
+					}
+					p { class: "mt-1 text-sm text-gray-500 dark:text-gray-400", {locale.t_with("serverUrlDefault", &[DEFAULT_SERVER_URL])} }
+				}
+				li {
+					label { class: "block text-base font-medium mb-1", r#for: "auth_token", {locale.t("authToken")} }
+					p { class: "mb-2 text-sm text-gray-500 dark:text-gray-400", {locale.t("onboardingTokenHint")} }
+					input {
+						class: INPUT_CLASS,
+						id: "auth_token",
+						r#type: "password",
+						value: "{config().auth_token}",
+						oninput: move |evt| config.write().auth_token = evt.value(),
+					}
+				}
+				li {
+					p { class: "text-base font-medium mb-1", {locale.t("enableNotifications")} }
+					p { class: "mb-2 text-sm text-gray-500 dark:text-gray-400", {locale.t("onboardingNotificationsHint")} }
+					if notifications_granted() == Some(true) {
+						p { class: "text-sm font-medium text-green-600", {locale.t("notificationsAllowed")} }
+					} else {
+						button {
+							class: "px-4 py-2 text-sm font-semibold text-white bg-blue-600 hover:bg-blue-700 rounded-md border-none cursor-pointer",
+							// the request has to be the first thing the click does, or the browser no longer counts it as a user gesture
+							onclick: move |_| {
+									to_owned![permissions, notifications];
+									async move {
+											match permissions.request(&notifications).await {
+													Ok(granted) => {
+															notifications_granted.set(Some(granted));
+															config.write().enable_notifications = granted;
+													},
+													Err(e) => warn!("could not request the notifications permission: {}", e),
+											}
+									}
+							},
+							{locale.t("allowNotifications")}
+						}
+					}
+				}
+			}
+
+			p { class: "mt-8 text-sm text-gray-600 dark:text-gray-300",
+				{locale.t("onboardingDone")}
+				" "
+				button {
+					class: "text-blue-600 hover:underline font-semibold bg-transparent border-none p-0 cursor-pointer",
+					onclick: move |_| {
+							to_owned![runtime];
+							async move {
+									if let Err(e) = runtime.open_options_page().await {
+											warn!("could not open the options page: {}", e);
+									}
+							}
+					},
+					{locale.t("extensionOptions")}
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,12 @@
+(async () => {
+  try {
+    const src = chrome.runtime.getURL("onboarding.js");
+    const wasmPath = chrome.runtime.getURL("onboarding_bg.wasm");
+    const wasmModule = await import(src);
+    if (!wasmModule.default) throw new Error("WASM entry point not found!");
+    await wasmModule.default({ module_or_path: wasmPath });
+    wasmModule.main();
+  } catch (err) {
+    console.error("Failed to initialize WASM module:", err);
+  }
+})();
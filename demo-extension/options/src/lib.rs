@@ -1,6 +1,13 @@
+use common::{CONFIG_KEY, Config, Language, SiteMode, SummaryStyle, Theme, i18n::use_locale_provider, theme::use_theme};
 use dioxus::prelude::*;
-use gloo_timers::future::TimeoutFuture;
+use futures::StreamExt;
 use wasm_bindgen::prelude::*;
+use webext_api::hooks::use_ext_storage;
+use webext_api::match_pattern::MatchPattern;
+use webext_api::{DownloadOptions, Permission, PermissionSet};
+
+// what the background falls back to while the server URL is left empty
+const DEFAULT_SERVER_URL: &str = env!("SERVER_URL");
 
 #[wasm_bindgen]
 pub fn main() {
@@ -10,70 +17,314 @@ pub fn main() {
 
 #[component]
 fn App() -> Element {
-	let mut enable_notifications = use_signal(|| true);
-	let mut summary_style = use_signal(|| "bullets".to_string());
-	let mut status_message = use_signal(String::new);
-
-	let on_save = move |_| async move {
-		status_message.set("Settings saved successfully!".to_string());
-		TimeoutFuture::new(2_000).await;
-		status_message.set("".to_string());
+	let browser = use_hook(|| webext_api::init().expect("the options page only runs inside an extension"));
+	// loaded from storage.sync on mount, and written back shortly after every edit
+	let mut config = use_ext_storage::<Config>(browser.storage().sync(), CONFIG_KEY);
+	use_theme(use_memo(move || config.read().theme));
+	let locale = use_locale_provider(&browser, use_memo(move || config.read().language));
+	let downloads = use_hook(|| browser.downloads());
+	let permissions = use_hook(|| browser.permissions());
+	// the outcome of the last import, or of an export that failed, `Err` holding what went wrong
+	let mut transfer_status = use_signal(|| None::<Result<(), String>>);
+	// the pattern being typed into the site rules, and why it was rejected if it was
+	let mut new_pattern = use_signal(String::new);
+	let mut pattern_error = use_signal(|| None::<String>);
+	let mut add_pattern = move || {
+		let parsed = MatchPattern::parse(new_pattern.peek().trim());
+		match parsed {
+			Ok(pattern) => {
+				if !config.peek().site_rules.patterns.contains(&pattern) {
+					config.write().site_rules.patterns.push(pattern);
+				}
+				new_pattern.set(String::new());
+				pattern_error.set(None);
+			},
+			Err(e) => pattern_error.set(Some(e.to_string())),
+		}
 	};
 
 	rsx! {
-		div { class: "max-w-md mx-auto mt-10 p-6 bg-white rounded-lg shadow-md font-sans",
-			h1 { class: "text-2xl font-bold text-gray-800 mb-6", "Extension Settings" }
+		div { class: "max-w-md mx-auto mt-10 p-6 bg-white dark:bg-gray-800 rounded-lg shadow-md font-sans",
+			h1 { class: "text-2xl font-bold text-gray-800 dark:text-gray-100 mb-6", {locale.t("settingsTitle")} }
+
+			div { class: "mb-4 py-2",
+				label {
+					class: "block text-base font-medium text-gray-700 dark:text-gray-200 mb-2",
+					r#for: "server_url",
+					{locale.t("serverUrl")}
+				}
+				input {
+					class: "w-full px-3 py-2 border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-700 text-gray-900 dark:text-white rounded-md shadow-sm focus:outline-none focus:ring-blue-500 focus:border-blue-500",
+					id: "server_url",
+					r#type: "url",
+					placeholder: DEFAULT_SERVER_URL,
+					value: "{config().server_url}",
+					oninput: move |evt| config.write().server_url = evt.value(),
+				}
+				p { class: "mt-1 text-sm text-gray-500 dark:text-gray-400", {locale.t_with("serverUrlDefault", &[DEFAULT_SERVER_URL])} }
+			}
+
+			div { class: "mb-4 py-2",
+				label {
+					class: "block text-base font-medium text-gray-700 dark:text-gray-200 mb-2",
+					r#for: "auth_token",
+					{locale.t("authToken")}
+				}
+				input {
+					class: "w-full px-3 py-2 border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-700 text-gray-900 dark:text-white rounded-md shadow-sm focus:outline-none focus:ring-blue-500 focus:border-blue-500",
+					id: "auth_token",
+					r#type: "password",
+					value: "{config().auth_token}",
+					oninput: move |evt| config.write().auth_token = evt.value(),
+				}
+			}
 
 			div { class: "flex items-center justify-between mb-4 py-2",
 				label {
-					class: "text-base font-medium text-gray-700",
+					class: "text-base font-medium text-gray-700 dark:text-gray-200",
 					r#for: "enable_notifications",
-					"Enable Notifications"
+					{locale.t("enableNotifications")}
 				}
 				label { class: "relative inline-flex items-center cursor-pointer",
 					input {
 						class: "sr-only peer",
 						id: "enable_notifications",
 						r#type: "checkbox",
-						checked: enable_notifications,
-						oninput: move |evt| enable_notifications.set(evt.value() == "true"),
+						checked: config().enable_notifications,
+						oninput: move |evt| {
+								let enabled = evt.value() == "true";
+								config.write().enable_notifications = enabled;
+								let permissions = permissions.clone();
+								async move {
+										// an optional permission, asked for while the click still counts as a user gesture
+										if enabled {
+												match permissions.request(&PermissionSet::permissions(&[Permission::Notifications])).await {
+														Ok(granted) => config.write().enable_notifications = granted,
+														Err(e) => warn!("could not request the notifications permission: {}", e),
+												}
+										}
+								}
+						},
 					}
 					div { class: "w-11 h-6 bg-gray-200 rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all peer-checked:bg-blue-600" }
 				}
 			}
 
+			div { class: "mb-4 py-2",
+				div { class: "flex items-center justify-between",
+					label {
+						class: "text-base font-medium text-gray-700 dark:text-gray-200",
+						r#for: "report_errors",
+						{locale.t("reportErrors")}
+					}
+					label { class: "relative inline-flex items-center cursor-pointer",
+						input {
+							class: "sr-only peer",
+							id: "report_errors",
+							r#type: "checkbox",
+							checked: config().report_errors,
+							oninput: move |evt| config.write().report_errors = evt.value() == "true",
+						}
+						div { class: "w-11 h-6 bg-gray-200 rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-0.5 after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all peer-checked:bg-blue-600" }
+					}
+				}
+				p { class: "mt-1 text-sm text-gray-500 dark:text-gray-400", {locale.t("reportErrorsHint")} }
+			}
+
 			div { class: "mb-6 py-2",
 				label {
-					class: "block text-base font-medium text-gray-700 mb-2",
+					class: "block text-base font-medium text-gray-700 dark:text-gray-200 mb-2",
 					r#for: "summary_style",
-					"Summarization Style"
+					{locale.t("summaryStyle")}
 				}
 				select {
-					class: "w-full px-3 py-2 border border-gray-300 rounded-md shadow-sm focus:outline-none focus:ring-blue-500 focus:border-blue-500",
+					class: "w-full px-3 py-2 border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-700 text-gray-900 dark:text-white rounded-md shadow-sm focus:outline-none focus:ring-blue-500 focus:border-blue-500",
 					id: "summary_style",
-					onchange: move |evt| summary_style.set(evt.value()),
+					onchange: move |evt| {
+							if let Ok(style) = evt.value().parse() {
+									config.write().summary_style = style;
+							}
+					},
 					option {
-						value: "bullets",
-						selected: summary_style() == "bullets",
-						"Bullet Points"
+						value: "{SummaryStyle::Bullets}",
+						selected: config().summary_style == SummaryStyle::Bullets,
+						{locale.t("summaryStyleBullets")}
 					}
 					option {
-						value: "paragraph",
-						selected: summary_style() == "paragraph",
-						"Single Paragraph"
+						value: "{SummaryStyle::Paragraph}",
+						selected: config().summary_style == SummaryStyle::Paragraph,
+						{locale.t("summaryStyleParagraph")}
+					}
+				}
+			}
+
+			div { class: "mb-6 py-2",
+				label {
+					class: "block text-base font-medium text-gray-700 dark:text-gray-200 mb-2",
+					r#for: "theme",
+					{locale.t("theme")}
+				}
+				select {
+					class: "w-full px-3 py-2 border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-700 text-gray-900 dark:text-white rounded-md shadow-sm focus:outline-none focus:ring-blue-500 focus:border-blue-500",
+					id: "theme",
+					onchange: move |evt| {
+							if let Ok(theme) = evt.value().parse() {
+									config.write().theme = theme;
+							}
+					},
+					for (theme , label) in [(Theme::System, "themeSystem"), (Theme::Light, "themeLight"), (Theme::Dark, "themeDark")] {
+						option { value: "{theme}", selected: config().theme == theme, {locale.t(label)} }
+					}
+				}
+			}
+
+			div { class: "mb-6 py-2",
+				label {
+					class: "block text-base font-medium text-gray-700 dark:text-gray-200 mb-2",
+					r#for: "language",
+					{locale.t("language")}
+				}
+				select {
+					class: "w-full px-3 py-2 border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-700 text-gray-900 dark:text-white rounded-md shadow-sm focus:outline-none focus:ring-blue-500 focus:border-blue-500",
+					id: "language",
+					onchange: move |evt| {
+							if let Ok(language) = evt.value().parse() {
+									config.write().language = language;
+							}
+					},
+					for (language , label) in [(Language::Auto, "languageAuto"), (Language::En, "languageEn"), (Language::De, "languageDe")] {
+						option { value: "{language}", selected: config().language == language, {locale.t(label)} }
 					}
 				}
 			}
 
-			button {
-				class: "w-full px-4 py-2 text-white font-semibold rounded-md shadow-sm transition-colors duration-200 ease-in-out bg-blue-600 hover:bg-blue-700",
-				onclick: on_save,
-				"Save Settings"
+			div { class: "mb-6 py-2",
+				label {
+					class: "block text-base font-medium text-gray-700 dark:text-gray-200 mb-2",
+					r#for: "site_mode",
+					{locale.t("siteRules")}
+				}
+				select {
+					class: "w-full px-3 py-2 border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-700 text-gray-900 dark:text-white rounded-md shadow-sm focus:outline-none focus:ring-blue-500 focus:border-blue-500",
+					id: "site_mode",
+					onchange: move |evt| {
+							if let Ok(mode) = evt.value().parse() {
+									config.write().site_rules.mode = mode;
+							}
+					},
+					for (mode , label) in [(SiteMode::Deny, "siteModeDeny"), (SiteMode::Allow, "siteModeAllow")] {
+						option { value: "{mode}", selected: config().site_rules.mode == mode, {locale.t(label)} }
+					}
+				}
+				ul { class: "mt-2 text-sm text-gray-700 dark:text-gray-200",
+					for (index , pattern) in config().site_rules.patterns.into_iter().enumerate() {
+						li { key: "{pattern}", class: "flex items-center justify-between py-1",
+							code { "{pattern}" }
+							button {
+								class: "text-xs text-red-600 hover:underline bg-transparent border-none cursor-pointer",
+								onclick: move |_| {
+										config.write().site_rules.patterns.remove(index);
+								},
+								{locale.t("delete")}
+							}
+						}
+					}
+				}
+				div { class: "flex gap-2 mt-2",
+					input {
+						class: "flex-1 px-3 py-2 border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-700 text-gray-900 dark:text-white rounded-md shadow-sm focus:outline-none focus:ring-blue-500 focus:border-blue-500",
+						placeholder: "*://*.example.com/*",
+						value: "{new_pattern}",
+						oninput: move |evt| new_pattern.set(evt.value()),
+						onkeydown: move |evt| {
+								if evt.key() == Key::Enter {
+										add_pattern();
+								}
+						},
+					}
+					button {
+						class: "px-3 py-2 text-sm font-medium text-gray-700 dark:text-gray-200 bg-gray-200 dark:bg-gray-700 hover:bg-gray-300 dark:hover:bg-gray-600 rounded-md",
+						onclick: move |_| add_pattern(),
+						{locale.t("addPattern")}
+					}
+				}
+				if let Some(e) = pattern_error() {
+					p { class: "mt-2 text-sm text-red-600", {locale.t_with("invalidPattern", &[&e])} }
+				}
 			}
 
-			if !status_message().is_empty() {
-				p { class: "mt-4 text-sm text-center text-green-600", "{status_message}" }
+			div { class: "mb-6 py-2",
+				div { class: "flex gap-2",
+					button {
+						class: "flex-1 px-3 py-2 text-sm font-medium text-gray-700 dark:text-gray-200 bg-gray-200 dark:bg-gray-700 hover:bg-gray-300 dark:hover:bg-gray-600 rounded-md",
+						onclick: move |_| {
+								let downloads = downloads.clone();
+								let json = config.read().to_export_json();
+								async move {
+										let url = match json_object_url(&json) {
+												Ok(url) => url,
+												Err(e) => {
+														transfer_status.set(Some(Err(format!("{e:?}"))));
+														return;
+												},
+										};
+										let options = DownloadOptions::new(&url).filename("ai-page-summarizer-settings.json").save_as(true);
+										match downloads.download(&options).await {
+												// Firefox only reads the blob once the save dialog is answered, so the URL is kept until the download ends
+												Ok(id) => {
+														if let Ok(mut progress) = downloads.watch_download(id) {
+																while progress.next().await.is_some() {}
+														}
+												},
+												Err(e) => transfer_status.set(Some(Err(e.to_string()))),
+										}
+										let _ = web_sys::Url::revoke_object_url(&url);
+								}
+						},
+						{locale.t("exportSettings")}
+					}
+					label { class: "flex-1 px-3 py-2 text-sm font-medium text-center text-gray-700 dark:text-gray-200 bg-gray-200 dark:bg-gray-700 hover:bg-gray-300 dark:hover:bg-gray-600 rounded-md cursor-pointer",
+						{locale.t("importSettings")}
+						input {
+							class: "hidden",
+							r#type: "file",
+							accept: "application/json,.json",
+							onchange: move |evt| async move {
+									let Some(file) = evt.files().into_iter().next() else {
+											return;
+									};
+									let imported = match file.read_string().await {
+											Ok(json) => config.peek().merged_with_json(&json).map_err(|e| e.to_string()),
+											Err(e) => Err(e.to_string()),
+									};
+									if let Ok(imported) = &imported {
+											config.set(imported.clone());
+									}
+									transfer_status.set(Some(imported.map(drop)));
+							},
+						}
+					}
+				}
+				match transfer_status() {
+						Some(Ok(())) => rsx! {
+							p { class: "mt-2 text-sm text-green-600", {locale.t("settingsImported")} }
+						},
+						Some(Err(e)) => rsx! {
+							p { class: "mt-2 text-sm text-red-600", {locale.t_with("transferFailed", &[&e])} }
+						},
+						None => rsx! {},
+				}
 			}
+
+			p { class: "text-sm text-center text-gray-500 dark:text-gray-400", {locale.t("savedAutomatically")} }
 		}
 	}
 }
+
+// an object URL for the exported settings; `downloads.download` refuses `data:` URLs in Firefox
+fn json_object_url(json: &str) -> Result<String, JsValue> {
+	let options = web_sys::BlobPropertyBag::new();
+	options.set_type("application/json");
+	let blob = web_sys::Blob::new_with_str_sequence_and_options(&js_sys::Array::of1(&json.into()), &options)?;
+	web_sys::Url::create_object_url_with_blob(&blob)
+}
@@ -0,0 +1,79 @@
+use chrono::Local;
+use common::{HISTORY_KEY, HistoryEntry, i18n::use_locale, markdown::plain_text};
+use dioxus::prelude::*;
+use webext_api::hooks::use_ext_storage;
+
+const PAGE_SIZE: usize = 5;
+
+/// The summaries the background saved to `storage.local`, newest first, a page at a time.
+#[component]
+pub fn HistoryView() -> Element {
+	let locale = use_locale();
+	let storage = use_hook(|| webext_api::init().expect("the popup only runs inside an extension").storage());
+	// summaries finished while the popup is open arrive through `storage.onChanged`, deletions are written back
+	let mut history = use_ext_storage::<Vec<HistoryEntry>>(storage.local(), HISTORY_KEY);
+	let mut page = use_signal(|| 0_usize);
+	let page_count = use_memo(move || history.read().len().div_ceil(PAGE_SIZE).max(1));
+	// deleting the last entry on the last page moves back a page
+	let current_page = use_memo(move || page().min(page_count() - 1));
+	let entries = use_memo(move || history.read().iter().skip(current_page() * PAGE_SIZE).take(PAGE_SIZE).cloned().collect::<Vec<_>>());
+
+	if history.read().is_empty() {
+		return rsx! {
+			p { class: "p-3 text-sm text-gray-500 dark:text-gray-400", {locale.t("historyEmpty")} }
+		};
+	}
+
+	rsx! {
+		ul { class: "divide-y divide-gray-200 dark:divide-gray-700 border border-gray-200 dark:border-gray-700 rounded-md",
+			for entry in entries() {
+				li { key: "{entry.id}", class: "relative p-3 text-sm",
+					a {
+						class: "block pr-6 font-semibold text-gray-800 dark:text-gray-100 truncate hover:underline",
+						href: "{entry.url}",
+						target: "_blank",
+						title: "{entry.url}",
+						if entry.title.is_empty() {
+							"{entry.url}"
+						} else {
+							"{entry.title}"
+						}
+					}
+					p { class: "text-xs text-gray-500 dark:text-gray-400", {entry.created_at.with_timezone(&Local).format("%b %e, %H:%M").to_string()} }
+					p { class: "mt-1 text-gray-700 dark:text-gray-300 line-clamp-3 whitespace-pre-line", {plain_text(&entry.summary)} }
+					button {
+						class: "absolute top-2 right-2 px-1 text-gray-400 hover:text-red-600 bg-transparent border-none cursor-pointer",
+						title: locale.t("delete"),
+						onclick: move |_| history.write().retain(|other| other.id != entry.id),
+						"×"
+					}
+				}
+			}
+		}
+		div { class: "flex items-center justify-between mt-3 text-sm",
+			div { class: "flex items-center gap-2",
+				button {
+					class: "px-2 py-1 rounded-md bg-gray-200 dark:bg-gray-700 hover:bg-gray-300 dark:hover:bg-gray-600 disabled:opacity-50 disabled:cursor-not-allowed",
+					disabled: current_page() == 0,
+					onclick: move |_| page.set(current_page().saturating_sub(1)),
+					{locale.t("previousPage")}
+				}
+				span { class: "text-gray-600 dark:text-gray-300", "{current_page() + 1} / {page_count}" }
+				button {
+					class: "px-2 py-1 rounded-md bg-gray-200 dark:bg-gray-700 hover:bg-gray-300 dark:hover:bg-gray-600 disabled:opacity-50 disabled:cursor-not-allowed",
+					disabled: current_page() + 1 >= page_count(),
+					onclick: move |_| page.set(current_page() + 1),
+					{locale.t("nextPage")}
+				}
+			}
+			button {
+				class: "px-2 py-1 text-red-600 hover:underline bg-transparent border-none cursor-pointer",
+				onclick: move |_| {
+						history.set(Vec::new());
+						page.set(0);
+				},
+				{locale.t("clearAll")}
+			}
+		}
+	}
+}
@@ -1,150 +1,320 @@
-use common::{AppError, ExtMessage};
+use std::pin::pin;
+
+use common::{
+	AppError, CONFIG_KEY, Config, Envelope, ExtMessage, SUMMARIZE_PORT, Source, SummaryOrigin,
+	i18n::{use_locale, use_locale_provider},
+	markdown::Markdown,
+	queue::QueueProgress,
+	theme::use_theme,
+};
 use dioxus::{
 	prelude::*,
-	web::{Config, launch::launch_cfg},
+	web::{Config as WebConfig, launch::launch_cfg},
+};
+use futures::{
+	StreamExt,
+	future::{Either, select},
 };
+use gloo_timers::future::TimeoutFuture;
 use wasm_bindgen::prelude::*;
-use web_sys::js_sys;
+use webext_api::{api::Runtime, hooks::use_ext_storage};
+
+use crate::history::HistoryView;
+
+mod history;
 
 #[derive(Clone, PartialEq)]
 enum AppState {
 	Idle,
 	Loading,
-	Success(String),
+	/// The server couldn't be reached and the background is trying again.
+	Retrying {
+		attempt: u32,
+		max_attempts: u32,
+	},
+	/// The part of the summary that has arrived so far.
+	Streaming(String),
+	/// The whole summary, and where it came from.
+	Success {
+		summary: String,
+		origin: SummaryOrigin,
+	},
 	Error(AppError),
 }
 
 #[wasm_bindgen]
 pub fn main() {
 	dioxus::logger::initialize_default();
-	launch_cfg(App, Config::default());
-}
-
-fn start_message_listener(mut app_state: Signal<AppState>) {
-	let listener = Closure::wrap(Box::new(move |message: JsValue, _sender: web_extensions_sys::MessageSender, _send_response: js_sys::Function| {
-		info!("[popup] Received message: {:?}", message);
-		match serde_wasm_bindgen::from_value::<ExtMessage>(message) {
-			Ok(msg) => match msg {
-				ExtMessage::SummarizeResponse(s) => app_state.set(AppState::Success(s)),
-				ExtMessage::Error(e) => app_state.set(AppState::Error(e)),
-				_ => {},
-			},
-			Err(e) => {
-				let err_msg = format!("Failed to deserialize message: {}", e);
-				error!("{}", err_msg);
-				app_state.set(AppState::Error(AppError::ExtensionError(err_msg)));
-			},
-		}
-	}) as Box<dyn FnMut(JsValue, web_extensions_sys::MessageSender, js_sys::Function)>);
-	web_extensions_sys::chrome().runtime().on_message().add_listener(listener.as_ref().unchecked_ref());
-	listener.forget();
+	launch_cfg(App, WebConfig::default());
 }
 
 #[component]
 fn App() -> Element {
 	let mut app_state = use_signal(|| AppState::Idle);
+	let browser = use_hook(|| webext_api::init().expect("the popup only runs inside an extension"));
+	let runtime = browser.runtime();
+	// follows edits made on the options page while the popup is open; the popup itself only changes the site rules
+	let mut config = use_ext_storage::<Config>(browser.storage().sync(), CONFIG_KEY);
+	use_theme(use_memo(move || config.read().theme));
+	let locale = use_locale_provider(&browser, use_memo(move || config.read().language));
 
-	use_effect(move || {
-		start_message_listener(app_state);
+	// seconds until the server accepts another request after answering `RateLimited`
+	let mut retry_in = use_signal(|| 0_u64);
+	let is_loading = use_memo(move || matches!(app_state(), AppState::Loading | AppState::Retrying { .. } | AppState::Streaming(_)));
+	let mut show_history = use_signal(|| false);
+	let side_panel = use_hook(|| browser.side_panel().kind().map(|_| browser.side_panel()));
+	// looked up ahead of time: `sidePanel.open` only works while the click's user gesture is still active
+	let active_tab = use_resource({
+		let tabs = browser.tabs();
+		move || {
+			let tabs = tabs.clone();
+			async move { tabs.get_active().await.ok() }
+		}
+	});
+	let active_url = use_memo(move || active_tab.read().clone().flatten().and_then(|tab| tab.url.clone()));
+	// `None` until the active tab is known
+	let site_allowed = use_memo(move || active_url().map(|url| config.read().site_rules.allows(&url)));
+	let can_summarize = use_memo(move || !is_loading() && retry_in() == 0 && site_allowed() != Some(false));
+	// starts a summary of the page for `SummarizeRequest`, or of the selected text for `SummarizeSelectionRequest`
+	let summarize = use_callback(move |payload: ExtMessage| {
+		let runtime = runtime.clone();
+		spawn(async move {
+			app_state.set(AppState::Loading);
+			if let Err(e) = request_summary(&runtime, payload, app_state).await {
+				error!("summarize failed: {}", e);
+				if let AppError::RateLimited { retry_after_secs } = e {
+					retry_in.set(retry_after_secs);
+					spawn(count_down(retry_in));
+				}
+				app_state.set(AppState::Error(e));
+			}
+		});
 	});
-
-	let is_loading = use_memo(move || matches!(app_state(), AppState::Loading));
 
 	rsx! {
-		div { class: "w-250 h-250 p-4 bg-white",
-			h1 { class: "text-lg font-bold text-center text-gray-800 mb-4", "AI Page Summarizer" }
-			button {
-				class: "w-full px-4 py-2 text-white font-semibold rounded-md shadow-sm transition-colors duration-200 ease-in-out bg-blue-600 hover:bg-blue-700 disabled:bg-gray-400 disabled:cursor-not-allowed",
-				disabled: is_loading,
-				onclick: move |_| async move {
-						app_state.set(AppState::Loading);
-						match serde_wasm_bindgen::to_value(&ExtMessage::SummarizeRequest) {
-								Ok(message) => {
-										match web_extensions_sys::chrome()
-												.runtime()
-												.send_message(None, &message, None)
-												.await
-										{
-												Ok(_) => info!("SummarizeRequest message sent successfully"),
-												Err(e) => {
-														let error_str = e
-																.as_string()
-																.unwrap_or_else(|| "Unknown JavaScript error".to_string());
-														error!("Error sending message: {}", error_str);
-														app_state
-																.set(AppState::Error(AppError::ExtensionError(error_str)));
-												}
-										}
-								}
-								Err(e) => {
-										let err_msg = format!("Failed to serialize message: {}", e);
-										error!("{}", err_msg);
-										app_state.set(AppState::Error(AppError::ExtensionError(err_msg)));
-								}
-						}
-				},
-				if is_loading() {
-					"Summarizing..."
-				} else {
-					"Summarize Page"
+		div { class: "w-250 h-250 p-4 bg-white dark:bg-gray-900",
+			h1 { class: "text-lg font-bold text-center text-gray-800 dark:text-gray-100 mb-4", {locale.t("extName")} }
+			div { class: "flex mb-4 border-b border-gray-200 dark:border-gray-700 text-sm font-medium",
+				for (label , history) in [(locale.t("tabSummarize"), false), (locale.t("tabHistory"), true)] {
+					button {
+						class: if show_history() == history { "flex-1 py-2 text-blue-600 border-b-2 border-blue-600" } else { "flex-1 py-2 text-gray-500 hover:text-gray-700 dark:text-gray-400 dark:hover:text-gray-200" },
+						onclick: move |_| show_history.set(history),
+						"{label}"
+					}
 				}
 			}
-			div { class: "relative mt-4 p-3 bg-gray-50 border border-gray-200 rounded-md min-h-[120px] text-gray-700 text-sm leading-relaxed",
-				match app_state() {
-						AppState::Idle => rsx! {
-							p { class: "text-gray-500", "Click the button to generate a summary." }
-						},
-						AppState::Loading => rsx! {
-							div { class: "absolute inset-0 flex items-center justify-center",
-								div { class: "animate-spin rounded-full h-8 w-8 border-b-2 border-blue-600" }
-							}
-						},
-						AppState::Success(summary) => rsx! {
-							SummaryView { summary }
+			if show_history() {
+				HistoryView {}
+			} else {
+				if site_allowed() == Some(false) {
+					p { class: "mb-2 text-sm text-center text-gray-600 dark:text-gray-300", {locale.t("disabledOnSite")} }
+				}
+				button {
+					class: "w-full px-4 py-2 text-white font-semibold rounded-md shadow-sm transition-colors duration-200 ease-in-out bg-blue-600 hover:bg-blue-700 disabled:bg-gray-400 disabled:cursor-not-allowed",
+					disabled: !can_summarize(),
+					onclick: move |_| summarize.call(ExtMessage::SummarizeRequest),
+					if is_loading() {
+						{locale.t("summarizing")}
+					} else if retry_in() > 0 {
+						{locale.t_with("tryAgainIn", &[&retry_in().to_string()])}
+					} else {
+						{locale.t("summarizePage")}
+					}
+				}
+				button {
+					class: "mt-2 w-full px-4 py-1.5 text-sm font-medium text-blue-600 dark:text-blue-400 border border-blue-600 dark:border-blue-400 rounded-md bg-transparent hover:bg-blue-50 dark:hover:bg-gray-800 disabled:text-gray-400 disabled:border-gray-400 disabled:cursor-not-allowed",
+					disabled: !can_summarize(),
+					onclick: move |_| summarize.call(ExtMessage::SummarizeSelectionRequest),
+					{locale.t("summarizeSelectionButton")}
+				}
+				if let (Some(url), Some(allowed)) = (active_url(), site_allowed()) {
+					button {
+						class: "mt-1 w-full text-xs text-gray-500 dark:text-gray-400 hover:underline bg-transparent border-none cursor-pointer",
+						onclick: move |_| {
+								let mut site_rules = config.peek().site_rules.clone();
+								// pages without a host, like local files, can't be listed
+								if site_rules.set_allowed(&url, !allowed) {
+										config.write().site_rules = site_rules;
+								}
 						},
-						AppState::Error(error) => rsx! {
-							p { class: "text-red-600 font-medium", "{error}" }
-							if error == AppError::MissingConfiguration {
-								p { class: "mt-2 text-sm text-gray-600",
-									"You can set them in the "
-									button {
-										class: "text-blue-600 hover:underline font-semibold bg-transparent border-none p-0 cursor-pointer",
-										onclick: move |_| web_extensions_sys::chrome().runtime().open_options_page(),
-										"extension options."
+						if allowed {
+							{locale.t("disableOnSite")}
+						} else {
+							{locale.t("enableOnSite")}
+						}
+					}
+				}
+				div { class: "relative mt-4 p-3 bg-gray-50 dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-md min-h-[120px] text-gray-700 dark:text-gray-200 text-sm leading-relaxed",
+					match app_state() {
+							AppState::Idle => rsx! {
+								p { class: "text-gray-500 dark:text-gray-400", {locale.t("idleHint")} }
+							},
+							AppState::Loading => rsx! {
+								div { class: "absolute inset-0 flex items-center justify-center",
+									div { class: "animate-spin rounded-full h-8 w-8 border-b-2 border-blue-600" }
+								}
+							},
+							AppState::Retrying { attempt, max_attempts } => rsx! {
+								div { class: "absolute inset-0 flex flex-col gap-2 items-center justify-center",
+									div { class: "animate-spin rounded-full h-8 w-8 border-b-2 border-amber-500" }
+									p { class: "text-xs text-gray-500 dark:text-gray-400",
+										{locale.t_with("retrying", &[&attempt.to_string(), &max_attempts.to_string()])}
+									}
+								}
+							},
+							AppState::Streaming(partial) => rsx! {
+								Markdown { text: partial }
+								span { class: "inline-block w-1.5 h-4 ml-0.5 align-text-bottom bg-gray-500 animate-pulse" }
+							},
+							AppState::Success { summary, origin } => rsx! {
+								SummaryView { summary, origin }
+							},
+							AppState::Error(error) => rsx! {
+								p { class: "text-red-600 font-medium", {locale.error(&error)} }
+								if error == AppError::MissingConfiguration {
+									p { class: "mt-2 text-sm text-gray-600 dark:text-gray-300",
+										{locale.t("setThemIn")}
+										" "
+										button {
+											class: "text-blue-600 hover:underline font-semibold bg-transparent border-none p-0 cursor-pointer",
+											onclick: move |_| web_extensions_sys::chrome().runtime().open_options_page(),
+											{locale.t("extensionOptions")}
+										}
 									}
 								}
+							},
+					}
+				}
+				QueueProgress { source: Source::Popup }
+			}
+			if let Some(side_panel) = side_panel {
+				button {
+					class: "mt-3 w-full text-sm text-blue-600 hover:underline bg-transparent border-none cursor-pointer",
+					onclick: move |_| {
+							let side_panel = side_panel.clone();
+							let tab_id = active_tab.read().clone().flatten().and_then(|tab| tab.id);
+							async move {
+									match side_panel.open(tab_id).await {
+											// the side panel takes over from the popup
+											Ok(()) => {
+													if let Some(window) = web_sys::window() {
+															let _ = window.close();
+													}
+											}
+											Err(e) => error!("could not open the side panel: {}", e),
+									}
 							}
-						},
+					},
+					{locale.t("openSidePanel")}
 				}
 			}
 		}
 	}
 }
 
+async fn count_down(mut seconds: Signal<u64>) {
+	while seconds() > 0 {
+		TimeoutFuture::new(1_000).await;
+		seconds -= 1;
+	}
+}
+
+// streams the summary over a port to the background, updating `app_state` with every chunk until the full summary or an error arrives
+async fn request_summary(runtime: &Runtime, payload: ExtMessage, mut app_state: Signal<AppState>) -> Result<(), AppError> {
+	let extension_error = |e: webext_api::error::ExtensionError| AppError::ExtensionError(e.to_string());
+	let port = runtime.connect(Some(SUMMARIZE_PORT)).map_err(extension_error)?;
+	let disconnected = port.on_disconnect().and_then(|on_disconnect| on_disconnect.once()).map_err(extension_error)?;
+	let mut messages = port.on_message_stream::<Envelope>().map_err(extension_error)?;
+	let request = Envelope::request(Source::Popup, payload);
+	port.post_message(&request).map_err(extension_error)?;
+
+	let receive = async {
+		while let Some(response) = messages.next().await {
+			match response.response_to(&request)? {
+				ExtMessage::SummarizeChunk(delta) => {
+					let mut partial = match app_state() {
+						AppState::Streaming(partial) => partial,
+						_ => String::new(),
+					};
+					partial.push_str(&delta);
+					app_state.set(AppState::Streaming(partial));
+				},
+				ExtMessage::SummarizeRetrying { attempt, max_attempts } => app_state.set(AppState::Retrying { attempt, max_attempts }),
+				ExtMessage::SummarizeResponse { summary, origin } => {
+					app_state.set(AppState::Success { summary, origin });
+					return Ok(());
+				},
+				_ => {},
+			}
+		}
+		Ok::<_, AppError>(())
+	};
+	let result = match select(pin!(receive), disconnected).await {
+		Either::Left((result, _)) => result,
+		Either::Right(_) => Err(AppError::ExtensionError("The background closed the connection before the summary was complete.".to_owned())),
+	};
+	port.disconnect();
+	result
+}
+
 #[component]
-fn SummaryView(summary: String) -> Element {
-	let mut copy_text = use_signal(|| "Copy".to_string());
+fn SummaryView(summary: String, origin: SummaryOrigin) -> Element {
+	let locale = use_locale();
+	// message names, looked up while rendering so they follow language changes
+	let mut copy_text = use_signal(|| "copy");
+	let mut overlay_text = use_signal(|| "showOnPage");
+	let runtime = use_hook(|| webext_api::init().expect("the popup only runs inside an extension").runtime());
 	rsx! {
-		p { "{summary}" }
-		button {
-			class: "absolute top-2 right-2 px-2 py-1 text-xs font-medium text-gray-600 bg-gray-200 hover:bg-gray-300 rounded-md transition-all",
-			onclick: move |_| {
-					to_owned![summary];
-					async move {
-							if let Some(window) = web_sys::window() {
-									let clipboard = window.navigator().clipboard();
-									if wasm_bindgen_futures::JsFuture::from(clipboard.write_text(&summary))
-											.await
-											.is_ok()
-									{
-											copy_text.set("Copied!".to_owned());
-									} else {
-											copy_text.set("Failed".to_owned());
-									}
-							}
-					}
-			},
-			"{copy_text}"
+		Markdown { text: summary.clone() }
+		match origin {
+				SummaryOrigin::Server => rsx! {},
+				SummaryOrigin::Cache => rsx! {
+					p { class: "mt-2 text-xs text-gray-500 dark:text-gray-400", {locale.t("cachedSummary")} }
+				},
+				SummaryOrigin::Offline => rsx! {
+					p { class: "mt-2 text-xs text-amber-600", {locale.t("offlineSummary")} }
+				},
+		}
+		div { class: "absolute top-2 right-2 flex gap-1",
+			button {
+				class: "px-2 py-1 text-xs font-medium text-gray-600 dark:text-gray-200 bg-gray-200 dark:bg-gray-700 hover:bg-gray-300 dark:hover:bg-gray-600 rounded-md transition-all",
+				onclick: {
+						to_owned![summary];
+						move |_| {
+								to_owned![summary, runtime];
+								async move {
+										// the background forwards this to the page, where the content script toggles its overlay
+										let request = Envelope::request(Source::Popup, ExtMessage::ToggleOverlay(summary));
+										let toggled = runtime
+												.send_message::<_, Envelope>(&request)
+												.await
+												.is_ok_and(|response| response.response_to(&request).is_ok());
+										overlay_text.set(if toggled { "showOnPage" } else { "notAvailableHere" });
+								}
+						}
+				},
+				{locale.t(overlay_text())}
+			}
+			button {
+				class: "px-2 py-1 text-xs font-medium text-gray-600 dark:text-gray-200 bg-gray-200 dark:bg-gray-700 hover:bg-gray-300 dark:hover:bg-gray-600 rounded-md transition-all",
+				onclick: move |_| {
+						to_owned![summary];
+						async move {
+								if let Some(window) = web_sys::window() {
+										let clipboard = window.navigator().clipboard();
+										if wasm_bindgen_futures::JsFuture::from(clipboard.write_text(&summary))
+												.await
+												.is_ok()
+										{
+												copy_text.set("copied");
+										} else {
+												copy_text.set("copyFailed");
+										}
+								}
+						}
+				},
+				{locale.t(copy_text())}
+			}
 		}
 	}
 }
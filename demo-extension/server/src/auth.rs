@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use common::{AppError, ServerErrorResponse};
+use dioxus::server::axum::{
+	Json,
+	extract::{Request, State},
+	http::{StatusCode, header},
+	middleware::Next,
+	response::{IntoResponse, Response},
+};
+
+/// The tokens the server accepts, from the comma-separated `AUTH_TOKENS`. With none configured every request is let through, which keeps
+/// a fresh checkout usable; set it before exposing the server beyond localhost.
+#[derive(Debug, Clone, Default)]
+pub struct AuthTokens(Arc<Vec<String>>);
+
+impl AuthTokens {
+	pub fn from_env() -> Self {
+		let tokens = std::env::var("AUTH_TOKENS").unwrap_or_default();
+		Self(Arc::new(tokens.split(',').map(str::trim).filter(|token| !token.is_empty()).map(str::to_owned).collect()))
+	}
+
+	pub fn is_enabled(&self) -> bool {
+		!self.0.is_empty()
+	}
+
+	fn accepts(&self, token: &str) -> bool {
+		// checks every token in constant time, so response timing doesn't reveal how much of a guess was right
+		self.0.iter().fold(false, |found, known| constant_time_eq(known.as_bytes(), token.as_bytes()) | found)
+	}
+}
+
+/// The token a request was let through with, put in its extensions by [`require_auth_token`] once it matched one of the
+/// [`AuthTokens`].
+#[derive(Debug, Clone)]
+pub struct AuthenticatedToken(pub String);
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+	a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Axum middleware requiring `Authorization: Bearer <token>` with one of the [`AuthTokens`]. A missing token is answered with
+/// [`AppError::MissingConfiguration`], since it means the extension's options were never filled in, and a wrong one with
+/// [`AppError::ServerError`].
+pub async fn require_auth_token(State(tokens): State<AuthTokens>, mut request: Request, next: Next) -> Response {
+	if !tokens.is_enabled() {
+		return next.run(request).await;
+	}
+	let token = request.headers().get(header::AUTHORIZATION).and_then(|value| value.to_str().ok()).and_then(|value| value.strip_prefix("Bearer "));
+	let error = match token.map(str::trim) {
+		Some(token) if tokens.accepts(token) => {
+			let token = AuthenticatedToken(token.to_owned());
+			request.extensions_mut().insert(token);
+			return next.run(request).await;
+		},
+		Some(_) => AppError::ServerError("The auth token was rejected. Please check it in the extension options.".to_owned()),
+		None => AppError::MissingConfiguration,
+	};
+	(StatusCode::UNAUTHORIZED, Json(ServerErrorResponse::from(error))).into_response()
+}
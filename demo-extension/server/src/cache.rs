@@ -0,0 +1,100 @@
+use std::{num::NonZeroUsize, sync::Arc};
+
+use common::ServerSummarizeRequest;
+use lru::LruCache;
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
+
+use crate::provider::Provider;
+
+const DEFAULT_CAPACITY: usize = 256;
+
+#[cfg(feature = "redis")]
+const DEFAULT_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Summaries that were already generated, so repeating a request while testing the extension doesn't pay for another completion.
+///
+/// By default the last `SUMMARY_CACHE_SIZE` summaries (256 unless set, `0` turns caching off) are kept in memory. Built with the
+/// `redis` feature and with `REDIS_URL` set, they go to Redis instead, where they outlive restarts and expire after
+/// `SUMMARY_CACHE_TTL_SECS` (a day by default).
+#[derive(Clone)]
+pub enum SummaryCache {
+	Disabled,
+	Memory(Arc<Mutex<LruCache<String, String>>>),
+	#[cfg(feature = "redis")]
+	Redis {
+		client: redis::Client,
+		ttl_secs: u64,
+	},
+}
+
+impl SummaryCache {
+	pub fn from_env() -> Self {
+		let var = |key: &str| std::env::var(key).ok().filter(|value| !value.is_empty());
+		#[cfg(feature = "redis")]
+		if let Some(url) = var("REDIS_URL") {
+			match redis::Client::open(url) {
+				Ok(client) => {
+					let ttl_secs = var("SUMMARY_CACHE_TTL_SECS").and_then(|value| value.parse().ok()).unwrap_or(DEFAULT_TTL_SECS);
+					return Self::Redis { client, ttl_secs };
+				},
+				Err(e) => dioxus::logger::tracing::error!("REDIS_URL is invalid, caching summaries in memory instead: {e}"),
+			}
+		}
+		let capacity = var("SUMMARY_CACHE_SIZE").and_then(|value| value.parse().ok()).unwrap_or(DEFAULT_CAPACITY);
+		Self::in_memory(capacity)
+	}
+
+	/// Keeps the `capacity` most recently used summaries, or none at all for `0`.
+	pub fn in_memory(capacity: usize) -> Self {
+		NonZeroUsize::new(capacity).map_or(Self::Disabled, |capacity| Self::Memory(Arc::new(Mutex::new(LruCache::new(capacity)))))
+	}
+
+	/// The summary stored under `key`. A cache that can't be reached counts as a miss.
+	pub async fn get(&self, key: &str) -> Option<String> {
+		match self {
+			Self::Disabled => None,
+			Self::Memory(entries) => entries.lock().get(key).cloned(),
+			#[cfg(feature = "redis")]
+			Self::Redis { client, .. } => {
+				use redis::AsyncCommands;
+				let result = async { client.get_multiplexed_async_connection().await?.get::<_, Option<String>>(key).await }.await;
+				result.inspect_err(|e| dioxus::logger::tracing::warn!("could not read the summary cache: {e}")).ok().flatten()
+			},
+		}
+	}
+
+	pub async fn put(&self, key: String, summary: String) {
+		match self {
+			Self::Disabled => {},
+			Self::Memory(entries) => {
+				entries.lock().put(key, summary);
+			},
+			#[cfg(feature = "redis")]
+			Self::Redis { client, ttl_secs } => {
+				use redis::AsyncCommands;
+				let result = async { client.get_multiplexed_async_connection().await?.set_ex::<_, _, ()>(key, summary, *ttl_secs).await }.await;
+				if let Err(e) = result {
+					dioxus::logger::tracing::warn!("could not write the summary cache: {e}");
+				}
+			},
+		}
+	}
+}
+
+/// The cache key for summarizing `req` with `provider`: a hash of the page text with its whitespace collapsed, so the same page
+/// extracted with different line breaks still hits, together with everything else that changes the answer.
+pub fn cache_key(provider: &Provider, req: &ServerSummarizeRequest) -> String {
+	let mut hasher = Sha256::new();
+	for part in [provider.name(), provider.model(), &req.style.to_string()] {
+		hasher.update(part.as_bytes());
+		hasher.update([0]);
+	}
+	for (i, word) in req.text.split_whitespace().enumerate() {
+		if i > 0 {
+			hasher.update(b" ");
+		}
+		hasher.update(word.as_bytes());
+	}
+	format!("summary:{:x}", hasher.finalize())
+}
@@ -1,2 +1,83 @@
-// Re-export the summarize function from common for server-side use
-pub use common::summarize;
+pub mod auth;
+pub mod cache;
+pub mod pdf;
+pub mod prompt;
+pub mod provider;
+pub mod rate_limit;
+
+use cache::{SummaryCache, cache_key};
+use common::{ServerSummarizeRequest, ServerSummarizeResponse};
+use dioxus::prelude::*;
+use futures::{
+	StreamExt,
+	stream::{self, BoxStream},
+};
+use parking_lot::Mutex;
+use prompt::Prompt;
+use provider::Provider;
+use std::sync::{Arc, LazyLock};
+
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
+
+static CACHE: LazyLock<SummaryCache> = LazyLock::new(SummaryCache::from_env);
+
+/// Summarizes `req.text` in the requested style with the provider configured through the environment, or answers from the
+/// [`SummaryCache`] if the same text was summarized before.
+pub async fn summarize_text(req: &ServerSummarizeRequest) -> anyhow::Result<ServerSummarizeResponse> {
+	let provider = Provider::from_env()?;
+	let key = cache_key(&provider, req);
+	if let Some(summary) = CACHE.get(&key).await {
+		dioxus::logger::tracing::info!("answering a summary of {} chars from the cache", req.text.len());
+		return Ok(ServerSummarizeResponse { summary, cached: true });
+	}
+	dioxus::logger::tracing::info!("summarizing {} chars as {} with {}", req.text.len(), req.style, provider.name());
+	let summary = provider.complete(&CLIENT, &Prompt::summarize(&req.text, req.style)).await?;
+	CACHE.put(key, summary.clone()).await;
+	Ok(ServerSummarizeResponse { summary, cached: false })
+}
+
+/// A summary on its way to the client.
+pub struct SummaryStream {
+	/// Whether `deltas` replays a cached summary, in which case it is a single item.
+	pub cached: bool,
+	pub deltas: BoxStream<'static, anyhow::Result<String>>,
+}
+
+/// The streaming counterpart of [`summarize_text`]; fails up front if the provider can't be reached or rejects the request, and with an
+/// `Err` item if it breaks off midway. Only summaries that streamed to the end are cached.
+pub async fn summarize_stream(req: &ServerSummarizeRequest) -> anyhow::Result<SummaryStream> {
+	let provider = Provider::from_env()?;
+	let key = cache_key(&provider, req);
+	if let Some(summary) = CACHE.get(&key).await {
+		dioxus::logger::tracing::info!("replaying a summary of {} chars from the cache", req.text.len());
+		return Ok(SummaryStream { cached: true, deltas: stream::once(async { Ok(summary) }).boxed() });
+	}
+	dioxus::logger::tracing::info!("streaming a summary of {} chars as {} with {}", req.text.len(), req.style, provider.name());
+	let deltas = provider.stream(&CLIENT, &Prompt::summarize(&req.text, req.style)).await?;
+
+	// `None` once a delta failed, so a summary that broke off is never stored
+	let collected = Arc::new(Mutex::new(Some(String::new())));
+	let deltas = deltas.inspect({
+		let collected = collected.clone();
+		move |delta| {
+			let mut collected = collected.lock();
+			match delta {
+				Ok(delta) => collected.iter_mut().for_each(|summary| summary.push_str(delta)),
+				Err(_) => *collected = None,
+			}
+		}
+	});
+	let store = stream::once(async move {
+		let summary = collected.lock().take();
+		if let Some(summary) = summary.map(|summary| summary.trim().to_owned()).filter(|summary| !summary.is_empty()) {
+			CACHE.put(key, summary).await;
+		}
+	})
+	.filter_map(|()| async { None });
+	Ok(SummaryStream { cached: false, deltas: deltas.chain(store).boxed() })
+}
+
+#[server(endpoint = "/api/summarize")]
+pub async fn summarize(req: ServerSummarizeRequest) -> Result<ServerSummarizeResponse, ServerFnError> {
+	summarize_text(&req).await.map_err(|e| ServerFnError::new(e.to_string()))
+}
@@ -1,21 +1,92 @@
-use common::{ServerSummarizeRequest, ServerSummarizeResponse};
-use dioxus::server::axum::{Json, Router, routing::post};
+use std::convert::Infallible;
 
-#[allow(unused_imports)]
-use server::*;
+use common::{AppError, ServerErrorReports, ServerErrorResponse, ServerPdfTextResponse, ServerSummarizeChunk, ServerSummarizeRequest, ServerSummarizeResponse};
+use dioxus::server::axum::{
+	Json, Router,
+	body::{Body, Bytes},
+	extract::DefaultBodyLimit,
+	http::{StatusCode, header},
+	middleware,
+	response::{IntoResponse, Response},
+	routing::post,
+};
+use futures::{StreamExt, stream};
+use server::{
+	auth::{AuthTokens, require_auth_token},
+	rate_limit::{SummarizeRateLimiter, rate_limit},
+};
 
-async fn summarize_handler(Json(req): Json<ServerSummarizeRequest>) -> Json<ServerSummarizeResponse> {
-	dioxus::logger::tracing::info!("Received text to summarize: {:?}", req.text);
-	let summary = format!(
-		"This is a hardcoded summary for the text: '{}...'",
-		req.text.chars().take(20).collect::<String>()
-	);
-	Json(ServerSummarizeResponse { summary })
+fn bad_gateway(e: &anyhow::Error) -> (StatusCode, Json<ServerErrorResponse>) {
+	dioxus::logger::tracing::error!("summarize failed: {e:#}");
+	(StatusCode::BAD_GATEWAY, Json(ServerErrorResponse::from(AppError::ServerError(format!("{e:#}")))))
+}
+
+async fn summarize_handler(Json(req): Json<ServerSummarizeRequest>) -> Result<Json<ServerSummarizeResponse>, (StatusCode, Json<ServerErrorResponse>)> {
+	server::summarize_text(&req).await.map(Json).map_err(|e| bad_gateway(&e))
+}
+
+// newline-delimited `ServerSummarizeChunk`s, flushed as the provider produces them
+async fn summarize_stream_handler(Json(req): Json<ServerSummarizeRequest>) -> Response {
+	let summary = match server::summarize_stream(&req).await {
+		Ok(summary) => summary,
+		Err(e) => return bad_gateway(&e).into_response(),
+	};
+	let deltas = summary.deltas.map(|delta| delta.map_or_else(|e| ServerSummarizeChunk::Error(format!("{e:#}")), ServerSummarizeChunk::Delta));
+	let lines = stream::iter(summary.cached.then_some(ServerSummarizeChunk::Cached)).chain(deltas).map(|chunk| {
+		let mut line = serde_json::to_string(&chunk).unwrap_or_default();
+		line.push('\n');
+		Ok::<_, Infallible>(line)
+	});
+	([(header::CONTENT_TYPE, "application/x-ndjson")], Body::from_stream(lines)).into_response()
+}
+
+async fn extract_pdf_handler(body: Bytes) -> Result<Json<ServerPdfTextResponse>, (StatusCode, Json<ServerErrorResponse>)> {
+	server::pdf::extract_text(body.to_vec()).await.map(|text| Json(ServerPdfTextResponse { text })).map_err(|e| {
+		dioxus::logger::tracing::warn!("PDF extraction failed: {e:#}");
+		(StatusCode::UNPROCESSABLE_ENTITY, Json(ServerErrorResponse::from(AppError::ServerError(format!("{e:#}")))))
+	})
+}
+
+// the reports only end up in the log, where they can be grepped for the failures users run into
+async fn error_reports_handler(Json(ServerErrorReports { reports }): Json<ServerErrorReports>) -> StatusCode {
+	for report in reports {
+		dioxus::logger::tracing::warn!(
+			version = %report.extension_version,
+			browser = %report.browser,
+			occurred_at = %report.occurred_at,
+			"reported error: {:?}",
+			report.error
+		);
+	}
+	StatusCode::NO_CONTENT
 }
 
 fn main() {
 	dioxus::logger::initialize_default();
-	dioxus::serve(|| async {
-		Ok::<Router, anyhow::Error>(Router::new().route("/api/summarize", post(summarize_handler)))
+	let tokens = AuthTokens::from_env();
+	let limiter = SummarizeRateLimiter::from_env();
+	if !tokens.is_enabled() {
+		dioxus::logger::tracing::warn!("AUTH_TOKENS is not set, so requests are accepted without an auth token");
+	}
+	dioxus::serve(move || {
+		let (tokens, limiter) = (tokens.clone(), limiter.clone());
+		async move {
+			limiter.spawn_retain_recent();
+			// a PDF is extracted on the way to being summarized, so it doesn't count against the quota a second time, and error
+			// reports aren't summaries at all
+			let unmetered = Router::new()
+				.route("/api/extract/pdf", post(extract_pdf_handler).layer(DefaultBodyLimit::max(server::pdf::MAX_PDF_BYTES)))
+				.route("/api/telemetry/errors", post(error_reports_handler))
+				.route_layer(middleware::from_fn_with_state(tokens.clone(), require_auth_token));
+			Ok::<Router, anyhow::Error>(
+				Router::new()
+					.route("/api/summarize", post(summarize_handler))
+					.route("/api/summarize/stream", post(summarize_stream_handler))
+					// layers run outermost-last, so unauthenticated requests are turned away before they count against a quota
+					.route_layer(middleware::from_fn_with_state(limiter, rate_limit))
+					.route_layer(middleware::from_fn_with_state(tokens, require_auth_token))
+					.merge(unmetered),
+			)
+		}
 	});
 }
@@ -0,0 +1,10 @@
+use anyhow::Context;
+
+/// The largest PDF `/api/extract/pdf` accepts.
+pub const MAX_PDF_BYTES: usize = 32 * 1024 * 1024;
+
+/// The text of the PDF in `bytes`, page by page. Documents without a text layer, like scans, come back empty.
+pub async fn extract_text(bytes: Vec<u8>) -> anyhow::Result<String> {
+	// parsing is CPU bound, so it stays off the async workers
+	tokio::task::spawn_blocking(move || pdf_extract::extract_text_from_mem(&bytes)).await?.context("could not read the PDF")
+}
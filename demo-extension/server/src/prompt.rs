@@ -0,0 +1,28 @@
+use common::SummaryStyle;
+
+/// Pages are cut to this many characters before they are sent, which keeps long articles inside every provider's context window.
+pub const MAX_INPUT_CHARS: usize = 24_000;
+
+const SYSTEM: &str = "You summarize web pages for a browser extension. Only use facts from the page text. Answer in the language of the page, \
+                      without any preamble, in Markdown without HTML.";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prompt {
+	pub system: String,
+	pub user: String,
+	pub max_tokens: u32,
+}
+
+impl Prompt {
+	pub fn summarize(text: &str, style: SummaryStyle) -> Self {
+		let instructions = match style {
+			SummaryStyle::Bullets => {
+				"Summarize the page below as a \"## \" heading naming its topic, followed by 3 to 7 short bullet points, one per line, each \
+				 starting with \"- \". Put the most important term of a bullet in **bold**."
+			},
+			SummaryStyle::Paragraph => "Summarize the page below as a single paragraph of at most 120 words, with its key terms in **bold**.",
+		};
+		let text: String = text.chars().take(MAX_INPUT_CHARS).collect();
+		Self { system: SYSTEM.to_owned(), user: format!("{instructions}\n\n<page>\n{}\n</page>", text.trim()), max_tokens: 512 }
+	}
+}
@@ -0,0 +1,155 @@
+use anyhow::{Context, anyhow, bail};
+use futures::{Stream, StreamExt, TryStreamExt, future, stream};
+use serde_json::{Value, json};
+
+use crate::prompt::Prompt;
+
+/// The LLM backend the summaries come from, picked by `SUMMARIZER_PROVIDER` (`openai`, `anthropic` or `ollama`; defaults to `ollama`).
+///
+/// `SUMMARIZER_MODEL` and `SUMMARIZER_BASE_URL` override the model and endpoint of any of them, so the `openai` provider also covers
+/// OpenAI-compatible servers such as vLLM, LM Studio or OpenRouter.
+#[derive(Debug, Clone)]
+pub enum Provider {
+	OpenAi { base_url: String, api_key: String, model: String },
+	Anthropic { base_url: String, api_key: String, model: String },
+	Ollama { base_url: String, model: String },
+}
+
+impl Provider {
+	pub fn from_env() -> anyhow::Result<Self> {
+		let var = |key: &str| std::env::var(key).ok().filter(|value| !value.is_empty());
+		let kind = var("SUMMARIZER_PROVIDER").unwrap_or_else(|| "ollama".to_owned());
+		let model = var("SUMMARIZER_MODEL");
+		let base_url = var("SUMMARIZER_BASE_URL");
+		Ok(match kind.to_lowercase().as_str() {
+			"openai" => Self::OpenAi {
+				base_url: base_url.unwrap_or_else(|| "https://api.openai.com/v1".to_owned()),
+				api_key: var("OPENAI_API_KEY").context("SUMMARIZER_PROVIDER=openai needs OPENAI_API_KEY")?,
+				model: model.unwrap_or_else(|| "gpt-4o-mini".to_owned()),
+			},
+			"anthropic" => Self::Anthropic {
+				base_url: base_url.unwrap_or_else(|| "https://api.anthropic.com/v1".to_owned()),
+				api_key: var("ANTHROPIC_API_KEY").context("SUMMARIZER_PROVIDER=anthropic needs ANTHROPIC_API_KEY")?,
+				model: model.unwrap_or_else(|| "claude-3-5-haiku-latest".to_owned()),
+			},
+			"ollama" => {
+				Self::Ollama { base_url: base_url.unwrap_or_else(|| "http://127.0.0.1:11434".to_owned()), model: model.unwrap_or_else(|| "llama3.2".to_owned()) }
+			},
+			other => bail!("unknown SUMMARIZER_PROVIDER `{other}`, expected `openai`, `anthropic` or `ollama`"),
+		})
+	}
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::OpenAi { .. } => "openai",
+			Self::Anthropic { .. } => "anthropic",
+			Self::Ollama { .. } => "ollama",
+		}
+	}
+
+	pub fn model(&self) -> &str {
+		match self {
+			Self::OpenAi { model, .. } | Self::Anthropic { model, .. } | Self::Ollama { model, .. } => model,
+		}
+	}
+
+	pub async fn complete(&self, client: &reqwest::Client, prompt: &Prompt) -> anyhow::Result<String> {
+		let response = self.request(client, prompt, false).send().await.with_context(|| format!("could not reach the {} provider", self.name()))?;
+		let status = response.status();
+		let body: Value = response.json().await.with_context(|| format!("the {} provider returned an unreadable response", self.name()))?;
+		if !status.is_success() {
+			bail!("the {} provider answered {status}: {body}", self.name());
+		}
+		let text = match self {
+			Self::OpenAi { .. } => body.pointer("/choices/0/message/content"),
+			Self::Anthropic { .. } => body.pointer("/content/0/text"),
+			Self::Ollama { .. } => body.pointer("/message/content"),
+		};
+		text.and_then(Value::as_str).map(|text| text.trim().to_owned()).ok_or_else(|| anyhow!("the {} provider returned no text: {body}", self.name()))
+	}
+
+	/// Like [`Self::complete`], but yields the text as the model produces it. OpenAI and Anthropic stream server-sent events, Ollama
+	/// newline-delimited JSON; both come down to one JSON object per line.
+	pub async fn stream(&self, client: &reqwest::Client, prompt: &Prompt) -> anyhow::Result<impl Stream<Item = anyhow::Result<String>> + Send + 'static> {
+		let response = self.request(client, prompt, true).send().await.with_context(|| format!("could not reach the {} provider", self.name()))?;
+		let status = response.status();
+		if !status.is_success() {
+			let body = response.text().await.unwrap_or_default();
+			bail!("the {} provider answered {status}: {body}", self.name());
+		}
+		let provider = self.clone();
+		Ok(lines(response.bytes_stream()).try_filter_map(move |line| future::ready(provider.delta(&line))))
+	}
+
+	fn request(&self, client: &reqwest::Client, prompt: &Prompt, stream: bool) -> reqwest::RequestBuilder {
+		match self {
+			Self::OpenAi { base_url, api_key, model } => client.post(format!("{base_url}/chat/completions")).bearer_auth(api_key).json(&json!({
+				"model": model,
+				"stream": stream,
+				"messages": [
+					{ "role": "system", "content": prompt.system },
+					{ "role": "user", "content": prompt.user },
+				],
+			})),
+			Self::Anthropic { base_url, api_key, model } => {
+				client.post(format!("{base_url}/messages")).header("x-api-key", api_key).header("anthropic-version", "2023-06-01").json(&json!({
+					"model": model,
+					"stream": stream,
+					"max_tokens": prompt.max_tokens,
+					"system": prompt.system,
+					"messages": [{ "role": "user", "content": prompt.user }],
+				}))
+			},
+			Self::Ollama { base_url, model } => client.post(format!("{base_url}/api/chat")).json(&json!({
+				"model": model,
+				"stream": stream,
+				"messages": [
+					{ "role": "system", "content": prompt.system },
+					{ "role": "user", "content": prompt.user },
+				],
+			})),
+		}
+	}
+
+	// the text in one line of a streamed response; `event:` lines, keep-alives and `[DONE]` carry none
+	fn delta(&self, line: &str) -> anyhow::Result<Option<String>> {
+		let json = line.strip_prefix("data:").map_or(line, str::trim);
+		let Ok(value) = serde_json::from_str::<Value>(json) else {
+			return Ok(None);
+		};
+		if let Some(error) = value.get("error") {
+			bail!("the {} provider failed mid-stream: {error}", self.name());
+		}
+		let text = match self {
+			Self::OpenAi { .. } => value.pointer("/choices/0/delta/content"),
+			Self::Anthropic { .. } => value.pointer("/delta/text"),
+			Self::Ollama { .. } => value.pointer("/message/content"),
+		};
+		Ok(text.and_then(Value::as_str).filter(|text| !text.is_empty()).map(str::to_owned))
+	}
+}
+
+// splits a byte stream into trimmed lines, whatever the chunk boundaries
+fn lines<B: AsRef<[u8]>>(bytes: impl Stream<Item = reqwest::Result<B>> + Send + 'static) -> impl Stream<Item = anyhow::Result<String>> + Send + 'static {
+	stream::unfold((Box::pin(bytes), Vec::new(), false), |(mut bytes, mut buffer, mut done)| async move {
+		loop {
+			if let Some(end) = buffer.iter().position(|&byte| byte == b'\n') {
+				let line = String::from_utf8_lossy(&buffer[..end]).trim().to_owned();
+				buffer.drain(..=end);
+				return Some((Ok(line), (bytes, buffer, done)));
+			}
+			if done {
+				if buffer.is_empty() {
+					return None;
+				}
+				let line = String::from_utf8_lossy(&buffer).trim().to_owned();
+				return Some((Ok(line), (bytes, Vec::new(), true)));
+			}
+			match bytes.next().await {
+				Some(Ok(chunk)) => buffer.extend_from_slice(chunk.as_ref()),
+				Some(Err(e)) => return Some((Err(e.into()), (bytes, Vec::new(), true))),
+				None => done = true,
+			}
+		}
+	})
+}
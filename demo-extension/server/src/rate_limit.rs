@@ -0,0 +1,102 @@
+use std::{
+	net::SocketAddr,
+	num::NonZeroU32,
+	sync::{Arc, Once},
+	time::Duration,
+};
+
+use common::{AppError, ServerErrorResponse};
+use dioxus::server::axum::{
+	Json,
+	extract::{ConnectInfo, Request, State},
+	http::{HeaderValue, StatusCode, header},
+	middleware::Next,
+	response::{IntoResponse, Response},
+};
+use governor::{DefaultKeyedRateLimiter, Quota, RateLimiter, clock::Clock};
+
+use crate::auth::AuthenticatedToken;
+
+const DEFAULT_PER_MINUTE: u32 = 10;
+
+// how often clients that have been quiet long enough to be back at a full quota are forgotten
+const RETAIN_RECENT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Per-client quotas for the summarize endpoints: `RATE_LIMIT_PER_MINUTE` requests a minute (10 by default), each client keyed by the
+/// auth token [`require_auth_token`](crate::auth::require_auth_token) accepted, or by its IP address when tokens are off. The address
+/// is the peer's, or with `TRUST_X_FORWARDED_FOR` set the first one in `X-Forwarded-For`, which only a reverse proxy in front of the
+/// server should be trusted to send.
+#[derive(Clone)]
+pub struct SummarizeRateLimiter {
+	limiter: Arc<DefaultKeyedRateLimiter<String>>,
+	trust_forwarded_for: bool,
+}
+
+impl SummarizeRateLimiter {
+	pub fn from_env() -> Self {
+		let per_minute = std::env::var("RATE_LIMIT_PER_MINUTE").ok().and_then(|value| value.parse().ok()).and_then(NonZeroU32::new);
+		let trust_forwarded_for = std::env::var("TRUST_X_FORWARDED_FOR").is_ok_and(|value| matches!(value.trim(), "1" | "true"));
+		Self { trust_forwarded_for, ..Self::per_minute(per_minute.unwrap_or(NonZeroU32::new(DEFAULT_PER_MINUTE).unwrap())) }
+	}
+
+	pub fn per_minute(per_minute: NonZeroU32) -> Self {
+		Self { limiter: Arc::new(RateLimiter::keyed(Quota::per_minute(per_minute))), trust_forwarded_for: false }
+	}
+
+	/// Forgets idle clients every minute, so the limiter doesn't keep a key for every address it has ever seen. Only the first call
+	/// starts the task; it has to be made from within the Tokio runtime.
+	pub fn spawn_retain_recent(&self) {
+		static STARTED: Once = Once::new();
+		STARTED.call_once(|| {
+			let limiter = Arc::clone(&self.limiter);
+			tokio::spawn(async move {
+				let mut interval = tokio::time::interval(RETAIN_RECENT_INTERVAL);
+				loop {
+					interval.tick().await;
+					limiter.retain_recent();
+					limiter.shrink_to_fit();
+				}
+			});
+		});
+	}
+
+	/// `Err` with the whole seconds until `key` may send again.
+	fn check(&self, key: String) -> Result<(), u64> {
+		self.limiter.check_key(&key).map_err(|not_until| {
+			let wait = not_until.wait_time_from(self.limiter.clock().now());
+			wait.as_secs() + u64::from(wait.subsec_nanos() > 0)
+		})
+	}
+
+	/// `None` when the request carries neither an accepted token nor the address it came from.
+	fn client_key(&self, request: &Request) -> Option<String> {
+		if let Some(AuthenticatedToken(token)) = request.extensions().get() {
+			return Some(format!("token:{token}"));
+		}
+		if self.trust_forwarded_for {
+			let forwarded = request.headers().get("x-forwarded-for").and_then(|value| value.to_str().ok()).and_then(|value| value.split(',').next());
+			if let Some(forwarded) = forwarded.map(str::trim).filter(|forwarded| !forwarded.is_empty()) {
+				return Some(format!("ip:{forwarded}"));
+			}
+		}
+		request.extensions().get::<ConnectInfo<SocketAddr>>().map(|ConnectInfo(addr)| format!("ip:{}", addr.ip()))
+	}
+}
+
+/// Axum middleware answering clients over their quota with 429, a `Retry-After` header and [`AppError::RateLimited`]. A request it
+/// can't tell the client of is refused with 500 rather than counted against a quota every such client would share.
+pub async fn rate_limit(State(limiter): State<SummarizeRateLimiter>, request: Request, next: Next) -> Response {
+	let Some(key) = limiter.client_key(&request) else {
+		dioxus::logger::tracing::error!("rate limiting needs the peer address, but the server was started without `ConnectInfo<SocketAddr>`");
+		let error = AppError::ServerError("The server can't tell clients apart to rate limit them.".to_owned());
+		return (StatusCode::INTERNAL_SERVER_ERROR, Json(ServerErrorResponse::from(error))).into_response();
+	};
+	match limiter.check(key) {
+		Ok(()) => next.run(request).await,
+		Err(retry_after_secs) => {
+			let mut response = (StatusCode::TOO_MANY_REQUESTS, Json(ServerErrorResponse::from(AppError::RateLimited { retry_after_secs }))).into_response();
+			response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+			response
+		},
+	}
+}
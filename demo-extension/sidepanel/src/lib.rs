@@ -0,0 +1,75 @@
+use chrono::Local;
+use common::{CONFIG_KEY, Config, HISTORY_KEY, HistoryEntry, Source, i18n::use_locale_provider, markdown::Markdown, queue::QueueProgress, theme::use_theme};
+use dioxus::prelude::*;
+use uuid::Uuid;
+use wasm_bindgen::prelude::*;
+use webext_api::hooks::use_ext_storage;
+
+#[wasm_bindgen]
+pub fn main() {
+	dioxus::logger::initialize_default();
+	dioxus::launch(App);
+}
+
+/// The whole summary of one history entry, which the popup only has room to preview, next to the history to pick it from.
+#[component]
+fn App() -> Element {
+	let browser = use_hook(|| webext_api::init().expect("the side panel only runs inside an extension"));
+	let config = use_ext_storage::<Config>(browser.storage().sync(), CONFIG_KEY);
+	use_theme(use_memo(move || config.read().theme));
+	let locale = use_locale_provider(&browser, use_memo(move || config.read().language));
+	// new summaries arrive through `storage.onChanged` while the panel stays open
+	let history = use_ext_storage::<Vec<HistoryEntry>>(browser.storage().local(), HISTORY_KEY);
+	let mut selected = use_signal(|| None::<Uuid>);
+	// the picked entry, or the newest one until something is picked or once the picked one is deleted
+	let shown = use_memo(move || {
+		let history = history.read();
+		selected().and_then(|id| history.iter().find(|entry| entry.id == id)).or_else(|| history.first()).cloned()
+	});
+
+	rsx! {
+		div { class: "flex flex-col gap-4 p-4 min-h-screen bg-white dark:bg-gray-900",
+			h1 { class: "text-lg font-bold text-gray-800 dark:text-gray-100", {locale.t("extName")} }
+			QueueProgress { source: Source::SidePanel }
+			if let Some(entry) = shown() {
+				article { class: "p-4 bg-gray-50 dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-md",
+					a {
+						class: "block font-semibold text-gray-800 dark:text-gray-100 hover:underline",
+						href: "{entry.url}",
+						target: "_blank",
+						if entry.title.is_empty() {
+							"{entry.url}"
+						} else {
+							"{entry.title}"
+						}
+					}
+					p { class: "mb-3 text-xs text-gray-500 dark:text-gray-400", {entry.created_at.with_timezone(&Local).format("%b %e, %H:%M").to_string()} }
+					div { class: "text-sm leading-relaxed text-gray-700 dark:text-gray-200",
+						Markdown { text: entry.summary }
+					}
+				}
+				h2 { class: "text-sm font-semibold text-gray-600 dark:text-gray-300", {locale.t("tabHistory")} }
+				ul { class: "divide-y divide-gray-200 dark:divide-gray-700 border border-gray-200 dark:border-gray-700 rounded-md",
+					for other in history() {
+						li { key: "{other.id}",
+							button {
+								class: if other.id == entry.id { "w-full p-3 text-left text-sm bg-blue-50 dark:bg-gray-800 border-none cursor-pointer" } else { "w-full p-3 text-left text-sm bg-transparent hover:bg-gray-50 dark:hover:bg-gray-800 border-none cursor-pointer" },
+								onclick: move |_| selected.set(Some(other.id)),
+								span { class: "block font-medium text-gray-800 dark:text-gray-100 truncate",
+									if other.title.is_empty() {
+										"{other.url}"
+									} else {
+										"{other.title}"
+									}
+								}
+								span { class: "text-xs text-gray-500 dark:text-gray-400", {other.created_at.with_timezone(&Local).format("%b %e, %H:%M").to_string()} }
+							}
+						}
+					}
+				}
+			} else {
+				p { class: "text-sm text-gray-500 dark:text-gray-400", {locale.t("historyEmpty")} }
+			}
+		}
+	}
+}
@@ -0,0 +1,55 @@
+//! `copy_dir_all` on asset trees of 1 000 and 10 000 files: the first copy into an empty `dist`, and a copy after nothing
+//! changed, which is what every watch event touching the assets costs.
+
+#[path = "../src/copy.rs"]
+mod copy;
+
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::fs;
+use tempfile::TempDir;
+
+const FILES_PER_DIR: usize = 100;
+const FILE_SIZE: usize = 1024;
+
+// `files` files of `FILE_SIZE` bytes, a hundred to a directory, no two with the same content
+fn asset_tree(files: usize) -> TempDir {
+	let dir = tempfile::tempdir().unwrap();
+	for i in 0..files {
+		let path = dir.path().join(format!("dir{}", i / FILES_PER_DIR)).join(format!("asset{i}.bin"));
+		fs::create_dir_all(path.parent().unwrap()).unwrap();
+		let mut content = i.to_le_bytes().repeat(FILE_SIZE / size_of::<usize>());
+		content.resize(FILE_SIZE, 0);
+		fs::write(path, content).unwrap();
+	}
+	dir
+}
+
+fn asset_copies(c: &mut Criterion) {
+	let runtime = tokio::runtime::Runtime::new().unwrap();
+	let mut group = c.benchmark_group("copy_dir_all");
+	group.sample_size(10);
+	for files in [1_000, 10_000] {
+		let src = asset_tree(files);
+		group.bench_with_input(BenchmarkId::new("into_empty_dist", files), src.path(), |b, src| {
+			// the `dist` is returned so deleting it isn't measured
+			b.iter_batched(
+				|| tempfile::tempdir().unwrap(),
+				|dist| {
+					runtime.block_on(copy::copy_dir_all(src, dist.path())).unwrap();
+					dist
+				},
+				BatchSize::PerIteration,
+			);
+		});
+		let dist = tempfile::tempdir().unwrap();
+		runtime.block_on(copy::copy_dir_all(src.path(), dist.path())).unwrap();
+		// the first iteration hashes both trees, the ones after find every hash in the cache
+		group.bench_with_input(BenchmarkId::new("unchanged", files), src.path(), |b, src| {
+			b.iter(|| assert_eq!(runtime.block_on(copy::copy_dir_all(src, dist.path())).unwrap(), 0));
+		});
+	}
+	group.finish();
+}
+
+criterion_group!(benches, asset_copies);
+criterion_main!(benches);
@@ -0,0 +1,89 @@
+//! The watcher under event storms: sorting thousands of events into the files to copy and the crates to rebuild, as
+//! `handle_event` does for each of them, and taking everything that piled up, as `process_pending_events` does once the
+//! events settle.
+
+#[path = "../src/watch.rs"]
+mod watch;
+
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use dashmap::DashSet;
+use std::path::PathBuf;
+
+// what `EFile` and `ExtensionCrate` match paths against in a project with every optional page
+const WATCH_PATHS: [&str; 17] = [
+	"manifest.json",
+	"index.html",
+	"index.js",
+	"options.html",
+	"options_index.js",
+	"sidepanel.html",
+	"sidepanel_index.js",
+	"onboarding.html",
+	"onboarding_index.js",
+	"devtools.html",
+	"devtools_index.js",
+	"devtools_panel.html",
+	"devtools_panel_index.js",
+	"background_index.js",
+	"content_index.js",
+	"popup/assets",
+	"_locales",
+];
+const CRATE_NAMES: [&str; 7] = ["popup", "options", "sidepanel", "onboarding", "devtools", "background", "content"];
+
+// `count` events of the kinds a save, a `git checkout` or a formatter run produce
+fn storm(count: usize) -> Vec<Vec<PathBuf>> {
+	let paths = [
+		"extension/popup/src/lib.rs",
+		"extension/background/src/menu.rs",
+		"extension/index.html",
+		"extension/popup/assets/icon.png",
+		"extension/content/src/.lib.rs.swp",
+		"extension/api/src/lib.rs",
+		"extension/_locales/en/messages.json",
+	];
+	(0..count).map(|i| vec![PathBuf::from(paths[i % paths.len()])]).collect()
+}
+
+fn targets(names: &[&str]) -> Vec<(usize, String)> {
+	names.iter().enumerate().map(|(i, name)| (i, (*name).to_owned())).collect()
+}
+
+// what every event of `events` leaves pending
+fn handle_events(events: &[Vec<PathBuf>], files: &[(usize, String)], crates: &[(usize, String)]) -> (DashSet<usize>, DashSet<usize>) {
+	let (copies, builds) = (DashSet::new(), DashSet::new());
+	for paths in events {
+		if let Some(changes) = watch::classify(paths, files, crates) {
+			for file in changes.copies {
+				copies.insert(file);
+			}
+			for e_crate in changes.builds {
+				builds.insert(e_crate);
+			}
+		}
+	}
+	(copies, builds)
+}
+
+fn event_storms(c: &mut Criterion) {
+	let (files, crates) = (targets(&WATCH_PATHS), targets(&CRATE_NAMES));
+	let mut group = c.benchmark_group("watch");
+	for count in [1_000, 10_000] {
+		let events = storm(count);
+		group.throughput(Throughput::Elements(count as u64));
+		group.bench_with_input(BenchmarkId::new("handle_event", count), &events, |b, events| {
+			b.iter(|| handle_events(events, &files, &crates));
+		});
+		group.bench_with_input(BenchmarkId::new("process_pending_events", count), &events, |b, events| {
+			b.iter_batched(
+				|| handle_events(events, &files, &crates),
+				|(copies, builds)| (watch::take_pending(&copies), watch::take_pending(&builds)),
+				BatchSize::SmallInput,
+			);
+		});
+	}
+	group.finish();
+}
+
+criterion_group!(benches, event_storms);
+criterion_main!(benches);
@@ -272,7 +272,8 @@ impl App {
 		self.throbber_state.normalize(&throbber_widgets_tui::Throbber::default());
 		self.user_scrolled = false;
 		self.add_log(LogLevel::Info, "Initializing tasks...");
-		for e_crate in ExtensionCrate::iter() {
+		let config = read_config().expect("Failed to read config");
+		for e_crate in ExtensionCrate::present(&config) {
 			PENDING_BUILDS.insert(e_crate);
 			self.tasks.insert(e_crate.get_task_name(), TaskStatus::Pending);
 			self.task_history.insert(e_crate.get_task_name(), TaskState::default());
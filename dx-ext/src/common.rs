@@ -1,20 +1,19 @@
 use {
 	crate::{LogLevel, efile::EFile, extcrate::ExtensionCrate},
 	clap::{ArgAction, Args, ValueHint},
-	dashmap::{DashMap, DashSet},
+	dashmap::DashSet,
 	ratatui::crossterm::event::{KeyCode, MouseEvent},
+	schemars::JsonSchema,
 	serde::{Deserialize, Serialize},
 	std::{
 		path::PathBuf,
 		sync::LazyLock,
-		time::{Duration, Instant, SystemTime},
+		time::{Duration, Instant},
 	},
 };
 
 pub(crate) static PENDING_BUILDS: LazyLock<DashSet<ExtensionCrate>> = LazyLock::new(DashSet::new);
 pub(crate) static PENDING_COPIES: LazyLock<DashSet<EFile>> = LazyLock::new(DashSet::new);
-pub(crate) static FILE_HASHES: LazyLock<DashMap<PathBuf, String>> = LazyLock::new(DashMap::new);
-pub(crate) static FILE_TIMESTAMPS: LazyLock<DashMap<PathBuf, SystemTime>> = LazyLock::new(DashMap::new);
 
 // task progress tracking
 #[derive(PartialEq, Default)]
@@ -103,8 +102,22 @@ pub(crate) enum BuildMode {
 	Release,
 }
 
+// the example `init` generates, on top of the popup, background and content crates every project has
+#[derive(Debug, Copy, Clone, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub(crate) enum ProjectTemplate {
+	Default,
+	// a devtools page that adds a panel listing the resources the inspected page loaded
+	DevTools,
+	// a content script that adds a floating button to every page, which messages the background
+	#[strum(serialize = "content-button")]
+	ContentButton,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub(crate) struct ExtConfig {
+	// the directory dx-ext.toml was read from, which stages run in
+	pub config_dir: PathBuf,
 	pub background_script_index_name: String,
 	pub content_script_index_name: String,
 	pub extension_directory_name: String,
@@ -112,23 +125,64 @@ pub(crate) struct ExtConfig {
 	pub assets_dir: String,
 	pub build_mode: BuildMode,
 	pub enable_incremental_builds: bool,
+	pub stages: Vec<Stage>,
+}
+
+/// A command run as a task of its own at one point of the pipeline
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
+pub(crate) struct Stage {
+	/// Shown on the stage's task and in front of its output in the log
+	pub name: String,
+	/// The program and its arguments, run from the directory of dx-ext.toml
+	pub command: Vec<String>,
+	/// When the stage runs
+	pub when: StageHook,
 }
 
-// config struct that matches the TOML structure
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize, Serialize, JsonSchema, strum::Display)]
 #[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub(crate) enum StageHook {
+	/// Before the crates are built
+	BeforeBuild,
+	/// After the crates are built
+	AfterBuild,
+	/// Before the files are copied to `dist`
+	BeforeCopy,
+	/// After the files are copied to `dist`
+	AfterCopy,
+}
+
+// config struct that matches the TOML structure; unknown keys are only left out of the schema, and `read_config` warns about them
+/// The configuration of the Dioxus browser extension builder
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
+#[schemars(title = "dx-ext.toml")]
 pub(crate) struct TomlConfig {
 	pub extension_config: ExtConfigToml,
+	/// Commands to run before or after the crates are built or the files are copied
+	#[serde(default, rename = "stage")]
+	pub stages: Vec<Stage>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub(crate) struct ExtConfigToml {
+	/// Your assets directory relative to the extension directory
 	pub assets_directory: String,
+	/// Name of your background script entry point
 	pub background_script_index_name: String,
+	/// Name of your content script entry point
 	pub content_script_index_name: String,
+	/// Name of your extension directory
 	pub extension_directory_name: String,
+	/// Name of your popup crate
 	pub popup_name: String,
+	/// Enable incremental builds for the watch command
 	pub enable_incremental_builds: bool,
 }
 
@@ -155,6 +209,10 @@ pub(crate) struct InitOptions {
 	#[arg(long, help = "Your assets directory relative to the extension directory", default_value = "popup/assets", value_hint = ValueHint::DirPath)]
 	pub assets_dir: String,
 
+	/// Project template
+	#[arg(short, long, help = "Project template to generate: default, devtools or content-button", default_value = "default")]
+	pub template: ProjectTemplate,
+
 	/// Force overwrite existing config file
 	#[arg(short, long, help = "Force overwrite of existing config file", action = ArgAction::SetTrue)]
 	pub force: bool,
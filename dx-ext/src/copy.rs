@@ -0,0 +1,118 @@
+// copying into `dist`, skipping files whose content is already there; this module doesn't use the rest of the crate, so
+// `tests/copy.rs` can include it
+use anyhow::{Context, Result};
+use async_walkdir::{DirEntry, Filtering, WalkDir};
+use dashmap::DashMap;
+use futures::StreamExt;
+use std::{
+	path::{Path, PathBuf},
+	sync::LazyLock,
+	time::SystemTime,
+};
+use tracing::debug;
+
+// the hash of every file hashed so far, along with the modification time and size the file had then
+static FILE_HASHES: LazyLock<DashMap<PathBuf, CachedHash>> = LazyLock::new(DashMap::new);
+
+struct CachedHash {
+	modified: SystemTime,
+	len: u64,
+	hash: String,
+}
+
+// directory copy with parallel processing and hash checking
+pub(crate) async fn copy_dir_all(src: &Path, dst: &Path) -> Result<usize> {
+	let src_owned = src.to_owned();
+	let dst_owned = dst.to_owned();
+	Ok(
+		WalkDir::new(src)
+			.filter(move |entry| {
+				let src = src_owned.clone();
+				let dst = dst_owned.clone();
+				async move { file_filter(entry, src, dst).await }
+			})
+			.filter_map(|entry| async move { entry.ok() })
+			.then(async |entry| {
+				let src_path = entry.path();
+				let rel_path = src_path.strip_prefix(src).context("Failed to get relative path")?;
+				let dst_path = dst.join(rel_path);
+				copy_file(&src_path, &dst_path).await
+			})
+			.collect::<Vec<_>>()
+			.await
+			.into_iter()
+			.filter_map(|t| t.ok())
+			.sum(),
+	)
+}
+
+async fn file_filter(entry: DirEntry, src: PathBuf, dst: PathBuf) -> Filtering {
+	match entry.file_type().await {
+		Ok(ft) if ft.is_file() => {
+			let src_path = entry.path();
+			let Ok(rel_path) = src_path.strip_prefix(src).context("Failed to get relative path") else {
+				return Filtering::Ignore;
+			};
+			let dst_path = dst.join(rel_path);
+			match needs_copy(&src_path, &dst_path).await {
+				Ok(should_copy) => {
+					if should_copy {
+						Filtering::Continue
+					} else {
+						Filtering::Ignore
+					}
+				},
+				Err(_) => Filtering::Ignore,
+			}
+		},
+		_ => Filtering::Ignore,
+	}
+}
+
+// a file is only read again once its modification time or size changed, in either direction; one rewritten with the same size
+// whose modification time was put back to the old one keeps its old hash
+pub(crate) async fn calculate_file_hash(path: &Path) -> Result<String> {
+	// taken before reading, so a write during the read leaves a modification time that no longer matches
+	let metadata = tokio::fs::metadata(path).await.with_context(|| format!("Failed to get metadata for file: {path:?}"))?;
+	let modified = metadata.modified().ok();
+	if let Some(modified) = modified
+		&& let Some(cached) = FILE_HASHES.get(path)
+		&& cached.modified == modified
+		&& cached.len == metadata.len()
+	{
+		return Ok(cached.hash.clone());
+	}
+	let data = tokio::fs::read(path).await.with_context(|| format!("Failed to read file: {path:?}"))?;
+	let hash = tokio::task::spawn_blocking(move || blake3::hash(&data).to_hex().to_string()).await.context("Hash calculation task failed")?;
+	if let Some(modified) = modified {
+		FILE_HASHES.insert(path.to_path_buf(), CachedHash { modified, len: metadata.len(), hash: hash.clone() });
+	}
+	Ok(hash)
+}
+
+pub(crate) async fn needs_copy(src: &Path, dest: &Path) -> Result<bool> {
+	let src_metadata = tokio::fs::metadata(src).await.with_context(|| format!("Failed to get metadata for source file: {src:?}"))?;
+	if !tokio::fs::try_exists(dest).await.unwrap_or(false) {
+		return Ok(true);
+	}
+	let dest_metadata = tokio::fs::metadata(dest).await.with_context(|| format!("Failed to get metadata for destination file: {dest:?}"))?;
+	// if sizes differ, definitely needs copy
+	if src_metadata.len() != dest_metadata.len() {
+		return Ok(true);
+	}
+	// both hashes are cached, so a file changed on either side is noticed and unchanged ones aren't read again
+	Ok(calculate_file_hash(src).await? != calculate_file_hash(dest).await?)
+}
+
+// hash checking to avoid unnecessary copies
+pub(crate) async fn copy_file(src: &Path, dest: &Path) -> Result<usize> {
+	if !tokio::fs::try_exists(src).await.unwrap_or(false) {
+		return Err(anyhow::anyhow!("Source file does not exist: {src:?}"));
+	}
+	if let Some(parent) = dest.parent() {
+		tokio::fs::create_dir_all(parent).await.with_context(|| format!("Failed to create parent directory: {parent:?}"))?;
+	}
+	tokio::fs::copy(src, dest).await.with_context(|| format!("Failed to copy file from {src:?} to {dest:?}"))?;
+	debug!("Copied file: {:?} -> {:?}", src, dest);
+	Ok(1)
+}
@@ -1,22 +1,38 @@
-use crate::common::{ExtConfig, FILE_HASHES, FILE_TIMESTAMPS};
-use anyhow::{Context, Result};
-use async_walkdir::{DirEntry, Filtering, WalkDir};
-use futures::StreamExt;
+use crate::{
+	common::ExtConfig,
+	copy::{copy_dir_all, copy_file},
+};
+use anyhow::Result;
 use std::path::{Path, PathBuf};
 use tracing::{debug, info, warn};
 
+const LOCALES_DIR: &str = "_locales";
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::EnumIter, strum::Display)]
 pub(crate) enum EFile {
 	// fixed files for Chrome extensions
 	Manifest,
 	IndexHtml,
 	IndexJs,
-	// dynamic files from config
+	// optional options page, built from the `options` crate
 	OptionsHtml,
 	OptionsJs,
+	// optional side panel page, built from the `sidepanel` crate
+	SidePanelHtml,
+	SidePanelJs,
+	// optional onboarding page, built from the `onboarding` crate
+	OnboardingHtml,
+	OnboardingJs,
+	// optional devtools page and the panel it adds, both built from the `devtools` crate
+	DevToolsHtml,
+	DevToolsJs,
+	DevToolsPanelHtml,
+	DevToolsPanelJs,
 	BackgroundScript,
 	ContentScript,
 	Assets,
+	// optional `_locales` directory for `__MSG_*__` manifest strings and `i18n.getMessage`
+	Locales,
 }
 
 impl EFile {
@@ -29,9 +45,18 @@ impl EFile {
 			Self::IndexJs => base_path.join("index.js"),
 			Self::OptionsHtml => base_path.join("options.html"),
 			Self::OptionsJs => base_path.join("options_index.js"),
+			Self::SidePanelHtml => base_path.join("sidepanel.html"),
+			Self::SidePanelJs => base_path.join("sidepanel_index.js"),
+			Self::OnboardingHtml => base_path.join("onboarding.html"),
+			Self::OnboardingJs => base_path.join("onboarding_index.js"),
+			Self::DevToolsHtml => base_path.join("devtools.html"),
+			Self::DevToolsJs => base_path.join("devtools_index.js"),
+			Self::DevToolsPanelHtml => base_path.join("devtools_panel.html"),
+			Self::DevToolsPanelJs => base_path.join("devtools_panel_index.js"),
 			Self::BackgroundScript => base_path.join(&config.background_script_index_name),
 			Self::ContentScript => base_path.join(&config.content_script_index_name),
 			Self::Assets => base_path.join(&config.assets_dir),
+			Self::Locales => base_path.join(LOCALES_DIR),
 		}
 	}
 
@@ -44,9 +69,18 @@ impl EFile {
 			Self::IndexJs => dist_path.join("index.js"),
 			Self::OptionsHtml => dist_path.join("options.html"),
 			Self::OptionsJs => dist_path.join("options_index.js"),
+			Self::SidePanelHtml => dist_path.join("sidepanel.html"),
+			Self::SidePanelJs => dist_path.join("sidepanel_index.js"),
+			Self::OnboardingHtml => dist_path.join("onboarding.html"),
+			Self::OnboardingJs => dist_path.join("onboarding_index.js"),
+			Self::DevToolsHtml => dist_path.join("devtools.html"),
+			Self::DevToolsJs => dist_path.join("devtools_index.js"),
+			Self::DevToolsPanelHtml => dist_path.join("devtools_panel.html"),
+			Self::DevToolsPanelJs => dist_path.join("devtools_panel_index.js"),
 			Self::BackgroundScript => dist_path.join(&config.background_script_index_name),
 			Self::ContentScript => dist_path.join(&config.content_script_index_name),
 			Self::Assets => dist_path.join("assets"),
+			Self::Locales => dist_path.join(LOCALES_DIR),
 		}
 	}
 
@@ -54,6 +88,10 @@ impl EFile {
 		info!("Copying {:?}...", self);
 		let src = self.get_copy_src(config);
 		let dest = self.get_copy_dest(config);
+		if self.is_optional() && !src.exists() {
+			debug!("{:?} not present, skipping", self);
+			return Ok(());
+		}
 		let result = if src.is_dir() { copy_dir_all(&src, &dest).await } else { copy_file(&src, &dest).await };
 		match result {
 			Ok(copied) => {
@@ -71,6 +109,24 @@ impl EFile {
 		}
 	}
 
+	// files only some extensions have, like a side panel or localized strings
+	pub fn is_optional(&self) -> bool {
+		matches!(
+			self,
+			Self::OptionsHtml
+				| Self::OptionsJs
+				| Self::SidePanelHtml
+				| Self::SidePanelJs
+				| Self::OnboardingHtml
+				| Self::OnboardingJs
+				| Self::DevToolsHtml
+				| Self::DevToolsJs
+				| Self::DevToolsPanelHtml
+				| Self::DevToolsPanelJs
+				| Self::Locales
+		)
+	}
+
 	// the file path string for file watching
 	pub fn get_watch_path(&self, config: &ExtConfig) -> String {
 		match self {
@@ -79,107 +135,18 @@ impl EFile {
 			Self::IndexJs => "index.js".to_owned(),
 			Self::OptionsHtml => "options.html".to_owned(),
 			Self::OptionsJs => "options_index.js".to_owned(),
+			Self::SidePanelHtml => "sidepanel.html".to_owned(),
+			Self::SidePanelJs => "sidepanel_index.js".to_owned(),
+			Self::OnboardingHtml => "onboarding.html".to_owned(),
+			Self::OnboardingJs => "onboarding_index.js".to_owned(),
+			Self::DevToolsHtml => "devtools.html".to_owned(),
+			Self::DevToolsJs => "devtools_index.js".to_owned(),
+			Self::DevToolsPanelHtml => "devtools_panel.html".to_owned(),
+			Self::DevToolsPanelJs => "devtools_panel_index.js".to_owned(),
 			Self::BackgroundScript => config.background_script_index_name.clone(),
 			Self::ContentScript => config.content_script_index_name.clone(),
 			Self::Assets => config.assets_dir.clone(),
+			Self::Locales => LOCALES_DIR.to_owned(),
 		}
 	}
 }
-
-// directory copy with parallel processing and hash checking
-async fn copy_dir_all(src: &Path, dst: &Path) -> Result<usize> {
-	let src_owned = src.to_owned();
-	let dst_owned = dst.to_owned();
-	Ok(
-		WalkDir::new(src)
-			.filter(move |entry| {
-				let src = src_owned.clone();
-				let dst = dst_owned.clone();
-				async move { file_filter(entry, src, dst).await }
-			})
-			.filter_map(|entry| async move { entry.ok() })
-			.then(async |entry| {
-				let src_path = entry.path();
-				let rel_path = src_path.strip_prefix(src).context("Failed to get relative path")?;
-				let dst_path = dst.join(rel_path);
-				copy_file(&src_path, &dst_path).await
-			})
-			.collect::<Vec<_>>()
-			.await
-			.into_iter()
-			.filter_map(|t| t.ok())
-			.sum(),
-	)
-}
-
-async fn file_filter(entry: DirEntry, src: PathBuf, dst: PathBuf) -> Filtering {
-	match entry.file_type().await {
-		Ok(ft) if ft.is_file() => {
-			let src_path = entry.path();
-			let Ok(rel_path) = src_path.strip_prefix(src).context("Failed to get relative path") else {
-				return Filtering::Ignore;
-			};
-			let dst_path = dst.join(rel_path);
-			match needs_copy(&src_path, &dst_path).await {
-				Ok(should_copy) => {
-					if should_copy {
-						Filtering::Continue
-					} else {
-						Filtering::Ignore
-					}
-				},
-				Err(_) => Filtering::Ignore,
-			}
-		},
-		_ => Filtering::Ignore,
-	}
-}
-
-async fn calculate_file_hash(path: &Path) -> Result<String> {
-	let data = tokio::fs::read(path).await.with_context(|| format!("Failed to read file: {path:?}"))?;
-	tokio::task::spawn_blocking(move || blake3::hash(&data).to_hex().to_string()).await.context("Hash calculation task failed")
-}
-
-async fn needs_copy(src: &Path, dest: &Path) -> Result<bool> {
-	let src_metadata = tokio::fs::metadata(src).await.with_context(|| format!("Failed to get metadata for source file: {src:?}"))?;
-	let src_len = src_metadata.len();
-	let src_time = src_metadata.modified().ok();
-	if !tokio::fs::try_exists(dest).await.unwrap_or(false) {
-		return Ok(true);
-	}
-	let dest_metadata = tokio::fs::metadata(dest).await.with_context(|| format!("Failed to get metadata for destination file: {dest:?}"))?;
-	// if sizes differ, definitely needs copy
-	if src_len != dest_metadata.len() {
-		return Ok(true);
-	}
-	// timestamps checks
-	if let Some(src_time) = src_time
-		&& let Some(stored_time) = FILE_TIMESTAMPS.get(src)
-		&& *stored_time == src_time
-	{
-		// file hasn't changed since last check
-		return Ok(false);
-	}
-	// hashes comparison for final determination
-	let src_hash = calculate_file_hash(src).await?;
-	let dest_hash = calculate_file_hash(dest).await?;
-	FILE_HASHES.insert(src.to_path_buf(), src_hash.clone());
-	if let Some(src_time) = src_time {
-		FILE_TIMESTAMPS.insert(src.to_path_buf(), src_time);
-	}
-
-	Ok(src_hash != dest_hash)
-}
-
-// hash checking to avoid unnecessary copies
-async fn copy_file(src: &Path, dest: &Path) -> Result<usize> {
-	if !tokio::fs::try_exists(src).await.unwrap_or(false) {
-		return Err(anyhow::anyhow!("Source file does not exist: {src:?}"));
-	}
-	if let Some(parent) = dest.parent() {
-		tokio::fs::create_dir_all(parent).await.with_context(|| format!("Failed to create parent directory: {parent:?}"))?;
-	}
-	tokio::fs::copy(src, dest).await.with_context(|| format!("Failed to copy file from {src:?} to {dest:?}"))?;
-	debug!("Copied file: {:?} -> {:?}", src, dest);
-	Ok(1)
-}
@@ -4,6 +4,7 @@ use {
 	anyhow::Result,
 	async_walkdir::WalkDir,
 	std::{fs, path::Path, process::Stdio, sync::LazyLock, time::SystemTime},
+	strum::IntoEnumIterator,
 	tokio::{
 		io::{AsyncBufReadExt, BufReader},
 		process::Command,
@@ -18,7 +19,14 @@ static LOG_REGEX: LazyLock<regex::Regex> =
 #[strum(serialize_all = "lowercase")]
 pub(crate) enum ExtensionCrate {
 	Popup,
+	// optional, only built when the extension directory has an `options` crate
 	Options,
+	// optional, only built when the extension directory has a `sidepanel` crate
+	SidePanel,
+	// optional, only built when the extension directory has an `onboarding` crate
+	Onboarding,
+	// optional, only built when the extension directory has a `devtools` crate
+	DevTools,
 
 	Background,
 	Content,
@@ -33,11 +41,24 @@ impl ExtensionCrate {
 		}
 	}
 
+	// every crate the extension has, leaving out optional ones it doesn't
+	pub fn present(config: &ExtConfig) -> Vec<Self> {
+		Self::iter().filter(|e_crate| !e_crate.is_optional() || Path::new(&config.extension_directory_name).join(e_crate.get_crate_name(config)).exists()).collect()
+	}
+
+	// pages only some extensions have
+	pub fn is_optional(&self) -> bool {
+		matches!(self, Self::Options | Self::SidePanel | Self::Onboarding | Self::DevTools)
+	}
+
 	pub fn get_task_name(&self) -> String {
 		match self {
 			Self::Popup => "Building Popup".to_owned(),
 			Self::Background => "Building Background".to_owned(),
 			Self::Options => "Building Options".to_owned(),
+			Self::SidePanel => "Building Side Panel".to_owned(),
+			Self::Onboarding => "Building Onboarding".to_owned(),
+			Self::DevTools => "Building DevTools".to_owned(),
 			Self::Content => "Building Content".to_owned(),
 		}
 	}
@@ -146,6 +167,10 @@ impl ExtensionCrate {
 				cmd.arg("--target").arg("web");
 			}
 			cmd.arg(format!("{extension_dir}/{crate_name}"));
+			// lets webext-api warn about namespaces used without their permission declared
+			if let Ok(manifest) = std::fs::canonicalize(format!("{extension_dir}/manifest.json")) {
+				cmd.env("WEBEXT_MANIFEST", manifest);
+			}
 			cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
 			let mut child = match cmd.spawn() {
 				Ok(child) => child,
@@ -19,6 +19,7 @@
 //! - `--background-script <FILE>`: Name of the background script entry point (default: "`background_index.js`")
 //! - `--content-script <FILE>`: Name of the content script entry point (default: "`content_index.js`")
 //! - `--assets-dir <DIR>`: Assets directory path relative to the extension's directory (default: "popup/assets")
+//! - `-t, --template <TEMPLATE>`: Project template, `default`, `devtools` for an example devtools panel or `content-button` for a button the content script injects (default: "default")
 //! - `-f, --force`: Force overwrite of the existing config file
 //! - `-i, --interactive`: Interactive mode to collect confiuration information
 //! - `--mode, -m`: Build mode: development or release (default: "development")
@@ -44,6 +45,24 @@
 //! dx-ext watch
 //! ```
 //!
+//! ### Config
+//!
+//! Prints a JSON Schema of `dx-ext.toml`, for editors to complete and check the file. Point taplo (Even Better TOML) at it with
+//! a `#:schema` comment at the top of `dx-ext.toml`.
+//!
+//! ```bash
+//! dx-ext config schema > dx-ext.schema.json
+//! ```
+//!
+//! ### External subcommands
+//!
+//! Any other subcommand runs `dx-ext-<name>` from the `PATH` with the remaining arguments, like cargo does for `cargo-<name>`.
+//! The `DX_EXT` environment variable holds the path of `dx-ext` itself.
+//!
+//! ```bash
+//! dx-ext publish --store chrome # runs `dx-ext-publish --store chrome`
+//! ```
+//!
 //! ## Configuration:
 //!
 //! The tool uses a `dx-ext.toml` file in the project root with the following structure:
@@ -58,12 +77,28 @@
 //! popup-name = "popup"                          # name of your popup crate
 //! ```
 //!
+//! `[[stage]]` tables add commands to the pipeline, run before or after the crates are built or the files are copied. Each one
+//! is a task of its own in the TUI, and its output goes to the log.
+//!
+//! ```toml
+//! [[stage]]
+//! name = "Tailwind"
+//! command = ["npx", "tailwindcss", "-i", "extension/popup/input.css", "-o", "extension/dist/tailwind.css"]
+//! when = "after-copy"                                 # before-build, after-build, before-copy or after-copy
+//! ```
+//!
+//! Unknown keys are an error, so a misspelled key doesn't quietly fall back to a default.
+//!
 //! ## Internal Structure
 //!
 //! The tool organizes extension components into three main crates:
 //! - `Popup`: The UI component of the extension
 //! - `Background`: The background script that runs persistently
 //! - `Content`: The content script that runs in the context of web pages
+//! - `Options`: An optional options page, built only when the extension directory has an `options` crate
+//! - `SidePanel`: An optional side panel page, built only when the extension directory has a `sidepanel` crate
+//! - `Onboarding`: An optional page to open on install, built only when the extension directory has an `onboarding` crate
+//! - `DevTools`: An optional devtools page and panel, built only when the extension directory has a `devtools` crate
 //!
 //! File operations are managed through the `EFile` enum which handles copying:
 //! - `Manifest`: The extension's manifest.json
@@ -71,7 +106,15 @@
 //! - `IndexJs`: Main JavaScript entry point
 //! - `BackgroundScript`: The background script entry point
 //! - `ContentScript`: The content script entry point
+//! - `OptionsHtml` / `OptionsJs`: The options page and its entry point, when the extension has one
+//! - `SidePanelHtml` / `SidePanelJs`: The side panel page and its entry point, when the extension has one
+//! - `OnboardingHtml` / `OnboardingJs`: The onboarding page and its entry point, when the extension has one
+//! - `DevToolsHtml` / `DevToolsJs` / `DevToolsPanelHtml` / `DevToolsPanelJs`: The devtools page, its panel and their entry points, when the extension has them
 //! - `Assets`: Additional assets required by the extension
+//! - `Locales`: The `_locales` message files, when the extension has any
+//!
+//! Directories are copied file by file. A file is skipped when `dist` already has the same content, judged by size first and
+//! then by a blake3 hash, which is cached for as long as the file keeps its modification time and size.
 //!
 //! Build operations for crates are managed through the `ExtensionCrate` enum which uses `wasm-pack`:
 //! - It represents different browser extension components: Popup, Background, and Content.
@@ -82,23 +125,27 @@
 
 mod app;
 mod common;
+mod copy;
 mod efile;
 mod extcrate;
 mod logging;
+mod plugin;
 mod terminal;
 mod utils;
+mod watch;
 
 use {
 	anyhow::Context,
 	app::App,
 	clap::{ArgAction, Args, Parser, Subcommand},
-	common::{BuildMode, BuildState, EXMessage, ExtConfig, InitOptions, PENDING_BUILDS, PENDING_COPIES, TaskStatus},
+	common::{BuildMode, BuildState, EXMessage, ExtConfig, InitOptions, PENDING_BUILDS, PENDING_COPIES, StageHook, TaskStatus},
 	efile::EFile,
 	extcrate::ExtensionCrate,
 	futures::future::join_all,
 	logging::{LogCallback, LogLevel, TUILogLayer},
 	notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Result as NotifyResult, Watcher},
 	std::{
+		ffi::OsString,
 		io,
 		path::Path,
 		sync::{Arc, LazyLock},
@@ -117,7 +164,7 @@ use {
 		fmt::{format::Writer, time::FormatTime},
 		layer::SubscriberExt,
 	},
-	utils::{clean_dist_directory, create_default_config_toml, read_config, setup_project_from_config, show_final_build_report},
+	utils::{clean_dist_directory, config_schema, create_default_config_toml, read_config, setup_project_from_config, show_final_build_report},
 };
 
 pub(crate) static UI_SENDER: LazyLock<Mutex<Option<mpsc::UnboundedSender<EXMessage>>>> = LazyLock::new(|| Mutex::new(None));
@@ -152,6 +199,22 @@ enum Commands {
 	/// Create a configuration file with customizable options
 	#[clap(name = "init")]
 	Init(InitOptions),
+	/// Inspect the configuration file
+	#[clap(name = "config")]
+	Config {
+		#[command(subcommand)]
+		command: ConfigCommand,
+	},
+	/// Run `dx-ext-<name>` from the PATH with the remaining arguments
+	#[command(external_subcommand)]
+	External(Vec<OsString>),
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+	/// Print a JSON Schema of dx-ext.toml
+	#[clap(name = "schema")]
+	Schema,
 }
 
 struct CustomTime;
@@ -165,13 +228,20 @@ impl FormatTime for CustomTime {
 #[tokio::main]
 async fn main() -> io::Result<()> {
 	let cli = Cli::parse();
+	if let Commands::External(args) = &cli.command {
+		return plugin::run_external(args);
+	}
+	if let Commands::Config { command: ConfigCommand::Schema } = &cli.command {
+		println!("{}", config_schema().map_err(|e| io::Error::other(e.to_string()))?);
+		return Ok(());
+	}
 	if let Commands::Init(options) = cli.command {
 		let subscriber = FmtSubscriber::builder().with_timer(CustomTime).with_max_level(Level::INFO).with_file(false).with_target(false).finish();
 		tracing::subscriber::set_global_default(subscriber).expect("Cannot set tracing subscriber");
 		let created = create_default_config_toml(&options).map_err(|e| io::Error::other(e.to_string()))?;
 		if created {
 			info!("Created dx-ext.toml configuration file");
-			let _ = setup_project_from_config();
+			let _ = setup_project_from_config(options.template);
 		}
 		return Ok(());
 	} else {
@@ -193,7 +263,7 @@ async fn main() -> io::Result<()> {
 				BuildMode::Development => Level::DEBUG,
 				BuildMode::Release => Level::INFO,
 			},
-			Commands::Init(_) => Level::INFO,
+			Commands::Init(_) | Commands::Config { .. } | Commands::External(_) => Level::INFO,
 		};
 		let subscriber = tracing_subscriber::registry().with(tui_layer).with(tracing_subscriber::filter::LevelFilter::from_level(log_level));
 		let _ = tracing::subscriber::set_global_default(subscriber);
@@ -227,55 +297,75 @@ async fn main() -> io::Result<()> {
 				// Initialize tasks in the app before building
 				{
 					let mut app_guard = app.lock().await;
-					for e_crate in ExtensionCrate::iter() {
+					for e_crate in ExtensionCrate::present(&config) {
 						app_guard.tasks.insert(e_crate.get_task_name(), TaskStatus::Pending);
 					}
+					for stage in &config.stages {
+						app_guard.tasks.insert(stage.get_task_name(), TaskStatus::Pending);
+					}
 				}
 				// Set start time
 				{
 					let mut app_guard = app.lock().await;
 					app_guard.overall_start_time = Some(std::time::Instant::now());
 				}
-				// build all crates concurrently
-				let build_futures = ExtensionCrate::iter().map(|e_crate| {
-					let config = config.clone();
-					let task_name = e_crate.get_task_name();
-					async move {
-						let progress_callback = move |progress| {
-							let task = task_name.clone();
-							tokio::spawn(async move {
-								send_ui_message(EXMessage::TaskProgress(task, progress)).await;
-							});
-						};
-						let result = e_crate.build_crate(&config, progress_callback).await;
-						let status = match &result {
-							Some(Ok(_)) => TaskStatus::Success,
-							Some(Err(e)) => {
-								error!("Failed to build {}: {:?}", e_crate.get_task_name(), e);
-								TaskStatus::Failed
-							},
-							None => TaskStatus::Failed,
-						};
-						(e_crate.get_task_name(), status)
-					}
-				});
-				let results: Vec<(String, TaskStatus)> = join_all(build_futures).await;
-				// Update app with build results directly
+				let mut results = plugin::run_stages(&config, StageHook::BeforeBuild).await;
+				// the crates aren't built after a failed before-build stage, nor the files copied after a failed before-copy one, since
+				// those stages may prepare what the step needs
+				if plugin::all_succeeded(&results) {
+					// build all crates concurrently
+					let build_futures = ExtensionCrate::present(&config).into_iter().map(|e_crate| {
+						let config = config.clone();
+						let task_name = e_crate.get_task_name();
+						async move {
+							let progress_callback = move |progress| {
+								let task = task_name.clone();
+								tokio::spawn(async move {
+									send_ui_message(EXMessage::TaskProgress(task, progress)).await;
+								});
+							};
+							let result = e_crate.build_crate(&config, progress_callback).await;
+							let status = match &result {
+								Some(Ok(_)) => TaskStatus::Success,
+								Some(Err(e)) => {
+									error!("Failed to build {}: {:?}", e_crate.get_task_name(), e);
+									TaskStatus::Failed
+								},
+								None => TaskStatus::Failed,
+							};
+							(e_crate.get_task_name(), status)
+						}
+					});
+					results.extend(join_all(build_futures).await);
+					results.extend(plugin::run_stages(&config, StageHook::AfterBuild).await);
+				} else {
+					error!("A before-build stage failed, skipping the build");
+					results.extend(ExtensionCrate::present(&config).into_iter().map(|e_crate| (e_crate.get_task_name(), TaskStatus::Failed)));
+				}
+				let before_copy = plugin::run_stages(&config, StageHook::BeforeCopy).await;
+				let copy = plugin::all_succeeded(&before_copy);
+				results.extend(before_copy);
+				if copy {
+					let copy_futures = EFile::iter().map(|e_file| {
+						let config = config.clone();
+						async move {
+							if let Err(e) = e_file.copy_file_to_dist(&config).await {
+								error!("Failed to copy file: {}", e);
+							}
+						}
+					});
+					join_all(copy_futures).await;
+					results.extend(plugin::run_stages(&config, StageHook::AfterCopy).await);
+				} else {
+					error!("A before-copy stage failed, skipping the copy to dist");
+				}
+				// Update app with build and stage results directly
 				{
 					let mut app_guard = app.lock().await;
 					for (task_name, status) in results {
 						app_guard.tasks.insert(task_name, status);
 					}
 				}
-				let copy_futures = EFile::iter().map(|e_file| {
-					let config = config.clone();
-					async move {
-						if let Err(e) = e_file.copy_file_to_dist(&config).await {
-							error!("Failed to copy file: {}", e);
-						}
-					}
-				});
-				join_all(copy_futures).await;
 				// Finalize task state directly before cancelling
 				{
 					let mut app_guard = app.lock().await;
@@ -292,7 +382,7 @@ async fn main() -> io::Result<()> {
 				let _ = ui_handle.await;
 				show_final_build_report(app).await;
 			},
-			Commands::Init(_) => unreachable!(),
+			Commands::Init(_) | Commands::Config { .. } | Commands::External(_) => unreachable!(),
 		}
 	}
 	Ok(())
@@ -319,52 +409,69 @@ async fn hot_reload(config: ExtConfig, app: Arc<Mutex<App>>, cancel_token: Cance
 	let app_clone = app.clone();
 	{
 		let mut app_guard = app.lock().await;
-		for e_crate in ExtensionCrate::iter() {
+		for e_crate in ExtensionCrate::present(&config) {
 			app_guard.tasks.insert(e_crate.get_task_name(), TaskStatus::Pending);
 		}
+		for stage in &config.stages {
+			app_guard.tasks.insert(stage.get_task_name(), TaskStatus::Pending);
+		}
 	}
-	info!("Building extension crates....");
-	let build_futures = ExtensionCrate::iter().map(|e_crate| {
-		let config = config.clone();
-		let task_name = e_crate.get_task_name();
-		let task_name_clone = task_name.clone();
-		async move {
-			update_task_status(&task_name, TaskStatus::InProgress).await;
-			let progress_callback = move |progress| {
-				let task = task_name.clone();
-				tokio::spawn(async move {
-					send_ui_message(EXMessage::TaskProgress(task, progress)).await;
-				});
-			};
-			let result = e_crate.build_crate(&config, progress_callback).await;
-			let status = match &result {
-				Some(Ok(_)) => TaskStatus::Success,
-				Some(Err(e)) => {
-					error!("Failed to build {}: {:?}", e_crate.get_task_name(), e);
-					TaskStatus::Failed
-				},
-				None => TaskStatus::Failed,
-			};
-			update_task_status(&task_name_clone, status).await;
-			result
+	// as in `build`, a failed before-build or before-copy stage skips the step it comes before
+	if plugin::all_succeeded(&plugin::run_stages(&config, StageHook::BeforeBuild).await) {
+		info!("Building extension crates....");
+		let build_futures = ExtensionCrate::present(&config).into_iter().map(|e_crate| {
+			let config = config.clone();
+			let task_name = e_crate.get_task_name();
+			let task_name_clone = task_name.clone();
+			async move {
+				update_task_status(&task_name, TaskStatus::InProgress).await;
+				let progress_callback = move |progress| {
+					let task = task_name.clone();
+					tokio::spawn(async move {
+						send_ui_message(EXMessage::TaskProgress(task, progress)).await;
+					});
+				};
+				let result = e_crate.build_crate(&config, progress_callback).await;
+				let status = match &result {
+					Some(Ok(_)) => TaskStatus::Success,
+					Some(Err(e)) => {
+						error!("Failed to build {}: {:?}", e_crate.get_task_name(), e);
+						TaskStatus::Failed
+					},
+					None => TaskStatus::Failed,
+				};
+				update_task_status(&task_name_clone, status).await;
+				result
+			}
+		});
+		join_all(build_futures).await;
+		plugin::run_stages(&config, StageHook::AfterBuild).await;
+	} else {
+		error!("A before-build stage failed, skipping the build");
+		for e_crate in ExtensionCrate::present(&config) {
+			update_task_status(&e_crate.get_task_name(), TaskStatus::Failed).await;
 		}
-	});
-	join_all(build_futures).await;
+	}
 
-	let copy_futures = EFile::iter().map(|e_file| {
-		let config = config.clone();
-		async move {
-			PENDING_COPIES.insert(e_file);
-			let result = e_file.copy_file_to_dist(&config).await;
-			if let Err(e) = &result {
-				error!("Failed to copy file: {}", e);
-			} else {
-				PENDING_COPIES.remove(&e_file);
+	if plugin::all_succeeded(&plugin::run_stages(&config, StageHook::BeforeCopy).await) {
+		let copy_futures = EFile::iter().map(|e_file| {
+			let config = config.clone();
+			async move {
+				PENDING_COPIES.insert(e_file);
+				let result = e_file.copy_file_to_dist(&config).await;
+				if let Err(e) = &result {
+					error!("Failed to copy file: {}", e);
+				} else {
+					PENDING_COPIES.remove(&e_file);
+				}
+				result
 			}
-			result
-		}
-	});
-	join_all(copy_futures).await;
+		});
+		join_all(copy_futures).await;
+		plugin::run_stages(&config, StageHook::AfterCopy).await;
+	} else {
+		error!("A before-copy stage failed, skipping the copy to dist");
+	}
 	info!("Initial build completed, setting up file watcher...");
 	let (tx, rx) = mpsc::channel(100);
 	let mut watcher = RecommendedWatcher::new(
@@ -382,13 +489,15 @@ async fn hot_reload(config: ExtConfig, app: Arc<Mutex<App>>, cancel_token: Cance
 	for e_file in EFile::iter() {
 		let watch_path = ext_dir.join(e_file.get_watch_path(&config));
 		if watch_path.exists() {
-			watcher.watch(&watch_path, RecursiveMode::NonRecursive).with_context(|| format!("Failed to watch file: {e_file:?} at path {watch_path:?}"))?;
-		} else {
+			// `_locales/<locale>/messages.json` sits one level down
+			let mode = if watch_path.is_dir() { RecursiveMode::Recursive } else { RecursiveMode::NonRecursive };
+			watcher.watch(&watch_path, mode).with_context(|| format!("Failed to watch file: {e_file:?} at path {watch_path:?}"))?;
+		} else if !e_file.is_optional() {
 			warn!("Watch path does not exist: {:?}", watch_path);
 		}
 	}
 
-	for e_crate in ExtensionCrate::iter() {
+	for e_crate in ExtensionCrate::present(&config) {
 		let crate_src_path = ext_dir.join(e_crate.get_crate_name(&config)).join("src");
 		if crate_src_path.exists() {
 			watcher.watch(&crate_src_path, RecursiveMode::Recursive).with_context(|| format!("Failed to watch directory: {e_crate:?} at path {crate_src_path:?}"))?;
@@ -437,73 +546,26 @@ async fn watch_loop(mut rx: mpsc::Receiver<Event>, cancel_token: CancellationTok
 }
 
 async fn handle_event(event: &Event, config: &ExtConfig) {
-	if event.paths.iter().any(|path| {
-		let path_str = path.to_string_lossy();
-		path_str.contains(".tmp") || path_str.contains(".swp") || path_str.contains("~") || path_str.ends_with(".git")
-	}) {
+	let files = EFile::iter().map(|e_file| (e_file, e_file.get_watch_path(config))).collect::<Vec<_>>();
+	let crates = ExtensionCrate::present(config).into_iter().map(|e_crate| (e_crate, e_crate.get_crate_name(config))).collect::<Vec<_>>();
+	let Some(changes) = watch::classify(&event.paths, &files, &crates) else {
 		info!("Skipping temporary or non-relevant file: {:?}", event.paths);
 		return;
+	};
+	for e_file in changes.copies {
+		PENDING_COPIES.insert(e_file);
 	}
-
-	let copy_futures = event
-		.paths
-		.iter()
-		.flat_map(|path| {
-			let path_str = path.to_str().unwrap_or_default();
-			EFile::iter().filter(|e_file| path_str.contains(&e_file.get_watch_path(config)))
-		})
-		.collect::<Vec<_>>();
-
-	if !copy_futures.is_empty() {
-		for copy_file in copy_futures {
-			PENDING_COPIES.insert(copy_file);
-		}
-	}
-
-	if event.paths.iter().any(|path| path.to_str().unwrap_or_default().contains("api")) {
-		for ext_crate in ExtensionCrate::iter() {
-			PENDING_BUILDS.insert(ext_crate);
-		}
-	} else {
-		let builds: Vec<_> = event
-			.paths
-			.iter()
-			.flat_map(|path| {
-				let path_str = path.to_str().unwrap_or_default();
-				ExtensionCrate::iter().filter(move |e_crate| path_str.contains(&e_crate.get_crate_name(config)))
-			})
-			.collect();
-
-		if !builds.is_empty() {
-			for crate_type in &builds {
-				update_task_status(&crate_type.get_task_name(), TaskStatus::Pending).await;
-			}
-			for build in builds {
-				PENDING_BUILDS.insert(build);
-			}
+	for e_crate in changes.builds {
+		// a crate already waiting for its build is only marked once
+		if PENDING_BUILDS.insert(e_crate) {
+			update_task_status(&e_crate.get_task_name(), TaskStatus::Pending).await;
 		}
 	}
 }
 
 async fn process_pending_events(config: &ExtConfig, app: Arc<Mutex<App>>) {
-	let builds = {
-		if PENDING_BUILDS.is_empty() {
-			Vec::new()
-		} else {
-			let pending_builds = PENDING_BUILDS.iter().map(|item| *item).collect();
-			PENDING_BUILDS.clear();
-			pending_builds
-		}
-	};
-	let copies = {
-		if PENDING_COPIES.is_empty() {
-			Vec::new()
-		} else {
-			let pending_builds = PENDING_COPIES.iter().map(|item| *item).collect();
-			PENDING_COPIES.clear();
-			pending_builds
-		}
-	};
+	let mut builds = watch::take_pending(&PENDING_BUILDS);
+	let copies = watch::take_pending(&PENDING_COPIES);
 
 	if builds.is_empty() && copies.is_empty() {
 		return;
@@ -513,6 +575,12 @@ async fn process_pending_events(config: &ExtConfig, app: Arc<Mutex<App>>) {
 		let task_names: Vec<String> = builds.iter().map(|build| build.get_task_name()).collect();
 		let update_futures = task_names.iter().map(|task_name| update_task_status(task_name, TaskStatus::InProgress));
 		join_all(update_futures).await;
+		// as in `build`, a failed before-build or before-copy stage skips the step it comes before
+		if !plugin::all_succeeded(&plugin::run_stages(config, StageHook::BeforeBuild).await) {
+			error!("A before-build stage failed, skipping the build");
+			join_all(task_names.iter().map(|task_name| update_task_status(task_name, TaskStatus::Failed))).await;
+			builds.clear();
+		}
 	}
 
 	let build_results = join_all(builds.iter().map(|crate_type| {
@@ -537,12 +605,20 @@ async fn process_pending_events(config: &ExtConfig, app: Arc<Mutex<App>>) {
 		}
 	}))
 	.await;
+	if !builds.is_empty() {
+		plugin::run_stages(config, StageHook::AfterBuild).await;
+	}
 
 	if !copies.is_empty() {
-		for e_file in copies {
-			if let Err(e) = e_file.copy_file_to_dist(config).await {
-				error!("Error during copy: {}", e);
+		if plugin::all_succeeded(&plugin::run_stages(config, StageHook::BeforeCopy).await) {
+			for e_file in copies {
+				if let Err(e) = e_file.copy_file_to_dist(config).await {
+					error!("Error during copy: {}", e);
+				}
 			}
+			plugin::run_stages(config, StageHook::AfterCopy).await;
+		} else {
+			error!("A before-copy stage failed, skipping the copy to dist");
 		}
 	}
 
@@ -554,7 +630,7 @@ async fn process_pending_events(config: &ExtConfig, app: Arc<Mutex<App>>) {
 	}
 	// final task statuses
 	let mut app_lock = app.lock().await;
-	for e_crate in ExtensionCrate::iter() {
+	for e_crate in ExtensionCrate::present(config) {
 		let task_name = e_crate.get_task_name();
 		if let Some(status) = app_lock.tasks.get_mut(&task_name)
 			&& *status == TaskStatus::InProgress
@@ -0,0 +1,95 @@
+use {
+	crate::{
+		common::{ExtConfig, Stage, StageHook, TaskStatus},
+		update_task_status,
+	},
+	anyhow::{Context, Result},
+	std::{env, ffi::OsString, io, path::PathBuf, process::Stdio},
+	tokio::{
+		io::{AsyncBufReadExt, AsyncRead, BufReader},
+		process::Command,
+	},
+	tracing::{error, info},
+};
+
+const PLUGIN_PREFIX: &str = "dx-ext-";
+
+// `dx-ext <name> <args>` runs `dx-ext-<name> <args>` from the `PATH`, the way cargo runs `cargo-<name>`; `DX_EXT` points the
+// plugin back at this binary
+pub(crate) fn run_external(args: &[OsString]) -> io::Result<()> {
+	let Some((name, args)) = args.split_first() else {
+		return Err(io::Error::new(io::ErrorKind::InvalidInput, "no subcommand given"));
+	};
+	let program = format!("{PLUGIN_PREFIX}{}", name.to_string_lossy());
+	let Some(path) = find_plugin(&program) else {
+		return Err(io::Error::new(io::ErrorKind::NotFound, format!("no such command `{}`, and no `{program}` on the PATH", name.to_string_lossy())));
+	};
+	let status = std::process::Command::new(path).args(args).env("DX_EXT", env::current_exe()?).status()?;
+	if status.success() { Ok(()) } else { Err(io::Error::other(format!("`{program}` failed with {status}"))) }
+}
+
+fn find_plugin(program: &str) -> Option<PathBuf> {
+	let file_name = format!("{program}{}", env::consts::EXE_SUFFIX);
+	env::split_paths(&env::var_os("PATH")?).map(|dir| dir.join(&file_name)).find(|path| path.is_file())
+}
+
+impl Stage {
+	pub fn get_task_name(&self) -> String {
+		format!("Running {}", self.name)
+	}
+
+	async fn run(&self, config: &ExtConfig) -> Result<()> {
+		let (program, args) = self.command.split_first().context("The stage's command is empty")?;
+		let mut child = Command::new(program)
+			.args(args)
+			.current_dir(&config.config_dir)
+			.env("DX_EXT_EXTENSION_DIR", &config.extension_directory_name)
+			.env("DX_EXT_DIST_DIR", format!("{}/dist", config.extension_directory_name))
+			.env("DX_EXT_BUILD_MODE", config.build_mode.to_string())
+			.stdout(Stdio::piped())
+			.stderr(Stdio::piped())
+			.spawn()
+			.with_context(|| format!("Failed to start {program}"))?;
+		// the output goes to the log, tagged with the stage
+		let stdout = child.stdout.take().map(|stdout| tokio::spawn(log_lines(self.name.clone(), stdout)));
+		let stderr = child.stderr.take().map(|stderr| tokio::spawn(log_lines(self.name.clone(), stderr)));
+		let status = child.wait().await.with_context(|| format!("Failed to wait for {program}"))?;
+		for reader in [stdout, stderr].into_iter().flatten() {
+			let _ = reader.await;
+		}
+		anyhow::ensure!(status.success(), "{program} failed with {status}");
+		Ok(())
+	}
+}
+
+async fn log_lines(stage: String, output: impl AsyncRead + Unpin) {
+	let mut lines = BufReader::new(output).lines();
+	while let Ok(Some(line)) = lines.next_line().await {
+		info!("[{}] {}", stage, line);
+	}
+}
+
+// runs the stages hooked to `hook` one after the other, in the order dx-ext.toml lists them, and returns their task statuses
+pub(crate) async fn run_stages(config: &ExtConfig, hook: StageHook) -> Vec<(String, TaskStatus)> {
+	let mut results = Vec::new();
+	for stage in config.stages.iter().filter(|stage| stage.when == hook) {
+		let task_name = stage.get_task_name();
+		info!("Running {} stage {}", hook, stage.name);
+		update_task_status(&task_name, TaskStatus::InProgress).await;
+		let status = match stage.run(config).await {
+			Ok(()) => TaskStatus::Success,
+			Err(e) => {
+				error!("Stage {} failed: {:?}", stage.name, e);
+				TaskStatus::Failed
+			},
+		};
+		update_task_status(&task_name, status).await;
+		results.push((task_name, status));
+	}
+	results
+}
+
+// whether none of the stages `run_stages` ran failed, so the step they come before may run
+pub(crate) fn all_succeeded(results: &[(String, TaskStatus)]) -> bool {
+	results.iter().all(|(_, status)| *status != TaskStatus::Failed)
+}
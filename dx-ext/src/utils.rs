@@ -2,13 +2,14 @@ use stilts::Template;
 use {
 	crate::{
 		App,
-		common::{BuildMode, BuildState, ExtConfig, InitOptions, TaskStatus, TomlConfig},
+		common::{BuildMode, BuildState, ExtConfig, InitOptions, ProjectTemplate, TaskStatus, TomlConfig},
 	},
 	anyhow::{Context, Result},
 	dialoguer::{Confirm, Input},
+	serde_json::Value,
 	std::{fs, io::Write, path::Path, sync::Arc},
 	tokio::sync::Mutex,
-	tracing::info,
+	tracing::{info, warn},
 };
 
 #[derive(Template)]
@@ -16,6 +17,8 @@ use {
 struct WorkspaceCargoToml<'s> {
 	directory_name: &'s str,
 	popup_name: &'s str,
+	// further crates the template adds, each as ` "<dir>/<crate>",`
+	extra_members: &'s str,
 }
 
 #[derive(Template)]
@@ -54,17 +57,99 @@ struct IndexHtml {}
 
 #[derive(Template)]
 #[stilts(path = "manifest.json.j2")]
-struct ManifestJson {
+struct ManifestJson<'s> {
 	extension_name: String,
+	background_script: String,
+	content_script: String,
+	// the `"devtools_page"` entry when the template has one, followed by the indentation of the next key
+	devtools_page: &'s str,
+}
+
+#[derive(Template)]
+#[stilts(path = "devtools_cargo.toml.j2")]
+struct DevToolsCargoToml {}
+
+#[derive(Template)]
+#[stilts(path = "devtools_lib_rs.rs.j2")]
+struct DevToolsLibRs {}
+
+#[derive(Template)]
+#[stilts(path = "devtools.html.j2")]
+struct DevToolsHtml {}
+
+#[derive(Template)]
+#[stilts(path = "devtools_entry.js.j2")]
+struct DevToolsEntry {}
+
+#[derive(Template)]
+#[stilts(path = "devtools_panel.html.j2")]
+struct DevToolsPanelHtml {}
+
+#[derive(Template)]
+#[stilts(path = "devtools_panel_entry.js.j2")]
+struct DevToolsPanelEntry {}
+
+#[derive(Template)]
+#[stilts(path = "content_button_cargo.toml.j2")]
+struct ContentButtonCargoToml {}
+
+#[derive(Template)]
+#[stilts(path = "content_button_lib_rs.rs.j2")]
+struct ContentButtonLibRs {}
+
+#[derive(Template)]
+#[stilts(path = "content_button_background_lib_rs.rs.j2")]
+struct ContentButtonBackgroundLibRs {}
+
+// the JSON Schema of dx-ext.toml, for editors to complete and check it
+pub(crate) fn config_schema() -> Result<String> {
+	Ok(serde_json::to_string_pretty(&schemars::schema_for!(TomlConfig))?)
+}
+
+// follows a `$ref` of the config schema into `$defs`, if `node` has one
+fn resolve<'s>(schema: &'s Value, node: &'s Value) -> &'s Value {
+	node["$ref"].as_str().and_then(|reference| schema.pointer(reference.trim_start_matches('#'))).unwrap_or(node)
+}
+
+// collects the keys of `table` that `node` of the config schema has no property for, e.g. `extension-config.popup-nmae`
+fn unknown_keys(schema: &Value, node: &Value, table: &toml::Table, path: &str, unknown: &mut Vec<String>) {
+	let Some(properties) = resolve(schema, node)["properties"].as_object() else {
+		return;
+	};
+	for (key, value) in table {
+		let path = if path.is_empty() { key.clone() } else { format!("{path}.{key}") };
+		match (properties.get(key), value) {
+			(None, _) => unknown.push(path),
+			(Some(property), toml::Value::Table(table)) => unknown_keys(schema, property, table, &path, unknown),
+			(Some(property), toml::Value::Array(items)) => {
+				let item = &resolve(schema, property)["items"];
+				for (index, value) in items.iter().enumerate() {
+					if let toml::Value::Table(table) = value {
+						unknown_keys(schema, item, table, &format!("{path}[{index}]"), unknown);
+					}
+				}
+			},
+			_ => {},
+		}
+	}
 }
 
 pub(crate) fn read_config() -> Result<ExtConfig> {
 	let toml_content = fs::read_to_string("dx-ext.toml").context("Failed to read dx-ext.toml file")?;
 
 	let parsed_toml: TomlConfig = toml::from_str(&toml_content).context("Failed to parse dx-ext.toml file")?;
+	if let Ok(table) = toml_content.parse::<toml::Table>() {
+		let schema = serde_json::to_value(schemars::schema_for!(TomlConfig))?;
+		let mut unknown = Vec::new();
+		unknown_keys(&schema, &schema, &table, "", &mut unknown);
+		for key in unknown {
+			warn!("Ignoring unknown key `{key}` in dx-ext.toml");
+		}
+	}
 
 	// converting to our internal config structure
 	Ok(ExtConfig {
+		config_dir: std::env::current_dir().context("Failed to resolve the directory of dx-ext.toml")?,
 		background_script_index_name: parsed_toml.extension_config.background_script_index_name,
 		content_script_index_name: parsed_toml.extension_config.content_script_index_name,
 		extension_directory_name: parsed_toml.extension_config.extension_directory_name,
@@ -72,6 +157,7 @@ pub(crate) fn read_config() -> Result<ExtConfig> {
 		assets_dir: parsed_toml.extension_config.assets_directory,
 		build_mode: BuildMode::Development,
 		enable_incremental_builds: parsed_toml.extension_config.enable_incremental_builds,
+		stages: parsed_toml.stages,
 	})
 }
 
@@ -115,7 +201,7 @@ enable-incremental-builds = {enable_incremental_builds}
 	Ok(true)
 }
 
-pub(crate) fn generate_project_structure(config: &ExtConfig) -> Result<()> {
+pub(crate) fn generate_project_structure(config: &ExtConfig, template: ProjectTemplate) -> Result<()> {
 	if !Path::new(&config.extension_directory_name).exists() {
 		let _ = fs::create_dir_all(&config.extension_directory_name).context("Failed to create extension directory");
 	}
@@ -151,17 +237,28 @@ pub(crate) fn generate_project_structure(config: &ExtConfig) -> Result<()> {
 	create_html_file(&config.extension_directory_name)?;
 	create_js_entry_point(&config.extension_directory_name, "index.js", "popup")?;
 
+	match template {
+		ProjectTemplate::Default => {},
+		ProjectTemplate::DevTools => create_devtools_files(&config.extension_directory_name)?,
+		ProjectTemplate::ContentButton => create_content_button_files(&background_src_dir, &content_dir)?,
+	}
+
 	// manifest.json
-	create_manifest_json(&config.extension_directory_name)?;
+	create_manifest_json(&config.extension_directory_name, template)?;
 
 	info!("Project structure generated successfully");
 
 	Ok(())
 }
 
-fn create_workspace_cargo_toml() -> Result<()> {
+fn create_workspace_cargo_toml(template: ProjectTemplate) -> Result<()> {
 	let config = read_config()?;
-	let cargo_content = WorkspaceCargoToml { directory_name: &config.extension_directory_name, popup_name: &config.popup_name }.render()?;
+	let extra_members = match template {
+		ProjectTemplate::Default | ProjectTemplate::ContentButton => String::new(),
+		ProjectTemplate::DevTools => format!(" \"{}/devtools\",", config.extension_directory_name),
+	};
+	let cargo_content =
+		WorkspaceCargoToml { directory_name: &config.extension_directory_name, popup_name: &config.popup_name, extra_members: &extra_members }.render()?;
 	let pwd = std::env::current_dir()?;
 	let cargo_path = pwd.join("Cargo.toml");
 	let mut file = fs::File::create(&cargo_path).context("Failed to create workspace Cargo.toml".to_owned())?;
@@ -218,18 +315,60 @@ fn create_html_file(base_dir: &str) -> Result<()> {
 	Ok(())
 }
 
-fn create_manifest_json(base_dir: &str) -> Result<()> {
-	let manifest_content = ManifestJson { extension_name: read_config()?.extension_directory_name }.render()?;
+// the devtools page, which adds the panel when the developer tools open, and the panel itself, both run by the `devtools` crate
+fn create_devtools_files(base_dir: &str) -> Result<()> {
+	let src_dir = format!("{base_dir}/devtools/src");
+	fs::create_dir_all(&src_dir).context("Failed to create devtools source directory")?;
+	let files = [
+		(format!("{base_dir}/devtools/Cargo.toml"), DevToolsCargoToml {}.render()?),
+		(format!("{src_dir}/lib.rs"), DevToolsLibRs {}.render()?),
+		(format!("{base_dir}/devtools.html"), DevToolsHtml {}.render()?),
+		(format!("{base_dir}/devtools_index.js"), DevToolsEntry {}.render()?),
+		(format!("{base_dir}/devtools_panel.html"), DevToolsPanelHtml {}.render()?),
+		(format!("{base_dir}/devtools_panel_index.js"), DevToolsPanelEntry {}.render()?),
+	];
+	for (path, content) in files {
+		fs::write(&path, content).context(format!("Failed to write {path}"))?;
+	}
+	Ok(())
+}
+
+// replaces the stub background and content crates with a content script that injects a floating button, and a background that answers it
+fn create_content_button_files(background_src_dir: &str, content_dir: &str) -> Result<()> {
+	let files = [
+		(format!("{background_src_dir}/lib.rs"), ContentButtonBackgroundLibRs {}.render()?),
+		(format!("{content_dir}/Cargo.toml"), ContentButtonCargoToml {}.render()?),
+		(format!("{content_dir}/src/lib.rs"), ContentButtonLibRs {}.render()?),
+	];
+	for (path, content) in files {
+		fs::write(&path, content).context(format!("Failed to write {path}"))?;
+	}
+	Ok(())
+}
+
+fn create_manifest_json(base_dir: &str, template: ProjectTemplate) -> Result<()> {
+	let devtools_page = match template {
+		ProjectTemplate::Default | ProjectTemplate::ContentButton => "",
+		ProjectTemplate::DevTools => "\"devtools_page\": \"devtools.html\",\n  ",
+	};
+	let config = read_config()?;
+	let manifest_content = ManifestJson {
+		extension_name: config.extension_directory_name,
+		background_script: config.background_script_index_name,
+		content_script: config.content_script_index_name,
+		devtools_page,
+	}
+	.render()?;
 	let manifest_path = format!("{base_dir}/manifest.json");
 	let mut file = fs::File::create(&manifest_path).context("Failed to create manifest.json")?;
 	file.write_all(manifest_content.as_bytes()).context("Failed to write to manifest.json")?;
 	Ok(())
 }
 
-pub fn setup_project_from_config() -> Result<()> {
+pub fn setup_project_from_config(template: ProjectTemplate) -> Result<()> {
 	let config = crate::read_config()?;
-	generate_project_structure(&config)?;
-	create_workspace_cargo_toml()?;
+	generate_project_structure(&config, template)?;
+	create_workspace_cargo_toml(template)?;
 	init_git()?;
 	Ok(())
 }
@@ -0,0 +1,42 @@
+// what file system events ask the watcher to do; like `copy.rs` this module doesn't use the rest of the crate, so
+// `benches/watch.rs` can include it
+use dashmap::DashSet;
+use std::{hash::Hash, path::PathBuf};
+
+// the files to copy again and the crates to rebuild for one event, possibly with repeats
+pub(crate) struct Changes<F, C> {
+	pub copies: Vec<F>,
+	pub builds: Vec<C>,
+}
+
+// `files` and `crates` pair every watched file and crate with the part of a path that points to it; `None` when the event is
+// about a temporary or otherwise irrelevant file
+pub(crate) fn classify<F: Copy, C: Copy>(paths: &[PathBuf], files: &[(F, String)], crates: &[(C, String)]) -> Option<Changes<F, C>> {
+	let paths = paths.iter().map(|path| path.to_string_lossy()).collect::<Vec<_>>();
+	if paths.iter().any(|path| path.contains(".tmp") || path.contains(".swp") || path.contains('~') || path.ends_with(".git")) {
+		return None;
+	}
+	let copies = paths.iter().flat_map(|path| matching(files, path)).collect();
+	// shared API code is used by every crate
+	let builds = if paths.iter().any(|path| path.contains("api")) {
+		crates.iter().map(|(e_crate, _)| *e_crate).collect()
+	} else {
+		paths.iter().flat_map(|path| matching(crates, path)).collect()
+	};
+	Some(Changes { copies, builds })
+}
+
+fn matching<'t, T: Copy>(targets: &'t [(T, String)], path: &'t str) -> impl Iterator<Item = T> + 't {
+	targets.iter().filter(move |(_, name)| path.contains(name.as_str())).map(|(target, _)| *target)
+}
+
+// empties `pending` and returns what it held; entries added while it is being emptied are either returned or left for the next
+// time, never lost
+pub(crate) fn take_pending<T: Copy + Eq + Hash>(pending: &DashSet<T>) -> Vec<T> {
+	let mut taken = Vec::new();
+	pending.retain(|item| {
+		taken.push(*item);
+		false
+	});
+	taken
+}
@@ -0,0 +1,46 @@
+//! Tests for `dx-ext config schema`: the schema has to know every key `init` writes to `dx-ext.toml`, and no others, and reject
+//! keys it doesn't know, so editors flag a misspelled one.
+
+use serde_json::Value;
+use std::{collections::BTreeSet, process::Command};
+
+fn schema() -> Value {
+	let output = Command::new(env!("CARGO_BIN_EXE_dx-ext")).args(["config", "schema"]).output().unwrap();
+	assert!(output.status.success(), "config schema failed: {}", String::from_utf8_lossy(&output.stderr));
+	serde_json::from_slice(&output.stdout).unwrap()
+}
+
+// follows a `$ref` into `$defs`, if the table has one
+fn resolve<'s>(schema: &'s Value, table: &'s Value) -> &'s Value {
+	match table["$ref"].as_str() {
+		Some(reference) => schema.pointer(reference.trim_start_matches('#')).unwrap(),
+		None => table,
+	}
+}
+
+fn keys(table: &Value) -> BTreeSet<&str> {
+	table["properties"].as_object().unwrap().keys().map(String::as_str).collect()
+}
+
+#[test]
+fn schema_covers_the_generated_config() {
+	let dir = tempfile::tempdir().unwrap();
+	let output = Command::new(env!("CARGO_BIN_EXE_dx-ext")).arg("init").current_dir(dir.path()).output().unwrap();
+	assert!(output.status.success(), "init failed: {}", String::from_utf8_lossy(&output.stderr));
+	let config = std::fs::read_to_string(dir.path().join("dx-ext.toml")).unwrap().parse::<toml::Table>().unwrap();
+
+	let schema = schema();
+	assert_eq!(keys(&schema), BTreeSet::from(["extension-config", "stage"]));
+	let extension_config = resolve(&schema, &schema["properties"]["extension-config"]);
+	assert_eq!(keys(extension_config), config["extension-config"].as_table().unwrap().keys().map(String::as_str).collect());
+}
+
+#[test]
+fn schema_rejects_unknown_keys() {
+	let schema = schema();
+	assert_eq!(schema["additionalProperties"], false);
+	assert_eq!(resolve(&schema, &schema["properties"]["extension-config"])["additionalProperties"], false);
+	let stage = resolve(&schema, &schema["properties"]["stage"]["items"]);
+	assert_eq!(stage["additionalProperties"], false);
+	assert_eq!(keys(stage), BTreeSet::from(["command", "name", "when"]));
+}
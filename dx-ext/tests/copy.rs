@@ -0,0 +1,181 @@
+//! Property tests for copying into `dist`: `needs_copy` has to tell files apart by their content alone, whatever their sizes and
+//! modification times do, `calculate_file_hash` may only answer from its cache while a file keeps its modification time and
+//! size, and `copy_dir_all` has to leave an exact copy of any tree behind while copying only the files that changed.
+
+#[path = "../src/copy.rs"]
+mod copy;
+
+use copy::{calculate_file_hash, copy_dir_all, needs_copy};
+use proptest::prelude::*;
+use std::{
+	collections::BTreeMap,
+	fs::{self, File},
+	path::{Path, PathBuf},
+	time::{Duration, SystemTime},
+};
+
+fn block_on<F: Future>(future: F) -> F::Output {
+	tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(future)
+}
+
+// writes `content` to `path` and gives it the modification time `modified`
+fn write(path: &Path, content: &[u8], modified: SystemTime) {
+	fs::create_dir_all(path.parent().unwrap()).unwrap();
+	fs::write(path, content).unwrap();
+	File::options().write(true).open(path).unwrap().set_modified(modified).unwrap();
+}
+
+fn hash(content: &[u8]) -> String {
+	blake3::hash(content).to_hex().to_string()
+}
+
+// a day ago, so that moving it forward still stays in the past
+fn yesterday() -> SystemTime {
+	SystemTime::now() - Duration::from_secs(24 * 60 * 60)
+}
+
+fn shifted(time: SystemTime, seconds: i64) -> SystemTime {
+	let shift = Duration::from_secs(seconds.unsigned_abs());
+	if seconds < 0 { time - shift } else { time + shift }
+}
+
+fn content() -> impl Strategy<Value = Vec<u8>> {
+	prop::collection::vec(any::<u8>(), 0..256)
+}
+
+// the other content has the same size, so only the hashes can tell them apart
+fn same_size() -> impl Strategy<Value = (Vec<u8>, Vec<u8>)> {
+	content().prop_flat_map(|content| {
+		let len = content.len();
+		(Just(content), prop::collection::vec(any::<u8>(), len))
+	})
+}
+
+// two contents that are unrelated, of the same size or the same
+fn pair() -> impl Strategy<Value = (Vec<u8>, Vec<u8>)> {
+	prop_oneof![(content(), content()), same_size(), content().prop_map(|content| (content.clone(), content))]
+}
+
+// seconds to move a modification time by, back as often as forward, never zero
+fn shift() -> impl Strategy<Value = i64> {
+	prop_oneof![-3600_i64..=-1, 1_i64..=3600]
+}
+
+// up to three levels of non-ASCII names, none of the files in the place of another's directory
+fn tree() -> impl Strategy<Value = BTreeMap<PathBuf, Vec<u8>>> {
+	let path = prop::collection::vec("[a-z0-9äöüñ日本語_-]{1,8}", 1..=3).prop_map(|names| names.iter().collect::<PathBuf>());
+	prop::collection::btree_map(path, content(), 1..12)
+		.prop_filter("a file can't be a directory too", |files| !files.keys().any(|file| files.keys().any(|other| other != file && other.starts_with(file))))
+}
+
+proptest! {
+	#![proptest_config(ProptestConfig::with_cases(64))]
+
+	#[test]
+	fn needs_copy_compares_content((src_content, dest_content) in pair(), src_shift in shift(), dest_shift in shift()) {
+		let dir = tempfile::tempdir().unwrap();
+		let (src, dest) = (dir.path().join("src"), dir.path().join("dest"));
+		let time = yesterday();
+		write(&src, &src_content, shifted(time, src_shift));
+		write(&dest, &dest_content, shifted(time, dest_shift));
+		prop_assert_eq!(block_on(needs_copy(&src, &dest)).unwrap(), src_content != dest_content);
+	}
+
+	#[test]
+	fn missing_destinations_need_a_copy(content in content()) {
+		let dir = tempfile::tempdir().unwrap();
+		let src = dir.path().join("src");
+		write(&src, &content, yesterday());
+		prop_assert!(block_on(needs_copy(&src, &dir.path().join("dest"))).unwrap());
+	}
+
+	#[test]
+	fn changes_on_either_side_are_noticed_whichever_way_the_time_moves((before, after) in pair(), shift in shift(), change_dest in any::<bool>()) {
+		let dir = tempfile::tempdir().unwrap();
+		let (src, dest) = (dir.path().join("src"), dir.path().join("dest"));
+		let time = yesterday();
+		write(&src, &before, time);
+		write(&dest, &before, time);
+		// hashes both files, which puts them in the cache
+		prop_assert!(!block_on(needs_copy(&src, &dest)).unwrap());
+		write(if change_dest { &dest } else { &src }, &after, shifted(time, shift));
+		prop_assert_eq!(block_on(needs_copy(&src, &dest)).unwrap(), before != after);
+	}
+
+	#[test]
+	fn hashes_follow_the_content(before in content(), after in content(), shift in shift()) {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("file");
+		let time = yesterday();
+		write(&path, &before, time);
+		prop_assert_eq!(block_on(calculate_file_hash(&path)).unwrap(), hash(&before));
+		write(&path, &after, shifted(time, shift));
+		prop_assert_eq!(block_on(calculate_file_hash(&path)).unwrap(), hash(&after));
+	}
+
+	#[test]
+	fn hashes_are_cached_while_time_and_size_stay((before, after) in same_size()) {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("file");
+		let time = yesterday();
+		write(&path, &before, time);
+		prop_assert_eq!(block_on(calculate_file_hash(&path)).unwrap(), hash(&before));
+		write(&path, &after, time);
+		prop_assert_eq!(block_on(calculate_file_hash(&path)).unwrap(), hash(&before));
+	}
+
+	#[test]
+	fn copies_mirror_the_tree(files in tree()) {
+		let dir = tempfile::tempdir().unwrap();
+		let (src, dst) = (dir.path().join("src"), dir.path().join("dst"));
+		let time = yesterday();
+		for (path, content) in &files {
+			write(&src.join(path), content, time);
+		}
+		prop_assert_eq!(block_on(copy_dir_all(&src, &dst)).unwrap(), files.len());
+		for (path, content) in &files {
+			prop_assert_eq!(&fs::read(dst.join(path)).unwrap(), content);
+		}
+		prop_assert_eq!(block_on(copy_dir_all(&src, &dst)).unwrap(), 0);
+	}
+
+	#[test]
+	fn only_changed_files_are_copied_again(files in tree(), changes in prop::collection::vec(prop::option::of((any::<bool>(), content(), shift())), 12)) {
+		let dir = tempfile::tempdir().unwrap();
+		let (src, dst) = (dir.path().join("src"), dir.path().join("dst"));
+		let time = yesterday();
+		for (path, content) in &files {
+			write(&src.join(path), content, time);
+		}
+		block_on(copy_dir_all(&src, &dst)).unwrap();
+
+		let mut changed = 0;
+		let mut files = files;
+		for ((path, content), change) in files.iter_mut().zip(changes) {
+			let Some((same_size, replacement, shift)) = change else {
+				continue;
+			};
+			let replacement = if same_size { content.iter().map(|byte| byte.wrapping_add(1)).collect() } else { replacement };
+			if replacement != *content {
+				changed += 1;
+			}
+			write(&src.join(path), &replacement, shifted(time, shift));
+			*content = replacement;
+		}
+		prop_assert_eq!(block_on(copy_dir_all(&src, &dst)).unwrap(), changed);
+		for (path, content) in &files {
+			prop_assert_eq!(&fs::read(dst.join(path)).unwrap(), content);
+		}
+	}
+}
+
+#[test]
+fn empty_files_in_nested_directories_are_copied_once() {
+	let dir = tempfile::tempdir().unwrap();
+	let (src, dst) = (dir.path().join("src"), dir.path().join("dst"));
+	let path = Path::new("ünïcödé/日本語/空.txt");
+	write(&src.join(path), b"", yesterday());
+	assert_eq!(block_on(copy_dir_all(&src, &dst)).unwrap(), 1);
+	assert_eq!(fs::read(dst.join(path)).unwrap(), b"");
+	assert_eq!(block_on(copy_dir_all(&src, &dst)).unwrap(), 0);
+}
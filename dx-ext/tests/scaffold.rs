@@ -0,0 +1,205 @@
+//! Runs the `dx-ext` binary in a temporary directory: `init` with its options and templates, and, when `DX_EXT_E2E` is set, a
+//! full `build` of the scaffolded project into `dist`. The build needs `wasm-pack`, the `wasm32-unknown-unknown` target, network
+//! access for the generated crates' dependencies and a terminal for the TUI, so it is skipped unless asked for.
+
+use std::{
+	fs,
+	path::Path,
+	process::{Command, Output},
+};
+use tempfile::TempDir;
+
+fn dx_ext(dir: &Path, args: &[&str]) -> Output {
+	Command::new(env!("CARGO_BIN_EXE_dx-ext")).args(args).current_dir(dir).output().expect("dx-ext should start")
+}
+
+// a fresh project from `dx-ext init` and `args`
+fn init(args: &[&str]) -> TempDir {
+	let dir = tempfile::tempdir().unwrap();
+	let output = dx_ext(dir.path(), &[["init"].as_slice(), args].concat());
+	assert!(output.status.success(), "init failed: {}", String::from_utf8_lossy(&output.stderr));
+	dir
+}
+
+fn assert_files(root: &Path, files: &[&str]) {
+	for file in files {
+		assert!(root.join(file).is_file(), "{file} is missing");
+	}
+}
+
+fn read_toml(path: &Path) -> toml::Table {
+	fs::read_to_string(path).unwrap().parse().unwrap_or_else(|e| panic!("{} is not valid TOML: {e}", path.display()))
+}
+
+fn read_manifest(path: &Path) -> serde_json::Value {
+	serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap_or_else(|e| panic!("{} is not valid JSON: {e}", path.display()))
+}
+
+fn members(root: &Path) -> Vec<String> {
+	read_toml(&root.join("Cargo.toml"))["workspace"]["members"].as_array().unwrap().iter().map(|member| member.as_str().unwrap().to_owned()).collect()
+}
+
+#[test]
+fn init_scaffolds_the_default_project() {
+	let dir = init(&[]);
+	let root = dir.path();
+	assert_files(
+		root,
+		&[
+			"dx-ext.toml",
+			"Cargo.toml",
+			".gitignore",
+			"extension/manifest.json",
+			"extension/index.html",
+			"extension/index.js",
+			"extension/background_index.js",
+			"extension/content_index.js",
+			"extension/popup/Cargo.toml",
+			"extension/popup/src/lib.rs",
+			"extension/background/Cargo.toml",
+			"extension/background/src/lib.rs",
+			"extension/content/Cargo.toml",
+			"extension/content/src/lib.rs",
+		],
+	);
+	assert!(root.join("extension/popup/assets").is_dir());
+	assert!(!root.join("extension/devtools").exists());
+
+	let config = read_toml(&root.join("dx-ext.toml"));
+	let config = config["extension-config"].as_table().unwrap();
+	assert_eq!(config["extension-directory-name"].as_str(), Some("extension"));
+	assert_eq!(config["popup-name"].as_str(), Some("popup"));
+	assert_eq!(config["assets-directory"].as_str(), Some("popup/assets"));
+
+	assert_eq!(members(root), ["extension/popup", "extension/content", "extension/background"]);
+	for e_crate in ["popup", "background", "content"] {
+		let manifest = read_toml(&root.join(format!("extension/{e_crate}/Cargo.toml")));
+		assert_eq!(manifest["package"]["name"].as_str(), Some(e_crate));
+		assert_eq!(manifest["dependencies"]["wasm-bindgen"]["workspace"].as_bool(), Some(true));
+	}
+
+	let manifest = read_manifest(&root.join("extension/manifest.json"));
+	assert_eq!(manifest["manifest_version"], 3);
+	assert_eq!(manifest["name"], "extension");
+	assert_eq!(manifest["background"]["service_worker"], "background_index.js");
+	assert_eq!(manifest["content_scripts"][0]["js"][0], "content_index.js");
+	assert_eq!(manifest["action"]["default_popup"], "index.html");
+	assert!(manifest.get("devtools_page").is_none());
+
+	let popup_entry = fs::read_to_string(root.join("extension/index.js")).unwrap();
+	assert!(popup_entry.contains(r#"chrome.runtime.getURL("popup_bg.wasm")"#));
+	assert!(popup_entry.contains("{ module_or_path: wasmPath }"));
+}
+
+#[test]
+fn init_uses_the_given_names() {
+	let dir = init(&["--extension-dir", "my-ext", "--popup-name", "my-popup", "--background-script", "worker.js", "--content-script", "page.js"]);
+	let root = dir.path();
+	assert_files(root, &["my-ext/manifest.json", "my-ext/worker.js", "my-ext/page.js", "my-ext/my-popup/Cargo.toml", "my-ext/my-popup/src/lib.rs"]);
+	assert!(!root.join("extension").exists());
+
+	let config = read_toml(&root.join("dx-ext.toml"));
+	let config = config["extension-config"].as_table().unwrap();
+	assert_eq!(config["assets-directory"].as_str(), Some("my-popup/assets"));
+	assert_eq!(config["background-script-index-name"].as_str(), Some("worker.js"));
+	assert_eq!(config["content-script-index-name"].as_str(), Some("page.js"));
+
+	assert_eq!(members(root), ["my-ext/my-popup", "my-ext/content", "my-ext/background"]);
+	let manifest = read_manifest(&root.join("my-ext/manifest.json"));
+	assert_eq!(manifest["name"], "my-ext");
+	assert_eq!(manifest["background"]["service_worker"], "worker.js");
+	assert_eq!(manifest["content_scripts"][0]["js"][0], "page.js");
+	// wasm-pack names its output after the crate, with dashes turned into underscores
+	assert!(fs::read_to_string(root.join("my-ext/index.js")).unwrap().contains(r#"chrome.runtime.getURL("my_popup.js")"#));
+}
+
+#[test]
+fn init_keeps_an_existing_config_unless_forced() {
+	let dir = init(&[]);
+	let root = dir.path();
+	fs::write(root.join("dx-ext.toml"), "# edited\n").unwrap();
+
+	assert!(dx_ext(root, &["init", "--popup-name", "other"]).status.success());
+	assert_eq!(fs::read_to_string(root.join("dx-ext.toml")).unwrap(), "# edited\n");
+	assert!(!root.join("extension/other").exists());
+
+	assert!(dx_ext(root, &["init", "--popup-name", "other", "--force"]).status.success());
+	assert!(fs::read_to_string(root.join("dx-ext.toml")).unwrap().contains(r#"popup-name = "other""#));
+	assert_files(root, &["extension/other/Cargo.toml"]);
+}
+
+#[test]
+fn devtools_template_adds_the_devtools_page_and_panel() {
+	let dir = init(&["--template", "devtools"]);
+	let root = dir.path();
+	assert_files(
+		root,
+		&[
+			"extension/devtools/Cargo.toml",
+			"extension/devtools/src/lib.rs",
+			"extension/devtools.html",
+			"extension/devtools_index.js",
+			"extension/devtools_panel.html",
+			"extension/devtools_panel_index.js",
+		],
+	);
+	assert!(members(root).contains(&"extension/devtools".to_owned()));
+	assert_eq!(read_toml(&root.join("extension/devtools/Cargo.toml"))["package"]["name"].as_str(), Some("devtools"));
+	assert_eq!(read_manifest(&root.join("extension/manifest.json"))["devtools_page"], "devtools.html");
+}
+
+#[test]
+fn content_button_template_replaces_the_content_and_background_crates() {
+	let dir = init(&["--template", "content-button"]);
+	let root = dir.path();
+	assert!(!root.join("extension/devtools").exists());
+	assert_eq!(members(root), ["extension/popup", "extension/content", "extension/background"]);
+
+	let content = read_toml(&root.join("extension/content/Cargo.toml"));
+	assert_eq!(content["package"]["name"].as_str(), Some("content"));
+	let features = content["dependencies"]["web-sys"]["features"].as_array().unwrap();
+	assert!(features.iter().any(|feature| feature.as_str() == Some("ShadowRoot")));
+	assert!(fs::read_to_string(root.join("extension/content/src/lib.rs")).unwrap().contains("attach_shadow"));
+	assert!(fs::read_to_string(root.join("extension/background/src/lib.rs")).unwrap().contains("onMessage"));
+}
+
+#[test]
+fn unknown_templates_are_rejected() {
+	let dir = tempfile::tempdir().unwrap();
+	let output = dx_ext(dir.path(), &["init", "--template", "sidebar"]);
+	assert!(!output.status.success());
+	assert!(!dir.path().join("dx-ext.toml").exists());
+}
+
+#[test]
+fn build_puts_a_loadable_extension_in_dist() {
+	// passes without doing anything unless asked for, see the module documentation
+	if std::env::var_os("DX_EXT_E2E").is_none() {
+		return;
+	}
+	let dir = init(&["--template", "devtools"]);
+	let root = dir.path();
+	let output = dx_ext(root, &["build", "--mode", "release"]);
+	let stdout = String::from_utf8_lossy(&output.stdout);
+	assert!(output.status.success() && stdout.contains("Build completed successfully"), "build failed:\n{stdout}");
+
+	let dist = root.join("extension/dist");
+	assert_files(
+		&dist,
+		&[
+			"manifest.json",
+			"index.html",
+			"index.js",
+			"background_index.js",
+			"content_index.js",
+			"devtools.html",
+			"devtools_index.js",
+			"devtools_panel.html",
+			"devtools_panel_index.js",
+		],
+	);
+	for e_crate in ["popup", "background", "content", "devtools"] {
+		assert_files(&dist, &[&format!("{e_crate}.js"), &format!("{e_crate}_bg.wasm")]);
+	}
+	assert_eq!(read_manifest(&dist.join("manifest.json")), read_manifest(&root.join("extension/manifest.json")));
+}
@@ -0,0 +1,97 @@
+//! Golden-file tests for the templates `dx-ext init` renders. Each case runs `init` with a representative set of options in a
+//! temporary directory and compares every generated file with its snapshot in `tests/snapshots/<case>`. Snapshots carry an extra
+//! `.snap` extension, so they are never taken for a real `Cargo.toml` or `.gitignore`. After a deliberate template change, run the
+//! tests with `UPDATE_SNAPSHOTS=1` to rewrite them and review the diff.
+
+use std::{
+	collections::BTreeMap,
+	ffi::OsString,
+	fs,
+	path::{Path, PathBuf},
+	process::Command,
+};
+use walkdir::WalkDir;
+
+const SNAPSHOT_EXTENSION: &str = "snap";
+
+// every file under `root` by its path relative to it, leaving out the repository `init` creates
+fn files(root: &Path) -> BTreeMap<PathBuf, String> {
+	WalkDir::new(root)
+		.into_iter()
+		.filter_entry(|entry| entry.file_name() != ".git")
+		.map(Result::unwrap)
+		.filter(|entry| entry.file_type().is_file())
+		.map(|entry| (entry.path().strip_prefix(root).unwrap().to_owned(), fs::read_to_string(entry.path()).unwrap()))
+		.collect()
+}
+
+fn snapshot_path(snapshots: &Path, path: &Path) -> PathBuf {
+	let mut snapshot = OsString::from(snapshots.join(path));
+	snapshot.push(".");
+	snapshot.push(SNAPSHOT_EXTENSION);
+	snapshot.into()
+}
+
+// the output is checked for leftover placeholders and broken TOML or JSON first, so an update never writes those into a snapshot
+fn assert_valid(path: &Path, content: &str) {
+	assert!(!content.contains("{%"), "{} has an unrendered placeholder", path.display());
+	match path.extension().and_then(|extension| extension.to_str()) {
+		Some("toml") => {
+			content.parse::<toml::Table>().unwrap_or_else(|e| panic!("{} is not valid TOML: {e}", path.display()));
+		},
+		Some("json") => {
+			serde_json::from_str::<serde_json::Value>(content).unwrap_or_else(|e| panic!("{} is not valid JSON: {e}", path.display()));
+		},
+		_ => {},
+	}
+}
+
+fn assert_snapshots(case: &str, args: &[&str]) {
+	let dir = tempfile::tempdir().unwrap();
+	let output = Command::new(env!("CARGO_BIN_EXE_dx-ext")).arg("init").args(args).current_dir(dir.path()).output().unwrap();
+	assert!(output.status.success(), "init failed: {}", String::from_utf8_lossy(&output.stderr));
+	let generated = files(dir.path());
+	for (path, content) in &generated {
+		assert_valid(path, content);
+	}
+
+	let snapshots = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots").join(case);
+	if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+		let _ = fs::remove_dir_all(&snapshots);
+		for (path, content) in &generated {
+			let snapshot = snapshot_path(&snapshots, path);
+			fs::create_dir_all(snapshot.parent().unwrap()).unwrap();
+			fs::write(snapshot, content).unwrap();
+		}
+		return;
+	}
+
+	let expected: BTreeMap<PathBuf, String> = files(&snapshots).into_iter().map(|(path, content)| (path.with_extension(""), content)).collect();
+	assert_eq!(generated.keys().collect::<Vec<_>>(), expected.keys().collect::<Vec<_>>(), "{case} generates other files than its snapshots");
+	for (path, content) in &generated {
+		assert_eq!(content, &expected[path], "{case}/{} differs from its snapshot, rerun with UPDATE_SNAPSHOTS=1 if that is intended", path.display());
+	}
+}
+
+#[test]
+fn default_project() {
+	assert_snapshots("default", &[]);
+}
+
+#[test]
+fn custom_names() {
+	assert_snapshots(
+		"custom",
+		&["--extension-dir", "my-ext", "--popup-name", "my-popup", "--background-script", "worker.js", "--content-script", "page.js", "-e"],
+	);
+}
+
+#[test]
+fn devtools_template() {
+	assert_snapshots("devtools", &["--template", "devtools"]);
+}
+
+#[test]
+fn content_button_template() {
+	assert_snapshots("content-button", &["--template", "content-button"]);
+}
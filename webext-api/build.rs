@@ -0,0 +1,28 @@
+use std::{env, fs};
+
+// With `WEBEXT_MANIFEST` pointing at the extension's manifest.json, every declared permission becomes a `webext_permission` cfg, and the
+// `Browser` accessors for namespaces whose permission is missing are marked deprecated so the call sites warn at compile time.
+fn main() {
+	println!("cargo::rustc-check-cfg=cfg(webext_manifest_checked)");
+	println!("cargo::rustc-check-cfg=cfg(webext_permission, values(any()))");
+	println!("cargo::rerun-if-env-changed=WEBEXT_MANIFEST");
+	let Ok(path) = env::var("WEBEXT_MANIFEST") else {
+		return;
+	};
+	println!("cargo::rerun-if-changed={path}");
+	let manifest: serde_json::Value = match fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|s| serde_json::from_str(&s).map_err(|e| e.to_string()))
+	{
+		Ok(manifest) => manifest,
+		Err(e) => {
+			println!("cargo::warning=could not read WEBEXT_MANIFEST `{path}`, skipping the permission check: {e}");
+			return;
+		},
+	};
+	println!("cargo::rustc-cfg=webext_manifest_checked");
+	// optional permissions still have to be granted at runtime, but the namespace exists once they are
+	for key in ["permissions", "optional_permissions"] {
+		for permission in manifest[key].as_array().into_iter().flatten().filter_map(|p| p.as_str()) {
+			println!("cargo::rustc-cfg=webext_permission=\"{permission}\"");
+		}
+	}
+}
@@ -1,29 +1,40 @@
 use crate::{
 	error::ExtensionError,
-	types::{BadgeConfig, BrowserType},
-	utils::{call_async_fn, get_api_namespace},
+	types::{ActionIcon, ActionUserSettings, BadgeConfig, BrowserType, EventStream, ListenerHandle, Once, TabInfo, attach_listener},
+	utils::{call_async_fn, call_async_fn_and_de, get_api_namespace, tab_details},
 };
+use js_sys::{Object, Reflect};
+use wasm_bindgen::{JsValue, prelude::*};
 
 #[derive(Clone)]
 pub struct Action {
 	api: js_sys::Object,
+	browser_type: BrowserType,
 }
 
 impl Action {
-	pub(crate) fn new(api_root: &js_sys::Object, browser_type: BrowserType) -> Self {
+	// MV2 Firefox and Safari only have `browserAction`; the rest of the namespace is the same apart from Firefox's window scopes
+	pub(crate) fn new(api_root: &js_sys::Object, browser_type: BrowserType) -> Result<Self, ExtensionError> {
 		let api = match browser_type {
-			BrowserType::Firefox => get_api_namespace(api_root, "action").or_else(|_| get_api_namespace(api_root, "browserAction")),
+			BrowserType::Firefox | BrowserType::Safari => get_api_namespace(api_root, "action").or_else(|_| get_api_namespace(api_root, "browserAction")),
 			_ => get_api_namespace(api_root, "action"),
-		}
-		.expect("Could not find action API namespace");
-		Self { api }
+		}?;
+		Ok(Self { api, browser_type })
 	}
 
+	/// Applies every field that is set: text, background color and text color, for one tab, one window (Firefox) or globally.
 	pub async fn set_badge_text(&self, config: BadgeConfig) -> Result<(), ExtensionError> {
-		let details = serde_wasm_bindgen::to_value(&config)?;
-		call_async_fn(&self.api, "setBadgeText", &[details.clone()][..]).await?;
-		if config.background_color.is_some() {
-			call_async_fn(&self.api, "setBadgeBackgroundColor", &[details][..]).await?;
+		let updates = [
+			("setBadgeText", "text", &config.text),
+			("setBadgeBackgroundColor", "color", &config.background_color),
+			("setBadgeTextColor", "color", &config.text_color),
+		];
+		for (method, key, value) in updates {
+			if let Some(value) = value {
+				let details = self.scope_details(config.tab_id, config.window_id)?;
+				Reflect::set(&details, &key.into(), &value.into())?;
+				call_async_fn(&self.api, method, &[details.into()][..]).await?;
+			}
 		}
 		Ok(())
 	}
@@ -31,4 +42,160 @@ impl Action {
 	pub async fn clear_badge(&self) -> Result<(), ExtensionError> {
 		self.set_badge_text(BadgeConfig { text: Some("".to_string()), ..Default::default() }).await
 	}
+
+	pub async fn get_badge_text(&self, tab_id: Option<u32>) -> Result<String, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getBadgeText", &[tab_details(tab_id)?.into()][..]).await
+	}
+
+	/// `[r, g, b, a]`.
+	pub async fn get_badge_background_color(&self, tab_id: Option<u32>) -> Result<[u8; 4], ExtensionError> {
+		call_async_fn_and_de(&self.api, "getBadgeBackgroundColor", &[tab_details(tab_id)?.into()][..]).await
+	}
+
+	/// `[r, g, b, a]`.
+	pub async fn get_badge_text_color(&self, tab_id: Option<u32>) -> Result<[u8; 4], ExtensionError> {
+		call_async_fn_and_de(&self.api, "getBadgeTextColor", &[tab_details(tab_id)?.into()][..]).await
+	}
+
+	/// Badge getters scoped to a window; Firefox only.
+	pub async fn get_window_badge_text(&self, window_id: u32) -> Result<String, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getBadgeText", &[self.scope_details(None, Some(window_id))?.into()][..]).await
+	}
+
+	fn scope_details(&self, tab_id: Option<u32>, window_id: Option<u32>) -> Result<Object, ExtensionError> {
+		let details = tab_details(tab_id)?;
+		if let Some(window_id) = window_id {
+			if self.browser_type != BrowserType::Firefox {
+				return Err(ExtensionError::ApiError("Window-scoped action details are only supported by Firefox".to_owned()));
+			}
+			Reflect::set(&details, &"windowId".into(), &window_id.into())?;
+		}
+		Ok(details)
+	}
+
+	pub async fn set_title(&self, title: &str, tab_id: Option<u32>) -> Result<(), ExtensionError> {
+		let details = tab_details(tab_id)?;
+		Reflect::set(&details, &"title".into(), &title.into())?;
+		call_async_fn(&self.api, "setTitle", &[details.into()][..]).await?;
+		Ok(())
+	}
+
+	pub async fn get_title(&self, tab_id: Option<u32>) -> Result<String, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getTitle", &[tab_details(tab_id)?.into()][..]).await
+	}
+
+	pub async fn set_popup(&self, popup: &str, tab_id: Option<u32>) -> Result<(), ExtensionError> {
+		let details = tab_details(tab_id)?;
+		Reflect::set(&details, &"popup".into(), &popup.into())?;
+		call_async_fn(&self.api, "setPopup", &[details.into()][..]).await?;
+		Ok(())
+	}
+
+	pub async fn get_popup(&self, tab_id: Option<u32>) -> Result<String, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getPopup", &[tab_details(tab_id)?.into()][..]).await
+	}
+
+	/// Opens the popup in `window_id`, or in the focused window. Chrome allows this without a user gesture from version 127 on,
+	/// Firefox only while handling one.
+	pub async fn open_popup(&self, window_id: Option<u32>) -> Result<(), ExtensionError> {
+		let options = Object::new();
+		if let Some(window_id) = window_id {
+			Reflect::set(&options, &"windowId".into(), &window_id.into())?;
+		}
+		call_async_fn(&self.api, "openPopup", &[options.into()][..]).await?;
+		Ok(())
+	}
+
+	pub async fn enable(&self, tab_id: Option<u32>) -> Result<(), ExtensionError> {
+		call_async_fn(&self.api, "enable", &tab_id.map(JsValue::from).into_iter().collect::<Vec<_>>()).await?;
+		Ok(())
+	}
+
+	pub async fn disable(&self, tab_id: Option<u32>) -> Result<(), ExtensionError> {
+		call_async_fn(&self.api, "disable", &tab_id.map(JsValue::from).into_iter().collect::<Vec<_>>()).await?;
+		Ok(())
+	}
+
+	pub async fn set_icon(&self, icon: ActionIcon, tab_id: Option<u32>) -> Result<(), ExtensionError> {
+		let details = tab_details(tab_id)?;
+		let sizes = Object::new();
+		let key = match icon {
+			ActionIcon::Path(paths) => {
+				for (size, path) in paths {
+					Reflect::set(&sizes, &size.into(), &path.into())?;
+				}
+				"path"
+			},
+			ActionIcon::ImageData(images) => {
+				for (size, image) in images {
+					Reflect::set(&sizes, &size.into(), &image)?;
+				}
+				"imageData"
+			},
+		};
+		Reflect::set(&details, &key.into(), &sizes)?;
+		call_async_fn(&self.api, "setIcon", &[details.into()][..]).await?;
+		Ok(())
+	}
+
+	/// Whether the user has pinned the icon, e.g. to show a "pin the extension" hint when they haven't.
+	pub async fn get_user_settings(&self) -> Result<ActionUserSettings, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getUserSettings", &[][..]).await
+	}
+
+	pub fn on_user_settings_changed(&self) -> Result<OnUserSettingsChanged, ExtensionError> {
+		Ok(OnUserSettingsChanged(get_api_namespace(&self.api, "onUserSettingsChanged")?))
+	}
+
+	pub fn on_user_settings_changed_stream(&self) -> Result<EventStream<ActionUserSettings>, ExtensionError> {
+		let on_changed = self.on_user_settings_changed()?;
+		EventStream::new(|sender| on_changed.add_listener(move |settings| drop(sender.unbounded_send(settings))))
+	}
+
+	pub fn on_clicked(&self) -> Result<OnActionClicked, ExtensionError> {
+		Ok(OnActionClicked(get_api_namespace(&self.api, "onClicked")?))
+	}
+
+	pub fn on_clicked_stream(&self) -> Result<EventStream<TabInfo>, ExtensionError> {
+		let on_clicked = self.on_clicked()?;
+		EventStream::new(|sender| on_clicked.add_listener(move |tab| drop(sender.unbounded_send(tab))))
+	}
+}
+
+pub struct OnActionClicked(Object);
+
+impl OnActionClicked {
+	pub fn add_listener(&self, mut callback: impl FnMut(TabInfo) + 'static) -> Result<ListenerHandle<dyn FnMut(JsValue)>, ExtensionError> {
+		attach_listener(
+			&self.0,
+			Closure::wrap(Box::new(move |val: JsValue| {
+				if let Ok(tab) = serde_wasm_bindgen::from_value(val) {
+					callback(tab);
+				}
+			}) as Box<dyn FnMut(JsValue)>),
+		)
+	}
+
+	pub fn once(&self) -> Result<Once<TabInfo>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |event| drop(sender.unbounded_send(event))))
+	}
+}
+
+pub struct OnUserSettingsChanged(Object);
+
+impl OnUserSettingsChanged {
+	pub fn add_listener(&self, mut callback: impl FnMut(ActionUserSettings) + 'static) -> Result<ListenerHandle<dyn FnMut(JsValue)>, ExtensionError> {
+		attach_listener(
+			&self.0,
+			Closure::wrap(Box::new(move |val: JsValue| {
+				if let Ok(settings) = serde_wasm_bindgen::from_value(val) {
+					callback(settings);
+				}
+			}) as Box<dyn FnMut(JsValue)>),
+		)
+	}
+
+	pub fn once(&self) -> Result<Once<ActionUserSettings>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |event| drop(sender.unbounded_send(event))))
+	}
 }
@@ -1,6 +1,6 @@
 use crate::{
 	error::ExtensionError,
-	types::{Alarm, AlarmInfo, ListenerHandle, attach_listener},
+	types::{Alarm, AlarmInfo, EventStream, ListenerHandle, Once, attach_listener},
 	utils::{call_async_fn, call_async_fn_and_de, get_api_namespace},
 };
 use js_sys::Object;
@@ -13,9 +13,9 @@ pub struct Alarms {
 }
 
 impl Alarms {
-	pub(crate) fn new(api_root: &Object) -> Self {
-		let api = get_api_namespace(api_root, "alarms").expect("`alarms` API not available");
-		Self { api }
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "alarms")?;
+		Ok(Self { api })
 	}
 
 	pub async fn create(&self, name: &str, alarm_info: AlarmInfo) -> Result<(), ExtensionError> {
@@ -23,13 +23,30 @@ impl Alarms {
 		Ok(())
 	}
 
+	pub async fn get(&self, name: &str) -> Result<Option<Alarm>, ExtensionError> {
+		call_async_fn_and_de(&self.api, "get", &[name.into()][..]).await
+	}
+
+	pub async fn get_all(&self) -> Result<Vec<Alarm>, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getAll", &[][..]).await
+	}
+
 	pub async fn clear(&self, name: &str) -> Result<bool, ExtensionError> {
 		call_async_fn_and_de(&self.api, "clear", &[name.into()][..]).await
 	}
 
+	pub async fn clear_all(&self) -> Result<bool, ExtensionError> {
+		call_async_fn_and_de(&self.api, "clearAll", &[][..]).await
+	}
+
 	pub fn on_alarm(&self) -> Result<OnAlarm, ExtensionError> {
 		Ok(OnAlarm(get_api_namespace(&self.api, "onAlarm")?))
 	}
+
+	pub fn on_alarm_stream(&self) -> Result<EventStream<Alarm>, ExtensionError> {
+		let on_alarm = self.on_alarm()?;
+		EventStream::new(|sender| on_alarm.add_listener(move |alarm| drop(sender.unbounded_send(alarm))))
+	}
 }
 
 pub struct OnAlarm(Object);
@@ -45,4 +62,8 @@ impl OnAlarm {
 			}) as Box<dyn FnMut(JsValue)>),
 		)
 	}
+
+	pub fn once(&self) -> Result<Once<Alarm>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |event| drop(sender.unbounded_send(event))))
+	}
 }
@@ -0,0 +1,96 @@
+use crate::{
+	api::{BrowserSetting, SettingMethods},
+	error::ExtensionError,
+	types::{ColorScheme, ContextMenuShowEvent, ImageAnimationBehavior, NewTabPosition, OverrideDocumentColors},
+	utils::get_api_namespace,
+};
+use js_sys::Object;
+use serde::{Serialize, de::DeserializeOwned};
+
+const SETTING: SettingMethods = SettingMethods { get: "get", set: "set", clear: "clear", on_change: "onChange", value_key: "value" };
+
+/// Firefox's `browserSettings`. Every setting is a `types.BrowserSetting`; `homepage_override` and `new_tab_page_override` are read-only
+/// and reject `set`.
+#[derive(Clone)]
+pub struct BrowserSettings {
+	api: Object,
+}
+
+impl BrowserSettings {
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "browserSettings")?;
+		Ok(Self { api })
+	}
+
+	pub fn allow_popups_for_user_events(&self) -> Result<BrowserSetting<bool>, ExtensionError> {
+		self.setting("allowPopupsForUserEvents")
+	}
+
+	pub fn cache_enabled(&self) -> Result<BrowserSetting<bool>, ExtensionError> {
+		self.setting("cacheEnabled")
+	}
+
+	pub fn close_tabs_by_double_click(&self) -> Result<BrowserSetting<bool>, ExtensionError> {
+		self.setting("closeTabsByDoubleClick")
+	}
+
+	pub fn context_menu_show_event(&self) -> Result<BrowserSetting<ContextMenuShowEvent>, ExtensionError> {
+		self.setting("contextMenuShowEvent")
+	}
+
+	pub fn homepage_override(&self) -> Result<BrowserSetting<String>, ExtensionError> {
+		self.setting("homepageOverride")
+	}
+
+	pub fn image_animation_behavior(&self) -> Result<BrowserSetting<ImageAnimationBehavior>, ExtensionError> {
+		self.setting("imageAnimationBehavior")
+	}
+
+	pub fn new_tab_page_override(&self) -> Result<BrowserSetting<String>, ExtensionError> {
+		self.setting("newTabPageOverride")
+	}
+
+	pub fn new_tab_position(&self) -> Result<BrowserSetting<NewTabPosition>, ExtensionError> {
+		self.setting("newTabPosition")
+	}
+
+	pub fn open_bookmarks_in_new_tabs(&self) -> Result<BrowserSetting<bool>, ExtensionError> {
+		self.setting("openBookmarksInNewTabs")
+	}
+
+	pub fn open_search_results_in_new_tabs(&self) -> Result<BrowserSetting<bool>, ExtensionError> {
+		self.setting("openSearchResultsInNewTabs")
+	}
+
+	pub fn open_urlbar_results_in_new_tabs(&self) -> Result<BrowserSetting<bool>, ExtensionError> {
+		self.setting("openUrlbarResultsInNewTabs")
+	}
+
+	pub fn override_content_color_scheme(&self) -> Result<BrowserSetting<ColorScheme>, ExtensionError> {
+		self.setting("overrideContentColorScheme")
+	}
+
+	pub fn override_document_colors(&self) -> Result<BrowserSetting<OverrideDocumentColors>, ExtensionError> {
+		self.setting("overrideDocumentColors")
+	}
+
+	pub fn use_document_fonts(&self) -> Result<BrowserSetting<bool>, ExtensionError> {
+		self.setting("useDocumentFonts")
+	}
+
+	pub fn web_notifications_disabled(&self) -> Result<BrowserSetting<bool>, ExtensionError> {
+		self.setting("webNotificationsDisabled")
+	}
+
+	pub fn zoom_full_page(&self) -> Result<BrowserSetting<bool>, ExtensionError> {
+		self.setting("zoomFullPage")
+	}
+
+	pub fn zoom_site_specific(&self) -> Result<BrowserSetting<bool>, ExtensionError> {
+		self.setting("zoomSiteSpecific")
+	}
+
+	fn setting<T: Serialize + DeserializeOwned + 'static>(&self, name: &str) -> Result<BrowserSetting<T>, ExtensionError> {
+		Ok(BrowserSetting::new(get_api_namespace(&self.api, name)?, SETTING, Object::new()))
+	}
+}
@@ -0,0 +1,103 @@
+use crate::{
+	api::Runtime,
+	error::ExtensionError,
+	rpc::{self, RpcRequest, RpcTarget},
+	types::ListenerHandle,
+	utils::{call_async_fn, call_async_fn_and_de, get_api_namespace},
+};
+use js_sys::{Array, Object, Reflect};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{HtmlDocument, HtmlTextAreaElement, Window};
+
+pub const DEFAULT_OFFSCREEN_PATH: &str = "offscreen.html";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum ClipboardRequest {
+	Write(String),
+	Read,
+}
+
+impl RpcRequest for ClipboardRequest {
+	const METHOD: &'static str = "webext_api.clipboard";
+	type Response = Option<String>;
+}
+
+/// Text clipboard access from any context. Pages use `navigator.clipboard` directly; MV3 service workers have no document, so the call is
+/// forwarded to an offscreen document whose wasm runs [`serve_offscreen_clipboard`].
+#[derive(Clone)]
+pub struct Clipboard {
+	api_root: Object,
+	offscreen_path: String,
+}
+
+impl Clipboard {
+	pub(crate) fn new(api_root: &Object) -> Self {
+		Self { api_root: api_root.clone(), offscreen_path: DEFAULT_OFFSCREEN_PATH.to_owned() }
+	}
+
+	pub fn with_offscreen_path(mut self, path: &str) -> Self {
+		self.offscreen_path = path.to_owned();
+		self
+	}
+
+	pub async fn write_text(&self, text: &str) -> Result<(), ExtensionError> {
+		if let Some(window) = js_sys::global().dyn_ref::<Window>() {
+			JsFuture::from(window.navigator().clipboard().write_text(text)).await?;
+			return Ok(());
+		}
+		self.ensure_offscreen_document().await?;
+		rpc::call(RpcTarget::Runtime(&Runtime::new(&self.api_root)?), &ClipboardRequest::Write(text.to_owned())).await?;
+		Ok(())
+	}
+
+	pub async fn read_text(&self) -> Result<String, ExtensionError> {
+		if let Some(window) = js_sys::global().dyn_ref::<Window>() {
+			return Ok(JsFuture::from(window.navigator().clipboard().read_text()).await?.as_string().unwrap_or_default());
+		}
+		self.ensure_offscreen_document().await?;
+		Ok(rpc::call(RpcTarget::Runtime(&Runtime::new(&self.api_root)?), &ClipboardRequest::Read).await?.unwrap_or_default())
+	}
+
+	async fn ensure_offscreen_document(&self) -> Result<(), ExtensionError> {
+		let offscreen = get_api_namespace(&self.api_root, "offscreen")?;
+		if call_async_fn_and_de::<bool>(&offscreen, "hasDocument", &[][..]).await? {
+			return Ok(());
+		}
+		let parameters = Object::new();
+		Reflect::set(&parameters, &"url".into(), &self.offscreen_path.as_str().into())?;
+		Reflect::set(&parameters, &"reasons".into(), &Array::of1(&"CLIPBOARD".into()))?;
+		Reflect::set(&parameters, &"justification".into(), &"Read and write the clipboard from the background".into())?;
+		match call_async_fn(&offscreen, "createDocument", &[parameters.into()][..]).await {
+			// a concurrent call got there first
+			Err(ExtensionError::ApiError(message)) if message.contains("single offscreen document") => Ok(()),
+			result => result.map(drop),
+		}
+	}
+}
+
+/// Handles clipboard requests forwarded by [`Clipboard`]; call it from the wasm loaded by the offscreen document and keep the handle alive.
+pub fn serve_offscreen_clipboard(runtime: &Runtime) -> Result<ListenerHandle<dyn FnMut(JsValue, JsValue, JsValue) -> JsValue>, ExtensionError> {
+	rpc::serve(runtime, |request: ClipboardRequest, _| async move {
+		// offscreen documents never have focus, so `navigator.clipboard` rejects there and `execCommand` is the only way in
+		let document: HtmlDocument =
+			web_sys::window().and_then(|window| window.document()).ok_or(ExtensionError::ApiNotFound("document".to_owned()))?.unchecked_into();
+		let textarea: HtmlTextAreaElement = document.create_element("textarea")?.unchecked_into();
+		let body = document.body().ok_or(ExtensionError::ApiNotFound("document.body".to_owned()))?;
+		body.append_child(&textarea)?;
+		let result = match request {
+			ClipboardRequest::Write(text) => {
+				textarea.set_value(&text);
+				textarea.select();
+				document.exec_command("copy").map(|_| None)
+			},
+			ClipboardRequest::Read => {
+				textarea.focus()?;
+				document.exec_command("paste").map(|_| Some(textarea.value()))
+			},
+		};
+		textarea.remove();
+		result.map_err(ExtensionError::from)
+	})
+}
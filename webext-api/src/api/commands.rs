@@ -1,9 +1,13 @@
 use crate::{
 	error::ExtensionError,
-	types::{Command, ListenerHandle, attach_listener},
+	types::{Command, EventStream, ListenerHandle, Once, TabInfo, attach_listener},
 	utils::{call_async_fn_and_de, get_api_namespace},
 };
+#[cfg(feature = "firefox")]
+use crate::{types::CommandUpdate, utils::call_async_fn};
 use js_sys::Object;
+#[cfg(feature = "firefox")]
+use serde_wasm_bindgen::to_value;
 use wasm_bindgen::{JsValue, prelude::*};
 
 #[derive(Clone)]
@@ -12,31 +16,55 @@ pub struct Commands {
 }
 
 impl Commands {
-	pub(crate) fn new(api_root: &Object) -> Self {
-		let api = get_api_namespace(api_root, "commands").expect("`commands` API not available");
-		Self { api }
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "commands")?;
+		Ok(Self { api })
 	}
 
 	pub async fn get_all(&self) -> Result<Vec<Command>, ExtensionError> {
 		call_async_fn_and_de(&self.api, "getAll", &[][..]).await
 	}
 
+	#[cfg(feature = "firefox")]
+	pub async fn update(&self, details: CommandUpdate) -> Result<(), ExtensionError> {
+		call_async_fn(&self.api, "update", &[to_value(&details)?][..]).await?;
+		Ok(())
+	}
+
+	#[cfg(feature = "firefox")]
+	pub async fn reset(&self, name: &str) -> Result<(), ExtensionError> {
+		call_async_fn(&self.api, "reset", &[name.into()][..]).await?;
+		Ok(())
+	}
+
 	pub fn on_command(&self) -> Result<OnCommand, ExtensionError> {
 		Ok(OnCommand(get_api_namespace(&self.api, "onCommand")?))
 	}
+
+	pub fn on_command_stream(&self) -> Result<EventStream<(String, Option<TabInfo>)>, ExtensionError> {
+		let on_command = self.on_command()?;
+		EventStream::new(|sender| on_command.add_listener(move |command, tab| drop(sender.unbounded_send((command, tab)))))
+	}
 }
 
 pub struct OnCommand(Object);
 
 impl OnCommand {
-	pub fn add_listener(&self, mut callback: impl FnMut(String) + 'static) -> Result<ListenerHandle<dyn FnMut(JsValue)>, ExtensionError> {
+	pub fn add_listener(
+		&self,
+		mut callback: impl FnMut(String, Option<TabInfo>) + 'static,
+	) -> Result<ListenerHandle<dyn FnMut(JsValue, JsValue)>, ExtensionError> {
 		attach_listener(
 			&self.0,
-			Closure::wrap(Box::new(move |val: JsValue| {
+			Closure::wrap(Box::new(move |val: JsValue, tab: JsValue| {
 				if let Some(command) = val.as_string() {
-					callback(command);
+					callback(command, serde_wasm_bindgen::from_value(tab).ok().flatten());
 				}
-			}) as Box<dyn FnMut(JsValue)>),
+			}) as Box<dyn FnMut(JsValue, JsValue)>),
 		)
 	}
+
+	pub fn once(&self) -> Result<Once<(String, Option<TabInfo>)>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |command, tab| drop(sender.unbounded_send((command, tab)))))
+	}
 }
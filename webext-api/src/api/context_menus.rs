@@ -1,10 +1,11 @@
+#[cfg(feature = "firefox")]
+use crate::types::{MenuShownInfo, TabInfo};
 use crate::{
 	error::ExtensionError,
-	types::{ContextMenuConfig, ListenerHandle, OnClickData, attach_listener},
-	utils::{call_async_fn, get_api_namespace},
+	types::{ContextMenuConfig, ContextMenuUpdate, EventStream, ListenerHandle, OnClickData, Once, attach_listener},
+	utils::{call_async_fn, call_with_callback, get_api_namespace, to_object_value},
 };
 use js_sys::Object;
-use serde_wasm_bindgen::to_value;
 use wasm_bindgen::{JsValue, prelude::*};
 
 #[derive(Clone)]
@@ -13,13 +14,24 @@ pub struct ContextMenus {
 }
 
 impl ContextMenus {
-	pub(crate) fn new(api_root: &Object) -> Self {
-		let api = get_api_namespace(api_root, "contextMenus").expect("`contextMenus` API not available");
-		Self { api }
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "contextMenus")?;
+		Ok(Self { api })
 	}
 
 	pub async fn create(&self, config: ContextMenuConfig) -> Result<(), ExtensionError> {
-		call_async_fn(&self.api, "create", &[to_value(&config)?][..]).await?;
+		// `contextMenus.create` returns the item id synchronously and only reports completion through its callback
+		call_with_callback(&self.api, "create", &[to_object_value(&config)?][..]).await?;
+		Ok(())
+	}
+
+	pub async fn update(&self, id: &str, props: ContextMenuUpdate) -> Result<(), ExtensionError> {
+		call_async_fn(&self.api, "update", &[id.into(), to_object_value(&props)?][..]).await?;
+		Ok(())
+	}
+
+	pub async fn remove(&self, id: &str) -> Result<(), ExtensionError> {
+		call_async_fn(&self.api, "remove", &[id.into()][..]).await?;
 		Ok(())
 	}
 
@@ -31,6 +43,40 @@ impl ContextMenus {
 	pub fn on_clicked(&self) -> Result<OnMenuClicked, ExtensionError> {
 		Ok(OnMenuClicked(get_api_namespace(&self.api, "onClicked")?))
 	}
+
+	pub fn on_clicked_stream(&self) -> Result<EventStream<OnClickData>, ExtensionError> {
+		let on_clicked = self.on_clicked()?;
+		EventStream::new(|sender| on_clicked.add_listener(move |data| drop(sender.unbounded_send(data))))
+	}
+
+	/// Re-renders the menu while it is open, so items changed with [`Self::update`] from an `onShown` listener take effect immediately.
+	#[cfg(feature = "firefox")]
+	pub async fn refresh(&self) -> Result<(), ExtensionError> {
+		call_async_fn(&self.api, "refresh", &[][..]).await?;
+		Ok(())
+	}
+
+	#[cfg(feature = "firefox")]
+	pub fn on_shown(&self) -> Result<OnMenuShown, ExtensionError> {
+		Ok(OnMenuShown(get_api_namespace(&self.api, "onShown")?))
+	}
+
+	#[cfg(feature = "firefox")]
+	pub fn on_shown_stream(&self) -> Result<EventStream<(MenuShownInfo, Option<TabInfo>)>, ExtensionError> {
+		let on_shown = self.on_shown()?;
+		EventStream::new(|sender| on_shown.add_listener(move |info, tab| drop(sender.unbounded_send((info, tab)))))
+	}
+
+	#[cfg(feature = "firefox")]
+	pub fn on_hidden(&self) -> Result<OnMenuHidden, ExtensionError> {
+		Ok(OnMenuHidden(get_api_namespace(&self.api, "onHidden")?))
+	}
+
+	#[cfg(feature = "firefox")]
+	pub fn on_hidden_stream(&self) -> Result<EventStream<()>, ExtensionError> {
+		let on_hidden = self.on_hidden()?;
+		EventStream::new(|sender| on_hidden.add_listener(move || drop(sender.unbounded_send(()))))
+	}
 }
 
 pub struct OnMenuClicked(Object);
@@ -46,4 +92,46 @@ impl OnMenuClicked {
 			}) as Box<dyn FnMut(JsValue)>),
 		)
 	}
+
+	pub fn once(&self) -> Result<Once<OnClickData>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |event| drop(sender.unbounded_send(event))))
+	}
+}
+
+#[cfg(feature = "firefox")]
+pub struct OnMenuShown(Object);
+
+#[cfg(feature = "firefox")]
+impl OnMenuShown {
+	pub fn add_listener(
+		&self,
+		mut callback: impl FnMut(MenuShownInfo, Option<TabInfo>) + 'static,
+	) -> Result<ListenerHandle<dyn FnMut(JsValue, JsValue)>, ExtensionError> {
+		attach_listener(
+			&self.0,
+			Closure::wrap(Box::new(move |info: JsValue, tab: JsValue| {
+				if let Ok(info) = serde_wasm_bindgen::from_value(info) {
+					callback(info, serde_wasm_bindgen::from_value(tab).ok());
+				}
+			}) as Box<dyn FnMut(JsValue, JsValue)>),
+		)
+	}
+
+	pub fn once(&self) -> Result<Once<(MenuShownInfo, Option<TabInfo>)>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |info, tab| drop(sender.unbounded_send((info, tab)))))
+	}
+}
+
+#[cfg(feature = "firefox")]
+pub struct OnMenuHidden(Object);
+
+#[cfg(feature = "firefox")]
+impl OnMenuHidden {
+	pub fn add_listener(&self, callback: impl FnMut() + 'static) -> Result<ListenerHandle<dyn FnMut()>, ExtensionError> {
+		attach_listener(&self.0, Closure::wrap(Box::new(callback) as Box<dyn FnMut()>))
+	}
+
+	pub fn once(&self) -> Result<Once<()>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move || drop(sender.unbounded_send(()))))
+	}
 }
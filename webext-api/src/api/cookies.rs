@@ -0,0 +1,68 @@
+use crate::{
+	error::ExtensionError,
+	types::{Cookie, CookieChangeInfo, CookieDetails, CookieQuery, EventStream, ListenerHandle, Once, SetCookieDetails, attach_listener},
+	utils::{call_async_fn_and_de, get_api_namespace},
+};
+use js_sys::Object;
+use serde_wasm_bindgen::to_value;
+use wasm_bindgen::{JsValue, prelude::*};
+
+#[derive(Clone)]
+pub struct Cookies {
+	api: Object,
+}
+
+impl Cookies {
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "cookies")?;
+		Ok(Self { api })
+	}
+
+	/// Partitioned (CHIPS) cookies are only returned when `details.partition_key` names their partition.
+	pub async fn get(&self, details: &CookieDetails) -> Result<Option<Cookie>, ExtensionError> {
+		call_async_fn_and_de(&self.api, "get", &[to_value(details)?][..]).await
+	}
+
+	/// With `partition_key` set to `Some(CookiePartitionKey::default())`, cookies from every partition are included.
+	pub async fn get_all(&self, query: &CookieQuery) -> Result<Vec<Cookie>, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getAll", &[to_value(query)?][..]).await
+	}
+
+	pub async fn set(&self, details: &SetCookieDetails) -> Result<Option<Cookie>, ExtensionError> {
+		call_async_fn_and_de(&self.api, "set", &[to_value(details)?][..]).await
+	}
+
+	/// Returns the removed cookie's details, or `None` if there was nothing to remove.
+	pub async fn remove(&self, details: &CookieDetails) -> Result<Option<CookieDetails>, ExtensionError> {
+		call_async_fn_and_de(&self.api, "remove", &[to_value(details)?][..]).await
+	}
+
+	pub fn on_changed(&self) -> Result<OnCookieChanged, ExtensionError> {
+		Ok(OnCookieChanged(get_api_namespace(&self.api, "onChanged")?))
+	}
+
+	pub fn on_changed_stream(&self) -> Result<EventStream<CookieChangeInfo>, ExtensionError> {
+		let on_changed = self.on_changed()?;
+		EventStream::new(|sender| on_changed.add_listener(move |change| drop(sender.unbounded_send(change))))
+	}
+}
+
+pub struct OnCookieChanged(Object);
+
+impl OnCookieChanged {
+	/// An overwrite fires twice: a removal with cause `Overwrite`, then the insertion with cause `Explicit`.
+	pub fn add_listener(&self, mut callback: impl FnMut(CookieChangeInfo) + 'static) -> Result<ListenerHandle<dyn FnMut(JsValue)>, ExtensionError> {
+		attach_listener(
+			&self.0,
+			Closure::wrap(Box::new(move |val: JsValue| {
+				if let Ok(change) = serde_wasm_bindgen::from_value(val) {
+					callback(change);
+				}
+			}) as Box<dyn FnMut(JsValue)>),
+		)
+	}
+
+	pub fn once(&self) -> Result<Once<CookieChangeInfo>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |event| drop(sender.unbounded_send(event))))
+	}
+}
@@ -0,0 +1,73 @@
+use crate::{
+	error::ExtensionError,
+	types::{ContentAction, ContentRule},
+	utils::{call_with_callback, get_api_namespace},
+};
+use js_sys::{Array, Function, Object, Reflect};
+use serde_wasm_bindgen::to_value;
+use wasm_bindgen::{JsCast, JsValue};
+
+#[derive(Clone)]
+pub struct DeclarativeContent {
+	api: Object,
+}
+
+impl DeclarativeContent {
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "declarativeContent")?;
+		Ok(Self { api })
+	}
+
+	/// Rules persist across browser restarts, so register them from `runtime.onInstalled`, typically after [`Self::remove_rules`] with `None`.
+	pub async fn add_rules(&self, rules: &[ContentRule]) -> Result<(), ExtensionError> {
+		let rules = rules.iter().map(|rule| self.rule_to_js(rule)).collect::<Result<Array, _>>()?;
+		call_with_callback(&self.on_page_changed()?, "addRules", &[rules.into()][..]).await?;
+		Ok(())
+	}
+
+	/// Removes the rules with the given ids, or every rule this extension registered when `None`.
+	pub async fn remove_rules(&self, rule_ids: Option<&[&str]>) -> Result<(), ExtensionError> {
+		let rule_ids = rule_ids.map_or(JsValue::UNDEFINED, |ids| ids.iter().map(|id| JsValue::from_str(id)).collect::<Array>().into());
+		call_with_callback(&self.on_page_changed()?, "removeRules", &[rule_ids][..]).await?;
+		Ok(())
+	}
+
+	fn on_page_changed(&self) -> Result<Object, ExtensionError> {
+		get_api_namespace(&self.api, "onPageChanged")
+	}
+
+	// conditions and actions are only accepted as instances of the namespace's own classes, not as plain objects
+	fn construct(&self, class: &str, details: &JsValue) -> Result<JsValue, ExtensionError> {
+		let constructor: Function = Reflect::get(&self.api, &class.into())?.dyn_into()?;
+		Ok(Reflect::construct(&constructor, &Array::of1(details))?)
+	}
+
+	fn rule_to_js(&self, rule: &ContentRule) -> Result<JsValue, ExtensionError> {
+		let conditions = rule.conditions.iter().map(|matcher| self.construct("PageStateMatcher", &to_value(matcher)?)).collect::<Result<Array, _>>()?;
+		let actions = rule
+			.actions
+			.iter()
+			.map(|action| match action {
+				ContentAction::ShowAction => self.construct("ShowAction", &Object::new()),
+				ContentAction::RequestContentScript { js, css, all_frames, match_about_blank } => {
+					let details = Object::new();
+					Reflect::set(&details, &"js".into(), &to_value(js)?)?;
+					Reflect::set(&details, &"css".into(), &to_value(css)?)?;
+					Reflect::set(&details, &"allFrames".into(), &(*all_frames).into())?;
+					Reflect::set(&details, &"matchAboutBlank".into(), &(*match_about_blank).into())?;
+					self.construct("RequestContentScript", &details)
+				},
+			})
+			.collect::<Result<Array, _>>()?;
+		let js_rule = Object::new();
+		if let Some(id) = &rule.id {
+			Reflect::set(&js_rule, &"id".into(), &id.into())?;
+		}
+		if let Some(priority) = rule.priority {
+			Reflect::set(&js_rule, &"priority".into(), &priority.into())?;
+		}
+		Reflect::set(&js_rule, &"conditions".into(), &conditions)?;
+		Reflect::set(&js_rule, &"actions".into(), &actions)?;
+		Ok(js_rule.into())
+	}
+}
@@ -1,10 +1,14 @@
 use crate::{
 	error::ExtensionError,
-	types::{BrowserType, UpdateRulesOptions},
-	utils::{call_async_fn, get_api_namespace},
+	types::{
+		BrowserType, ListenerHandle, MatchedRule, MatchedRuleInfo, MatchedRuleInfoDebug, MatchedRulesFilter, Once, Rule, RulesMatchedDetails,
+		TestMatchOutcomeResult, TestRequestDetails, UpdateRulesOptions, UpdateRulesetOptions, attach_listener,
+	},
+	utils::{call_async_fn, call_async_fn_and_de, get_api_namespace},
 };
-use js_sys::Object;
+use js_sys::{Object, Reflect};
 use serde_wasm_bindgen::to_value;
+use wasm_bindgen::{JsValue, prelude::*};
 
 #[derive(Clone)]
 pub struct DeclarativeNetRequest {
@@ -14,18 +18,81 @@ pub struct DeclarativeNetRequest {
 impl DeclarativeNetRequest {
 	pub(crate) fn new(api_root: &Object, browser_type: BrowserType) -> Self {
 		let api = match browser_type {
-			BrowserType::Chrome => get_api_namespace(api_root, "declarativeNetRequest").ok(),
 			BrowserType::Firefox => None,
+			_ => get_api_namespace(api_root, "declarativeNetRequest").ok(),
 		};
 		Self { api }
 	}
 
+	fn api(&self) -> Result<&Object, ExtensionError> {
+		self.api.as_ref().ok_or_else(|| ExtensionError::ApiNotFound("declarativeNetRequest".to_string()))
+	}
+
 	pub async fn update_dynamic_rules(&self, options: UpdateRulesOptions) -> Result<(), ExtensionError> {
-		if let Some(api) = &self.api {
-			call_async_fn(api, "updateDynamicRules", &[to_value(&options)?][..]).await?;
-			Ok(())
-		} else {
-			Err(ExtensionError::ApiNotFound("declarativeNetRequest".to_string()))
-		}
+		call_async_fn(self.api()?, "updateDynamicRules", &[to_value(&options)?][..]).await?;
+		Ok(())
+	}
+
+	pub async fn update_session_rules(&self, options: UpdateRulesOptions) -> Result<(), ExtensionError> {
+		call_async_fn(self.api()?, "updateSessionRules", &[to_value(&options)?][..]).await?;
+		Ok(())
+	}
+
+	pub async fn get_dynamic_rules(&self, rule_ids: Option<&[u32]>) -> Result<Vec<Rule>, ExtensionError> {
+		call_async_fn_and_de(self.api()?, "getDynamicRules", &[rule_filter(rule_ids)?][..]).await
+	}
+
+	pub async fn get_session_rules(&self, rule_ids: Option<&[u32]>) -> Result<Vec<Rule>, ExtensionError> {
+		call_async_fn_and_de(self.api()?, "getSessionRules", &[rule_filter(rule_ids)?][..]).await
+	}
+
+	pub async fn get_enabled_rulesets(&self) -> Result<Vec<String>, ExtensionError> {
+		call_async_fn_and_de(self.api()?, "getEnabledRulesets", &[][..]).await
+	}
+
+	pub async fn update_enabled_rulesets(&self, options: UpdateRulesetOptions) -> Result<(), ExtensionError> {
+		call_async_fn(self.api()?, "updateEnabledRulesets", &[to_value(&options)?][..]).await?;
+		Ok(())
+	}
+
+	pub async fn test_match_outcome(&self, request: TestRequestDetails) -> Result<Vec<MatchedRule>, ExtensionError> {
+		let result: TestMatchOutcomeResult = call_async_fn_and_de(self.api()?, "testMatchOutcome", &[to_value(&request)?][..]).await?;
+		Ok(result.matched_rules)
+	}
+
+	pub async fn get_matched_rules(&self, filter: MatchedRulesFilter) -> Result<Vec<MatchedRuleInfo>, ExtensionError> {
+		let details: RulesMatchedDetails = call_async_fn_and_de(self.api()?, "getMatchedRules", &[to_value(&filter)?][..]).await?;
+		Ok(details.rules_info)
+	}
+
+	pub fn on_rule_matched_debug(&self) -> Result<OnRuleMatchedDebug, ExtensionError> {
+		Ok(OnRuleMatchedDebug(get_api_namespace(self.api()?, "onRuleMatchedDebug")?))
+	}
+}
+
+pub struct OnRuleMatchedDebug(Object);
+
+impl OnRuleMatchedDebug {
+	pub fn add_listener(&self, mut callback: impl FnMut(MatchedRuleInfoDebug) + 'static) -> Result<ListenerHandle<dyn FnMut(JsValue)>, ExtensionError> {
+		attach_listener(
+			&self.0,
+			Closure::wrap(Box::new(move |val: JsValue| {
+				if let Ok(info) = serde_wasm_bindgen::from_value(val) {
+					callback(info);
+				}
+			}) as Box<dyn FnMut(JsValue)>),
+		)
+	}
+
+	pub fn once(&self) -> Result<Once<MatchedRuleInfoDebug>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |event| drop(sender.unbounded_send(event))))
+	}
+}
+
+fn rule_filter(rule_ids: Option<&[u32]>) -> Result<JsValue, ExtensionError> {
+	let filter = Object::new();
+	if let Some(ids) = rule_ids {
+		Reflect::set(&filter, &"ruleIds".into(), &to_value(ids)?)?;
 	}
+	Ok(filter.into())
 }
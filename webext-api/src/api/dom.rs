@@ -0,0 +1,33 @@
+use crate::{error::ExtensionError, types::BrowserType, utils::get_api_namespace};
+use js_sys::{Function, Object, Reflect};
+use wasm_bindgen::JsCast;
+use web_sys::{Element, ShadowRoot};
+
+#[derive(Clone)]
+pub struct Dom {
+	api_root: Object,
+	browser_type: BrowserType,
+}
+
+impl Dom {
+	pub(crate) fn new(api_root: &Object, browser_type: BrowserType) -> Self {
+		Self { api_root: api_root.clone(), browser_type }
+	}
+
+	/// The element's shadow root whether it was attached in open or closed mode. Content scripts only.
+	pub fn open_or_closed_shadow_root(&self, element: &Element) -> Result<Option<ShadowRoot>, ExtensionError> {
+		let root = match self.browser_type {
+			// Firefox exposes it as a method on elements seen from the content script's isolated world
+			BrowserType::Firefox => {
+				let func: Function = Reflect::get(element, &"openOrClosedShadowRoot".into())?.dyn_into()?;
+				func.call0(element)?
+			},
+			BrowserType::Chrome | BrowserType::Edge | BrowserType::Opera | BrowserType::Safari => {
+				let dom_api = get_api_namespace(&self.api_root, "dom")?;
+				let func: Function = Reflect::get(&dom_api, &"openOrClosedShadowRoot".into())?.dyn_into()?;
+				func.call1(&dom_api, element)?
+			},
+		};
+		Ok(root.dyn_into().ok())
+	}
+}
@@ -0,0 +1,144 @@
+use crate::{
+	error::ExtensionError,
+	types::{DownloadDelta, DownloadItem, DownloadOptions, DownloadProgress, DownloadQuery, DownloadState, EventStream, ListenerHandle, Once, attach_listener},
+	utils::{call_async_fn, call_async_fn_and_de, get_api_namespace, sleep},
+};
+use futures::{
+	StreamExt,
+	future::{Either, select},
+	stream::{self, LocalBoxStream},
+};
+use js_sys::Object;
+use serde_wasm_bindgen::to_value;
+use std::{pin::pin, time::Duration};
+use wasm_bindgen::{JsValue, prelude::*};
+
+/// How often [`Downloads::watch_download`] reads `bytesReceived`, which `onChanged` never reports.
+pub const PROGRESS_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Clone)]
+pub struct Downloads {
+	api: Object,
+}
+
+impl Downloads {
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "downloads")?;
+		Ok(Self { api })
+	}
+
+	/// Starts a download and returns its id.
+	pub async fn download(&self, options: &DownloadOptions) -> Result<u32, ExtensionError> {
+		call_async_fn_and_de(&self.api, "download", &[to_value(options)?][..]).await
+	}
+
+	pub async fn search(&self, query: &DownloadQuery) -> Result<Vec<DownloadItem>, ExtensionError> {
+		call_async_fn_and_de(&self.api, "search", &[to_value(query)?][..]).await
+	}
+
+	pub async fn get(&self, id: u32) -> Result<Option<DownloadItem>, ExtensionError> {
+		Ok(self.search(&DownloadQuery { id: Some(id), ..Default::default() }).await?.into_iter().next())
+	}
+
+	pub async fn pause(&self, id: u32) -> Result<(), ExtensionError> {
+		call_async_fn(&self.api, "pause", &[id.into()]).await.map(|_| ())
+	}
+
+	pub async fn resume(&self, id: u32) -> Result<(), ExtensionError> {
+		call_async_fn(&self.api, "resume", &[id.into()]).await.map(|_| ())
+	}
+
+	pub async fn cancel(&self, id: u32) -> Result<(), ExtensionError> {
+		call_async_fn(&self.api, "cancel", &[id.into()]).await.map(|_| ())
+	}
+
+	pub fn on_created(&self) -> Result<OnDownloadCreated, ExtensionError> {
+		Ok(OnDownloadCreated(get_api_namespace(&self.api, "onCreated")?))
+	}
+
+	pub fn on_created_stream(&self) -> Result<EventStream<DownloadItem>, ExtensionError> {
+		let on_created = self.on_created()?;
+		EventStream::new(|sender| on_created.add_listener(move |item| drop(sender.unbounded_send(item))))
+	}
+
+	pub fn on_changed(&self) -> Result<OnDownloadChanged, ExtensionError> {
+		Ok(OnDownloadChanged(get_api_namespace(&self.api, "onChanged")?))
+	}
+
+	pub fn on_changed_stream(&self) -> Result<EventStream<DownloadDelta>, ExtensionError> {
+		let on_changed = self.on_changed()?;
+		EventStream::new(|sender| on_changed.add_listener(move |delta| drop(sender.unbounded_send(delta))))
+	}
+
+	/// Progress of download `id`, yielded whenever it changes: on every `onChanged` for it, and every [`PROGRESS_POLL_INTERVAL`]
+	/// while it is in progress. The stream ends after the download completes or is interrupted, or once it has been erased.
+	pub fn watch_download(&self, id: u32) -> Result<LocalBoxStream<'static, DownloadProgress>, ExtensionError> {
+		let changes = self.on_changed_stream()?.filter(move |delta| std::future::ready(delta.id == id));
+		let downloads = self.clone();
+		// `None` as the last progress means nothing has been yielded yet; `done` is set once a terminal state went out
+		let state = (downloads, Box::pin(changes), None::<DownloadProgress>, false);
+		Ok(
+			stream::unfold(state, move |(downloads, mut changes, last, done)| async move {
+				if done {
+					return None;
+				}
+				loop {
+					if last.is_some() {
+						let paused = last.as_ref().is_some_and(|last| last.paused);
+						// a paused download only moves again after an `onChanged`, so there is nothing to poll for
+						if paused {
+							changes.next().await?;
+						} else if let Either::Left((None, _)) = select(changes.next(), pin!(sleep(PROGRESS_POLL_INTERVAL))).await {
+							return None;
+						}
+					}
+					let item = downloads.get(id).await.ok()??;
+					let progress = DownloadProgress::from(&item);
+					if last.as_ref() != Some(&progress) {
+						let done = progress.state != DownloadState::InProgress;
+						return Some((progress.clone(), (downloads, changes, Some(progress), done)));
+					}
+				}
+			})
+			.boxed_local(),
+		)
+	}
+}
+
+pub struct OnDownloadCreated(Object);
+
+impl OnDownloadCreated {
+	pub fn add_listener(&self, mut callback: impl FnMut(DownloadItem) + 'static) -> Result<ListenerHandle<dyn FnMut(JsValue)>, ExtensionError> {
+		attach_listener(
+			&self.0,
+			Closure::wrap(Box::new(move |val: JsValue| {
+				if let Ok(item) = serde_wasm_bindgen::from_value(val) {
+					callback(item);
+				}
+			}) as Box<dyn FnMut(JsValue)>),
+		)
+	}
+
+	pub fn once(&self) -> Result<Once<DownloadItem>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |event| drop(sender.unbounded_send(event))))
+	}
+}
+
+pub struct OnDownloadChanged(Object);
+
+impl OnDownloadChanged {
+	pub fn add_listener(&self, mut callback: impl FnMut(DownloadDelta) + 'static) -> Result<ListenerHandle<dyn FnMut(JsValue)>, ExtensionError> {
+		attach_listener(
+			&self.0,
+			Closure::wrap(Box::new(move |val: JsValue| {
+				if let Ok(delta) = serde_wasm_bindgen::from_value(val) {
+					callback(delta);
+				}
+			}) as Box<dyn FnMut(JsValue)>),
+		)
+	}
+
+	pub fn once(&self) -> Result<Once<DownloadDelta>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |event| drop(sender.unbounded_send(event))))
+	}
+}
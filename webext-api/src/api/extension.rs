@@ -0,0 +1,29 @@
+use crate::{error::ExtensionError, types::ViewFilter, utils::get_api_namespace};
+use js_sys::{Array, Function, Object, Reflect};
+use serde_wasm_bindgen::to_value;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::Window;
+
+#[derive(Clone)]
+pub struct Extension {
+	api: Object,
+}
+
+impl Extension {
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "extension")?;
+		Ok(Self { api })
+	}
+
+	pub fn in_incognito_context(&self) -> bool {
+		Reflect::get(&self.api, &"inIncognitoContext".into()).is_ok_and(|value| value.is_truthy())
+	}
+
+	/// The windows of this extension's open pages. Only works from contexts that have a DOM; MV3 service workers should use
+	/// `Runtime::get_contexts` instead.
+	pub fn get_views(&self, filter: ViewFilter) -> Result<Vec<Window>, ExtensionError> {
+		let func: Function = Reflect::get(&self.api, &"getViews".into())?.dyn_into()?;
+		let views: Array = func.call1(&self.api, &to_value(&filter)?)?.dyn_into()?;
+		Ok(views.iter().filter_map(|view: JsValue| view.dyn_into().ok()).collect())
+	}
+}
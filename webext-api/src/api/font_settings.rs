@@ -0,0 +1,69 @@
+use crate::{
+	api::{BrowserSetting, SettingMethods},
+	error::ExtensionError,
+	types::{FontName, GenericFamily},
+	utils::{call_async_fn_and_de, get_api_namespace},
+};
+use js_sys::{Object, Reflect};
+use serde_wasm_bindgen::to_value;
+
+const FONT: SettingMethods = SettingMethods { get: "getFont", set: "setFont", clear: "clearFont", on_change: "onFontChanged", value_key: "fontId" };
+const DEFAULT_FONT_SIZE: SettingMethods = SettingMethods {
+	get: "getDefaultFontSize",
+	set: "setDefaultFontSize",
+	clear: "clearDefaultFontSize",
+	on_change: "onDefaultFontSizeChanged",
+	value_key: "pixelSize",
+};
+const DEFAULT_FIXED_FONT_SIZE: SettingMethods = SettingMethods {
+	get: "getDefaultFixedFontSize",
+	set: "setDefaultFixedFontSize",
+	clear: "clearDefaultFixedFontSize",
+	on_change: "onDefaultFixedFontSizeChanged",
+	value_key: "pixelSize",
+};
+const MINIMUM_FONT_SIZE: SettingMethods = SettingMethods {
+	get: "getMinimumFontSize",
+	set: "setMinimumFontSize",
+	clear: "clearMinimumFontSize",
+	on_change: "onMinimumFontSizeChanged",
+	value_key: "pixelSize",
+};
+
+#[derive(Clone)]
+pub struct FontSettings {
+	api: Object,
+}
+
+impl FontSettings {
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "fontSettings")?;
+		Ok(Self { api })
+	}
+
+	/// The font id for `generic_family` in `script` (an ISO 15924 code such as `"Arab"`), or in the default script when `None`.
+	pub fn font(&self, generic_family: GenericFamily, script: Option<&str>) -> Result<BrowserSetting<String>, ExtensionError> {
+		let details = Object::new();
+		Reflect::set(&details, &"genericFamily".into(), &to_value(&generic_family)?)?;
+		if let Some(script) = script {
+			Reflect::set(&details, &"script".into(), &script.into())?;
+		}
+		Ok(BrowserSetting::new(self.api.clone(), FONT, details))
+	}
+
+	pub fn default_font_size(&self) -> BrowserSetting<u32> {
+		BrowserSetting::new(self.api.clone(), DEFAULT_FONT_SIZE, Object::new())
+	}
+
+	pub fn default_fixed_font_size(&self) -> BrowserSetting<u32> {
+		BrowserSetting::new(self.api.clone(), DEFAULT_FIXED_FONT_SIZE, Object::new())
+	}
+
+	pub fn minimum_font_size(&self) -> BrowserSetting<u32> {
+		BrowserSetting::new(self.api.clone(), MINIMUM_FONT_SIZE, Object::new())
+	}
+
+	pub async fn get_font_list(&self) -> Result<Vec<FontName>, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getFontList", &[][..]).await
+	}
+}
@@ -0,0 +1,144 @@
+use crate::{
+	error::ExtensionError,
+	types::{EventStream, GcmMessage, GcmSendError, GcmTokenOptions, IncomingGcmMessage, ListenerHandle, Once, attach_listener},
+	utils::{call_async_fn, call_async_fn_and_de, get_api_namespace, to_object_value},
+};
+use js_sys::Object;
+use serde::de::DeserializeOwned;
+use serde_wasm_bindgen::to_value;
+use wasm_bindgen::{JsValue, prelude::*};
+
+/// Chrome only, and deprecated there in favour of `instanceID` for registration; sending and receiving still go through `gcm`.
+#[derive(Clone)]
+pub struct Gcm {
+	api: Object,
+}
+
+impl Gcm {
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "gcm")?;
+		Ok(Self { api })
+	}
+
+	/// Returns the registration id the server uses to address this extension.
+	pub async fn register(&self, sender_ids: &[&str]) -> Result<String, ExtensionError> {
+		call_async_fn_and_de(&self.api, "register", &[to_value(sender_ids)?][..]).await
+	}
+
+	pub async fn unregister(&self) -> Result<(), ExtensionError> {
+		call_async_fn(&self.api, "unregister", &[][..]).await?;
+		Ok(())
+	}
+
+	/// Sends an upstream message, returning its message id; the payload is checked against the size and key limits first.
+	pub async fn send(&self, message: &GcmMessage) -> Result<String, ExtensionError> {
+		message.validate()?;
+		call_async_fn_and_de(&self.api, "send", &[to_object_value(message)?][..]).await
+	}
+
+	pub fn on_message(&self) -> Result<OnGcmEvent<IncomingGcmMessage>, ExtensionError> {
+		Ok(OnGcmEvent::new(get_api_namespace(&self.api, "onMessage")?))
+	}
+
+	pub fn on_message_stream(&self) -> Result<EventStream<IncomingGcmMessage>, ExtensionError> {
+		let on_message = self.on_message()?;
+		EventStream::new(|sender| on_message.add_listener(move |message| drop(sender.unbounded_send(message))))
+	}
+
+	/// Fires when the server had to drop queued messages, e.g. because too many piled up while offline; refetch state from the server.
+	pub fn on_messages_deleted(&self) -> Result<OnGcmSignal, ExtensionError> {
+		Ok(OnGcmSignal(get_api_namespace(&self.api, "onMessagesDeleted")?))
+	}
+
+	pub fn on_send_error(&self) -> Result<OnGcmEvent<GcmSendError>, ExtensionError> {
+		Ok(OnGcmEvent::new(get_api_namespace(&self.api, "onSendError")?))
+	}
+
+	pub fn on_send_error_stream(&self) -> Result<EventStream<GcmSendError>, ExtensionError> {
+		let on_send_error = self.on_send_error()?;
+		EventStream::new(|sender| on_send_error.add_listener(move |error| drop(sender.unbounded_send(error))))
+	}
+}
+
+pub struct OnGcmEvent<T> {
+	event: Object,
+	_marker: std::marker::PhantomData<T>,
+}
+
+impl<T: DeserializeOwned + 'static> OnGcmEvent<T> {
+	fn new(event: Object) -> Self {
+		Self { event, _marker: std::marker::PhantomData }
+	}
+
+	pub fn add_listener(&self, mut callback: impl FnMut(T) + 'static) -> Result<ListenerHandle<dyn FnMut(JsValue)>, ExtensionError> {
+		attach_listener(
+			&self.event,
+			Closure::wrap(Box::new(move |val: JsValue| {
+				if let Ok(value) = serde_wasm_bindgen::from_value(val) {
+					callback(value);
+				}
+			}) as Box<dyn FnMut(JsValue)>),
+		)
+	}
+
+	pub fn once(&self) -> Result<Once<T>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |event| drop(sender.unbounded_send(event))))
+	}
+}
+
+/// An event without arguments.
+pub struct OnGcmSignal(Object);
+
+impl OnGcmSignal {
+	pub fn add_listener(&self, callback: impl FnMut() + 'static) -> Result<ListenerHandle<dyn FnMut()>, ExtensionError> {
+		attach_listener(&self.0, Closure::wrap(Box::new(callback) as Box<dyn FnMut()>))
+	}
+
+	pub fn once(&self) -> Result<Once<()>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move || drop(sender.unbounded_send(()))))
+	}
+}
+
+/// Chrome only: per-app-instance identity and FCM tokens.
+#[derive(Clone)]
+pub struct InstanceId {
+	api: Object,
+}
+
+impl InstanceId {
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "instanceID")?;
+		Ok(Self { api })
+	}
+
+	pub async fn get_id(&self) -> Result<String, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getID", &[][..]).await
+	}
+
+	/// Milliseconds since the epoch at which the id was created.
+	pub async fn get_creation_time(&self) -> Result<f64, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getCreationTime", &[][..]).await
+	}
+
+	/// A token the server can push to; `authorized_entity` is the FCM sender id and `scope` is usually `"GCM"`.
+	pub async fn get_token(&self, options: &GcmTokenOptions) -> Result<String, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getToken", &[to_object_value(options)?][..]).await
+	}
+
+	pub async fn delete_token(&self, authorized_entity: &str, scope: &str) -> Result<(), ExtensionError> {
+		let details = GcmTokenOptions::new(authorized_entity, scope);
+		call_async_fn(&self.api, "deleteToken", &[to_object_value(&details)?][..]).await?;
+		Ok(())
+	}
+
+	/// Revokes the id together with every token issued for it.
+	pub async fn delete_id(&self) -> Result<(), ExtensionError> {
+		call_async_fn(&self.api, "deleteID", &[][..]).await?;
+		Ok(())
+	}
+
+	/// Fires when tokens were invalidated; fetch new ones with `get_token` and hand them to the server.
+	pub fn on_token_refresh(&self) -> Result<OnGcmSignal, ExtensionError> {
+		Ok(OnGcmSignal(get_api_namespace(&self.api, "onTokenRefresh")?))
+	}
+}
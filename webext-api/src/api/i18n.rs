@@ -0,0 +1,48 @@
+use crate::{
+	error::ExtensionError,
+	types::DetectedLanguages,
+	utils::{call_async_fn_and_de, get_api_namespace},
+};
+use js_sys::{Array, Function, Object, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+
+/// Messages from the extension's `_locales/<locale>/messages.json`, in the browser's UI language.
+#[derive(Clone)]
+pub struct I18n {
+	api: Object,
+}
+
+impl I18n {
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "i18n")?;
+		Ok(Self { api })
+	}
+
+	fn call_sync(&self, method: &str, args: &[JsValue]) -> Result<JsValue, ExtensionError> {
+		let func: Function = Reflect::get(&self.api, &method.into())?.dyn_into()?;
+		Ok(func.apply(&self.api, &args.iter().collect::<Array>())?)
+	}
+
+	/// The message named `name` with `$1`…`$9` replaced by `substitutions`, or `None` if no locale defines it.
+	pub fn get_message(&self, name: &str, substitutions: &[&str]) -> Result<Option<String>, ExtensionError> {
+		let substitutions = substitutions.iter().map(|substitution| JsValue::from_str(substitution)).collect::<Array>();
+		let message = self.call_sync("getMessage", &[name.into(), substitutions.into()])?;
+		// an unknown name comes back as the empty string
+		Ok(message.as_string().filter(|message| !message.is_empty()))
+	}
+
+	/// The browser UI's locale, e.g. `en-US`, which picks the `_locales` directory [`Self::get_message`] reads from.
+	pub fn get_ui_language(&self) -> Result<String, ExtensionError> {
+		self.call_sync("getUILanguage", &[])?.as_string().ok_or_else(|| ExtensionError::ApiError("`i18n.getUILanguage` did not return a string".to_owned()))
+	}
+
+	/// The user's preferred content languages, most preferred first.
+	pub async fn get_accept_languages(&self) -> Result<Vec<String>, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getAcceptLanguages", &[]).await
+	}
+
+	/// Guesses the languages `text` is written in, using the browser's built-in detector.
+	pub async fn detect_language(&self, text: &str) -> Result<DetectedLanguages, ExtensionError> {
+		call_async_fn_and_de(&self.api, "detectLanguage", &[text.into()]).await
+	}
+}
@@ -1,23 +1,129 @@
+#[cfg(feature = "action")]
 mod action;
+#[cfg(feature = "alarms")]
 mod alarms;
+#[cfg(feature = "firefox")]
+mod browser_settings;
+#[cfg(feature = "clipboard")]
+mod clipboard;
+#[cfg(feature = "commands")]
 mod commands;
+#[cfg(feature = "context_menus")]
 mod context_menus;
-#[cfg(feature = "chrome")]
+#[cfg(feature = "cookies")]
+mod cookies;
+#[cfg(feature = "declarative_content")]
+mod declarative_content;
+#[cfg(feature = "declarative_net_request")]
 mod declarative_net_request;
+#[cfg(feature = "dom")]
+mod dom;
+#[cfg(feature = "downloads")]
+mod downloads;
+#[cfg(feature = "extension")]
+mod extension;
+#[cfg(feature = "font_settings")]
+mod font_settings;
+#[cfg(feature = "gcm")]
+mod gcm;
+#[cfg(feature = "i18n")]
+mod i18n;
+#[cfg(feature = "notifications")]
+mod notifications;
+#[cfg(feature = "permissions")]
+mod permissions;
+mod port;
+#[cfg(feature = "printing")]
+mod printing;
+#[cfg(feature = "printing_metrics")]
+mod printing_metrics;
+#[cfg(feature = "reading_list")]
+mod reading_list;
 mod runtime;
+#[cfg(feature = "scripting")]
 mod scripting;
+#[cfg(any(feature = "font_settings", feature = "firefox"))]
+mod setting;
+#[cfg(feature = "side_panel")]
 mod side_panel;
+#[cfg(feature = "storage")]
 mod storage;
+#[cfg(feature = "system_cpu")]
+mod system_cpu;
+#[cfg(feature = "system_display")]
+mod system_display;
+#[cfg(feature = "system_memory")]
+mod system_memory;
+#[cfg(feature = "tab_capture")]
+mod tab_capture;
+#[cfg(feature = "tabs")]
 mod tabs;
+#[cfg(feature = "tts")]
+mod tts;
+#[cfg(feature = "tts_engine")]
+mod tts_engine;
 
+#[cfg(feature = "action")]
 pub use action::*;
+#[cfg(feature = "alarms")]
 pub use alarms::*;
+#[cfg(feature = "firefox")]
+pub use browser_settings::*;
+#[cfg(feature = "clipboard")]
+pub use clipboard::*;
+#[cfg(feature = "commands")]
 pub use commands::*;
+#[cfg(feature = "context_menus")]
 pub use context_menus::*;
-#[cfg(feature = "chrome")]
+#[cfg(feature = "cookies")]
+pub use cookies::*;
+#[cfg(feature = "declarative_content")]
+pub use declarative_content::*;
+#[cfg(feature = "declarative_net_request")]
 pub use declarative_net_request::*;
+#[cfg(feature = "dom")]
+pub use dom::*;
+#[cfg(feature = "downloads")]
+pub use downloads::*;
+#[cfg(feature = "extension")]
+pub use extension::*;
+#[cfg(feature = "font_settings")]
+pub use font_settings::*;
+#[cfg(feature = "gcm")]
+pub use gcm::*;
+#[cfg(feature = "i18n")]
+pub use i18n::*;
+#[cfg(feature = "notifications")]
+pub use notifications::*;
+#[cfg(feature = "permissions")]
+pub use permissions::*;
+pub use port::*;
+#[cfg(feature = "printing")]
+pub use printing::*;
+#[cfg(feature = "printing_metrics")]
+pub use printing_metrics::*;
+#[cfg(feature = "reading_list")]
+pub use reading_list::*;
 pub use runtime::*;
+#[cfg(feature = "scripting")]
 pub use scripting::*;
+#[cfg(any(feature = "font_settings", feature = "firefox"))]
+pub use setting::*;
+#[cfg(feature = "side_panel")]
 pub use side_panel::*;
+#[cfg(feature = "storage")]
 pub use storage::*;
+#[cfg(feature = "system_cpu")]
+pub use system_cpu::*;
+#[cfg(feature = "system_display")]
+pub use system_display::*;
+#[cfg(feature = "system_memory")]
+pub use system_memory::*;
+#[cfg(feature = "tab_capture")]
+pub use tab_capture::*;
+#[cfg(feature = "tabs")]
 pub use tabs::*;
+#[cfg(feature = "tts")]
+pub use tts::*;
+#[cfg(feature = "tts_engine")]
+pub use tts_engine::*;
@@ -0,0 +1,85 @@
+use crate::{
+	error::ExtensionError,
+	types::{EventStream, ListenerHandle, NotificationOptions, Once, attach_listener},
+	utils::{call_async_fn_and_de, get_api_namespace},
+};
+use js_sys::Object;
+use serde_wasm_bindgen::to_value;
+use wasm_bindgen::{JsValue, prelude::*};
+
+#[derive(Clone)]
+pub struct Notifications {
+	api: Object,
+}
+
+impl Notifications {
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "notifications")?;
+		Ok(Self { api })
+	}
+
+	/// Shows a notification and resolves to its id: `id` if given, which replaces a notification already showing under it, or one
+	/// the browser generated.
+	pub async fn create(&self, id: Option<&str>, options: &NotificationOptions) -> Result<String, ExtensionError> {
+		let mut args = Vec::with_capacity(2);
+		if let Some(id) = id {
+			args.push(JsValue::from(id));
+		}
+		args.push(to_value(options)?);
+		call_async_fn_and_de(&self.api, "create", &args).await
+	}
+
+	/// Resolves to whether a notification was showing under `id`.
+	pub async fn clear(&self, id: &str) -> Result<bool, ExtensionError> {
+		call_async_fn_and_de(&self.api, "clear", &[id.into()][..]).await
+	}
+
+	/// Fires with the id of a notification the user clicked, outside its buttons.
+	pub fn on_clicked(&self) -> Result<OnNotificationClicked, ExtensionError> {
+		Ok(OnNotificationClicked(get_api_namespace(&self.api, "onClicked")?))
+	}
+
+	pub fn on_clicked_stream(&self) -> Result<EventStream<String>, ExtensionError> {
+		let on_clicked = self.on_clicked()?;
+		EventStream::new(|sender| on_clicked.add_listener(move |id| drop(sender.unbounded_send(id))))
+	}
+
+	/// Fires with the id of a notification that went away and whether the user dismissed it.
+	pub fn on_closed(&self) -> Result<OnNotificationClosed, ExtensionError> {
+		Ok(OnNotificationClosed(get_api_namespace(&self.api, "onClosed")?))
+	}
+}
+
+pub struct OnNotificationClicked(Object);
+
+impl OnNotificationClicked {
+	pub fn add_listener(&self, mut callback: impl FnMut(String) + 'static) -> Result<ListenerHandle<dyn FnMut(JsValue)>, ExtensionError> {
+		attach_listener(
+			&self.0,
+			Closure::wrap(Box::new(move |val: JsValue| {
+				if let Some(id) = val.as_string() {
+					callback(id);
+				}
+			}) as Box<dyn FnMut(JsValue)>),
+		)
+	}
+
+	pub fn once(&self) -> Result<Once<String>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |id| drop(sender.unbounded_send(id))))
+	}
+}
+
+pub struct OnNotificationClosed(Object);
+
+impl OnNotificationClosed {
+	pub fn add_listener(&self, mut callback: impl FnMut(String, bool) + 'static) -> Result<ListenerHandle<dyn FnMut(JsValue, JsValue)>, ExtensionError> {
+		attach_listener(
+			&self.0,
+			Closure::wrap(Box::new(move |id: JsValue, by_user: JsValue| {
+				if let Some(id) = id.as_string() {
+					callback(id, by_user.as_bool().unwrap_or(false));
+				}
+			}) as Box<dyn FnMut(JsValue, JsValue)>),
+		)
+	}
+}
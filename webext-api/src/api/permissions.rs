@@ -0,0 +1,72 @@
+use crate::{
+	error::ExtensionError,
+	types::{EventStream, ListenerHandle, PermissionSet, attach_listener},
+	utils::{call_async_fn_and_de, get_api_namespace},
+};
+use js_sys::Object;
+use serde_wasm_bindgen::to_value;
+use wasm_bindgen::{JsValue, prelude::*};
+
+/// Optional permissions, declared under `optional_permissions` and `optional_host_permissions` in the manifest and granted by the user
+/// at runtime.
+#[derive(Clone)]
+pub struct Permissions {
+	api: Object,
+}
+
+impl Permissions {
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "permissions")?;
+		Ok(Self { api })
+	}
+
+	/// Whether every permission in `set` is granted.
+	pub async fn contains(&self, set: &PermissionSet) -> Result<bool, ExtensionError> {
+		call_async_fn_and_de(&self.api, "contains", &[to_value(set)?][..]).await
+	}
+
+	/// Asks the user for `set`, resolving to whether it was granted. Browsers only show the prompt while handling a user gesture, so
+	/// call it straight from a click handler, before awaiting anything else.
+	pub async fn request(&self, set: &PermissionSet) -> Result<bool, ExtensionError> {
+		call_async_fn_and_de(&self.api, "request", &[to_value(set)?][..]).await
+	}
+
+	/// Gives up optional permissions the extension no longer needs, resolving to whether they were removed.
+	pub async fn remove(&self, set: &PermissionSet) -> Result<bool, ExtensionError> {
+		call_async_fn_and_de(&self.api, "remove", &[to_value(set)?][..]).await
+	}
+
+	pub fn on_added(&self) -> Result<OnPermissionsChanged, ExtensionError> {
+		Ok(OnPermissionsChanged(get_api_namespace(&self.api, "onAdded")?))
+	}
+
+	pub fn on_removed(&self) -> Result<OnPermissionsChanged, ExtensionError> {
+		Ok(OnPermissionsChanged(get_api_namespace(&self.api, "onRemoved")?))
+	}
+
+	pub fn on_added_stream(&self) -> Result<EventStream<PermissionSet>, ExtensionError> {
+		let on_added = self.on_added()?;
+		EventStream::new(|sender| on_added.add_listener(move |set| drop(sender.unbounded_send(set))))
+	}
+
+	pub fn on_removed_stream(&self) -> Result<EventStream<PermissionSet>, ExtensionError> {
+		let on_removed = self.on_removed()?;
+		EventStream::new(|sender| on_removed.add_listener(move |set| drop(sender.unbounded_send(set))))
+	}
+}
+
+pub struct OnPermissionsChanged(Object);
+
+impl OnPermissionsChanged {
+	/// Sets the browser reports that aren't [`Permission`](crate::Permission)s, like a newer API's, are skipped.
+	pub fn add_listener(&self, mut callback: impl FnMut(PermissionSet) + 'static) -> Result<ListenerHandle<dyn FnMut(JsValue)>, ExtensionError> {
+		attach_listener(
+			&self.0,
+			Closure::wrap(Box::new(move |val: JsValue| {
+				if let Ok(set) = serde_wasm_bindgen::from_value(val) {
+					callback(set);
+				}
+			}) as Box<dyn FnMut(JsValue)>),
+		)
+	}
+}
@@ -0,0 +1,119 @@
+use crate::{
+	error::ExtensionError,
+	types::{EventStream, ListenerHandle, MessageSender, Once, SenderAllowlist, attach_listener},
+	utils::{get_api_namespace, last_error_value},
+};
+use js_sys::{Function, Object, Reflect};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_wasm_bindgen::{from_value, to_value};
+use std::{marker::PhantomData, rc::Rc};
+use wasm_bindgen::{JsCast, prelude::*};
+
+/// A long-lived `runtime.Port`, from `connect` on one side and `onConnect` on the other.
+#[derive(Clone)]
+pub struct Port {
+	port: Object,
+	// Chrome's `lastError` while the port's `onDisconnect` listeners ran
+	error: Option<JsValue>,
+}
+
+impl Port {
+	pub(crate) fn from_js(port: JsValue) -> Self {
+		Self { port: port.unchecked_into(), error: None }
+	}
+
+	pub fn name(&self) -> String {
+		Reflect::get(&self.port, &"name".into()).ok().and_then(|name| name.as_string()).unwrap_or_default()
+	}
+
+	/// Only set on the receiving side of a connection.
+	pub fn sender(&self) -> Option<MessageSender> {
+		Reflect::get(&self.port, &"sender".into()).ok().and_then(|sender| from_value(sender).ok())
+	}
+
+	/// Fails once the other end has disconnected.
+	pub fn post_message<M: Serialize>(&self, message: &M) -> Result<(), ExtensionError> {
+		let post: Function = Reflect::get(&self.port, &"postMessage".into())?.dyn_into()?;
+		post.call1(&self.port, &to_value(message)?)?;
+		Ok(())
+	}
+
+	/// Why the port closed, on the one `onDisconnect` passes to its listeners, e.g. that nothing was listening on the other end;
+	/// `None` when the other end called `disconnect`. Firefox sets `Port.error`, Chrome only `lastError` while the listeners run.
+	pub fn error(&self) -> Option<ExtensionError> {
+		self.error.clone().or_else(|| Reflect::get(&self.port, &"error".into()).ok().filter(|error| !error.is_undefined() && !error.is_null())).map(Into::into)
+	}
+
+	pub fn disconnect(&self) {
+		if let Ok(disconnect) = Reflect::get(&self.port, &"disconnect".into()).and_then(|f| f.dyn_into::<Function>()) {
+			let _ = disconnect.call0(&self.port);
+		}
+	}
+
+	pub fn on_message<T: DeserializeOwned + 'static>(&self) -> Result<OnPortMessage<T>, ExtensionError> {
+		Ok(OnPortMessage { api: get_api_namespace(&self.port, "onMessage")?, _phantom: PhantomData })
+	}
+
+	pub fn on_message_stream<T: DeserializeOwned + 'static>(&self) -> Result<EventStream<T>, ExtensionError> {
+		let on_message = self.on_message::<T>()?;
+		EventStream::new(|sender| on_message.add_listener(move |message| drop(sender.unbounded_send(message))))
+	}
+
+	pub fn on_disconnect(&self) -> Result<OnPortEvent, ExtensionError> {
+		Ok(OnPortEvent { api: get_api_namespace(&self.port, "onDisconnect")?, allowlist: None })
+	}
+}
+
+pub struct OnPortMessage<T> {
+	api: Object,
+	_phantom: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned + 'static> OnPortMessage<T> {
+	pub fn add_listener(&self, mut callback: impl FnMut(T) + 'static) -> Result<ListenerHandle<dyn FnMut(JsValue)>, ExtensionError> {
+		attach_listener(
+			&self.api,
+			Closure::wrap(Box::new(move |message: JsValue| {
+				if let Ok(message) = from_value(message) {
+					callback(message);
+				}
+			}) as Box<dyn FnMut(JsValue)>),
+		)
+	}
+
+	pub fn once(&self) -> Result<Once<T>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |event| drop(sender.unbounded_send(event))))
+	}
+}
+
+/// An event delivering a [`Port`]: `onConnect`, `onConnectExternal` or a port's `onDisconnect`.
+pub struct OnPortEvent {
+	api: Object,
+	allowlist: Option<Rc<SenderAllowlist>>,
+}
+
+impl OnPortEvent {
+	pub(crate) fn new(api: Object, allowlist: Option<SenderAllowlist>) -> Self {
+		Self { api, allowlist: allowlist.map(Rc::new) }
+	}
+
+	/// With an allowlist, ports from other senders are disconnected right away instead of being passed on.
+	pub fn add_listener(&self, mut callback: impl FnMut(Port) + 'static) -> Result<ListenerHandle<dyn FnMut(JsValue)>, ExtensionError> {
+		let allowlist = self.allowlist.clone();
+		attach_listener(
+			&self.api,
+			Closure::wrap(Box::new(move |port: JsValue| {
+				let port = Port { error: last_error_value(), ..Port::from_js(port) };
+				if allowlist.as_ref().is_some_and(|allowlist| !port.sender().is_some_and(|sender| allowlist.allows(&sender))) {
+					port.disconnect();
+					return;
+				}
+				callback(port);
+			}) as Box<dyn FnMut(JsValue)>),
+		)
+	}
+
+	pub fn once(&self) -> Result<Once<Port>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |event| drop(sender.unbounded_send(event))))
+	}
+}
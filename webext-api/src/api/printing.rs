@@ -0,0 +1,87 @@
+use crate::{
+	error::ExtensionError,
+	types::{EventStream, JobStatus, ListenerHandle, Once, PrintTicket, Printer, PrinterInfo, SubmitJobResponse, attach_listener},
+	utils::{call_async_fn, call_async_fn_and_de, get_api_namespace},
+};
+use js_sys::{Object, Reflect};
+use serde_wasm_bindgen::to_value;
+use wasm_bindgen::{JsValue, prelude::*};
+use web_sys::Blob;
+
+/// ChromeOS printing; needs the `printing` permission.
+#[derive(Clone)]
+pub struct Printing {
+	api: Object,
+}
+
+impl Printing {
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "printing")?;
+		Ok(Self { api })
+	}
+
+	pub async fn get_printers(&self) -> Result<Vec<Printer>, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getPrinters", &[][..]).await
+	}
+
+	pub async fn get_printer_info(&self, printer_id: &str) -> Result<PrinterInfo, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getPrinterInfo", &[printer_id.into()][..]).await
+	}
+
+	/// `content_type` is the document's MIME type, e.g. `application/pdf`; the user may be asked to confirm the job.
+	pub async fn submit_job(
+		&self,
+		printer_id: &str,
+		title: &str,
+		ticket: &PrintTicket,
+		content_type: &str,
+		document: &Blob,
+	) -> Result<SubmitJobResponse, ExtensionError> {
+		let job = Object::new();
+		Reflect::set(&job, &"printerId".into(), &printer_id.into())?;
+		Reflect::set(&job, &"title".into(), &title.into())?;
+		Reflect::set(&job, &"ticket".into(), &to_value(ticket)?)?;
+		Reflect::set(&job, &"contentType".into(), &content_type.into())?;
+		Reflect::set(&job, &"document".into(), document)?;
+		let request = Object::new();
+		Reflect::set(&request, &"job".into(), &job)?;
+		call_async_fn_and_de(&self.api, "submitJob", &[request.into()][..]).await
+	}
+
+	pub async fn cancel_job(&self, job_id: &str) -> Result<(), ExtensionError> {
+		call_async_fn(&self.api, "cancelJob", &[job_id.into()][..]).await?;
+		Ok(())
+	}
+
+	pub async fn get_job_status(&self, job_id: &str) -> Result<JobStatus, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getJobStatus", &[job_id.into()][..]).await
+	}
+
+	pub fn on_job_status_changed(&self) -> Result<OnJobStatusChanged, ExtensionError> {
+		Ok(OnJobStatusChanged(get_api_namespace(&self.api, "onJobStatusChanged")?))
+	}
+
+	pub fn on_job_status_changed_stream(&self) -> Result<EventStream<(String, JobStatus)>, ExtensionError> {
+		let on_job_status_changed = self.on_job_status_changed()?;
+		EventStream::new(|sender| on_job_status_changed.add_listener(move |job_id, status| drop(sender.unbounded_send((job_id, status)))))
+	}
+}
+
+pub struct OnJobStatusChanged(Object);
+
+impl OnJobStatusChanged {
+	pub fn add_listener(&self, mut callback: impl FnMut(String, JobStatus) + 'static) -> Result<ListenerHandle<dyn FnMut(JsValue, JsValue)>, ExtensionError> {
+		attach_listener(
+			&self.0,
+			Closure::wrap(Box::new(move |job_id: JsValue, status: JsValue| {
+				if let (Some(job_id), Ok(status)) = (job_id.as_string(), serde_wasm_bindgen::from_value(status)) {
+					callback(job_id, status);
+				}
+			}) as Box<dyn FnMut(JsValue, JsValue)>),
+		)
+	}
+
+	pub fn once(&self) -> Result<Once<(String, JobStatus)>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |job_id, status| drop(sender.unbounded_send((job_id, status)))))
+	}
+}
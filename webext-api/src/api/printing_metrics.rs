@@ -0,0 +1,52 @@
+use crate::{
+	error::ExtensionError,
+	types::{EventStream, ListenerHandle, Once, PrintJobInfo, attach_listener},
+	utils::{call_async_fn_and_de, get_api_namespace},
+};
+use js_sys::Object;
+use wasm_bindgen::{JsValue, prelude::*};
+
+/// ChromeOS print history; policy-installed extensions only.
+#[derive(Clone)]
+pub struct PrintingMetrics {
+	api: Object,
+}
+
+impl PrintingMetrics {
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "printingMetrics")?;
+		Ok(Self { api })
+	}
+
+	pub async fn get_print_jobs(&self) -> Result<Vec<PrintJobInfo>, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getPrintJobs", &[][..]).await
+	}
+
+	pub fn on_print_job_finished(&self) -> Result<OnPrintJobFinished, ExtensionError> {
+		Ok(OnPrintJobFinished(get_api_namespace(&self.api, "onPrintJobFinished")?))
+	}
+
+	pub fn on_print_job_finished_stream(&self) -> Result<EventStream<PrintJobInfo>, ExtensionError> {
+		let on_print_job_finished = self.on_print_job_finished()?;
+		EventStream::new(|sender| on_print_job_finished.add_listener(move |job| drop(sender.unbounded_send(job))))
+	}
+}
+
+pub struct OnPrintJobFinished(Object);
+
+impl OnPrintJobFinished {
+	pub fn add_listener(&self, mut callback: impl FnMut(PrintJobInfo) + 'static) -> Result<ListenerHandle<dyn FnMut(JsValue)>, ExtensionError> {
+		attach_listener(
+			&self.0,
+			Closure::wrap(Box::new(move |val: JsValue| {
+				if let Ok(job) = serde_wasm_bindgen::from_value(val) {
+					callback(job);
+				}
+			}) as Box<dyn FnMut(JsValue)>),
+		)
+	}
+
+	pub fn once(&self) -> Result<Once<PrintJobInfo>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |event| drop(sender.unbounded_send(event))))
+	}
+}
@@ -0,0 +1,91 @@
+use crate::{
+	error::ExtensionError,
+	types::{EventStream, ListenerHandle, Once, ReadingListEntry, ReadingListEntryOptions, ReadingListQuery, attach_listener},
+	utils::{call_async_fn, call_async_fn_and_de, get_api_namespace},
+};
+use js_sys::{Object, Reflect};
+use serde_wasm_bindgen::to_value;
+use wasm_bindgen::{JsValue, prelude::*};
+
+#[derive(Clone)]
+pub struct ReadingList {
+	api: Object,
+}
+
+impl ReadingList {
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "readingList")?;
+		Ok(Self { api })
+	}
+
+	pub async fn add_entry(&self, url: &str, title: &str, has_been_read: bool) -> Result<(), ExtensionError> {
+		let entry = ReadingListEntryOptions { url: url.to_owned(), title: Some(title.to_owned()), has_been_read: Some(has_been_read) };
+		call_async_fn(&self.api, "addEntry", &[to_value(&entry)?][..]).await?;
+		Ok(())
+	}
+
+	pub async fn query(&self, query: ReadingListQuery) -> Result<Vec<ReadingListEntry>, ExtensionError> {
+		call_async_fn_and_de(&self.api, "query", &[to_value(&query)?][..]).await
+	}
+
+	/// Changes the title and/or read state of the entry for `update.url`; `None` fields are left alone.
+	pub async fn update_entry(&self, update: ReadingListEntryOptions) -> Result<(), ExtensionError> {
+		call_async_fn(&self.api, "updateEntry", &[to_value(&update)?][..]).await?;
+		Ok(())
+	}
+
+	pub async fn remove_entry(&self, url: &str) -> Result<(), ExtensionError> {
+		let info = Object::new();
+		Reflect::set(&info, &"url".into(), &url.into())?;
+		call_async_fn(&self.api, "removeEntry", &[info.into()][..]).await?;
+		Ok(())
+	}
+
+	pub fn on_entry_added(&self) -> Result<OnReadingListEntry, ExtensionError> {
+		Ok(OnReadingListEntry(get_api_namespace(&self.api, "onEntryAdded")?))
+	}
+
+	pub fn on_entry_added_stream(&self) -> Result<EventStream<ReadingListEntry>, ExtensionError> {
+		self.on_entry_added()?.into_stream()
+	}
+
+	pub fn on_entry_updated(&self) -> Result<OnReadingListEntry, ExtensionError> {
+		Ok(OnReadingListEntry(get_api_namespace(&self.api, "onEntryUpdated")?))
+	}
+
+	pub fn on_entry_updated_stream(&self) -> Result<EventStream<ReadingListEntry>, ExtensionError> {
+		self.on_entry_updated()?.into_stream()
+	}
+
+	pub fn on_entry_removed(&self) -> Result<OnReadingListEntry, ExtensionError> {
+		Ok(OnReadingListEntry(get_api_namespace(&self.api, "onEntryRemoved")?))
+	}
+
+	pub fn on_entry_removed_stream(&self) -> Result<EventStream<ReadingListEntry>, ExtensionError> {
+		self.on_entry_removed()?.into_stream()
+	}
+}
+
+/// Shared by `onEntryAdded`, `onEntryUpdated` and `onEntryRemoved`, which all pass the affected entry.
+pub struct OnReadingListEntry(Object);
+
+impl OnReadingListEntry {
+	pub fn add_listener(&self, mut callback: impl FnMut(ReadingListEntry) + 'static) -> Result<ListenerHandle<dyn FnMut(JsValue)>, ExtensionError> {
+		attach_listener(
+			&self.0,
+			Closure::wrap(Box::new(move |val: JsValue| {
+				if let Ok(entry) = serde_wasm_bindgen::from_value(val) {
+					callback(entry);
+				}
+			}) as Box<dyn FnMut(JsValue)>),
+		)
+	}
+
+	pub fn once(&self) -> Result<Once<ReadingListEntry>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |event| drop(sender.unbounded_send(event))))
+	}
+
+	fn into_stream(self) -> Result<EventStream<ReadingListEntry>, ExtensionError> {
+		EventStream::new(|sender| self.add_listener(move |entry| drop(sender.unbounded_send(entry))))
+	}
+}
@@ -1,13 +1,18 @@
 use crate::utils::call_async_fn;
 use crate::{
+	api::{OnPortEvent, Port},
 	error::ExtensionError,
-	types::{ListenerHandle, MessageSender, attach_listener},
-	utils::{call_async_fn_and_de, get_api_namespace},
+	retry::{RetryPolicy, with_retries},
+	types::{
+		ContextFilter, EventStream, ExtensionContext, InstalledDetails, ListenerHandle, MessageSender, Once, PlatformInfo, SenderAllowlist, UpdateCheck,
+		UpdateCheckStatus, attach_listener,
+	},
+	utils::{call_async_fn_and_de, get_api_namespace, last_error},
 };
-use js_sys::{Object, Promise};
-use serde::{Serialize, de::DeserializeOwned};
+use js_sys::{Function, Object, Promise, Reflect};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_wasm_bindgen::to_value;
-use std::{future::Future, marker::PhantomData};
+use std::{future::Future, marker::PhantomData, rc::Rc, time::Duration};
 use wasm_bindgen::{JsValue, prelude::*};
 use wasm_bindgen_futures::future_to_promise;
 
@@ -17,59 +22,263 @@ pub struct Runtime {
 }
 
 impl Runtime {
-	pub(crate) fn new(api_root: &Object) -> Self {
-		let api = get_api_namespace(api_root, "runtime").expect("`runtime` API not available");
-		Self { api }
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "runtime")?;
+		Ok(Self { api })
 	}
 
 	pub async fn send_message<M: Serialize, R: DeserializeOwned>(&self, message: &M) -> Result<R, ExtensionError> {
 		call_async_fn_and_de(&self.api, "sendMessage", &[to_value(message)?][..]).await
 	}
 
+	/// [`Self::send_message`], retried under `policy` while nothing is listening yet.
+	pub async fn send_message_with_retries<M: Serialize, R: DeserializeOwned>(&self, message: &M, policy: &RetryPolicy) -> Result<R, ExtensionError> {
+		with_retries(policy, "runtime.sendMessage", || self.send_message(message)).await
+	}
+
 	pub fn on_message<T: DeserializeOwned + 'static>(&self) -> Result<OnMessage<T>, ExtensionError> {
 		Ok(OnMessage::new(get_api_namespace(&self.api, "onMessage")?))
 	}
 
+	pub fn on_message_stream<T: DeserializeOwned + 'static>(&self) -> Result<EventStream<(T, MessageSender)>, ExtensionError> {
+		let on_message = self.on_message::<T>()?;
+		EventStream::new(|sender| on_message.add_listener(move |message, message_sender| drop(sender.unbounded_send((message, message_sender)))))
+	}
+
+	/// Messages another extension; `extension_id` must list this one (or all ids) under `externally_connectable`.
+	pub async fn send_message_external<M: Serialize, R: DeserializeOwned>(&self, extension_id: &str, message: &M) -> Result<R, ExtensionError> {
+		call_async_fn_and_de(&self.api, "sendMessage", &[extension_id.into(), to_value(message)?][..]).await
+	}
+
+	/// Messages from other extensions and web pages, with senders outside `allowlist` dropped before deserializing.
+	pub fn on_message_external<T: DeserializeOwned + 'static>(&self, allowlist: SenderAllowlist) -> Result<OnMessage<T>, ExtensionError> {
+		Ok(OnMessage { api: get_api_namespace(&self.api, "onMessageExternal")?, allowlist: Some(Rc::new(allowlist)), _phantom: PhantomData })
+	}
+
+	pub fn on_message_external_stream<T: DeserializeOwned + 'static>(
+		&self,
+		allowlist: SenderAllowlist,
+	) -> Result<EventStream<(T, MessageSender)>, ExtensionError> {
+		let on_message = self.on_message_external::<T>(allowlist)?;
+		EventStream::new(|sender| on_message.add_listener(move |message, message_sender| drop(sender.unbounded_send((message, message_sender)))))
+	}
+
+	pub fn connect(&self, name: Option<&str>) -> Result<Port, ExtensionError> {
+		let connect_info = Object::new();
+		if let Some(name) = name {
+			Reflect::set(&connect_info, &"name".into(), &name.into())?;
+		}
+		let connect: Function = Reflect::get(&self.api, &"connect".into())?.dyn_into()?;
+		Ok(Port::from_js(connect.call1(&self.api, &connect_info)?))
+	}
+
+	pub fn connect_external(&self, extension_id: &str, name: Option<&str>) -> Result<Port, ExtensionError> {
+		let connect_info = Object::new();
+		if let Some(name) = name {
+			Reflect::set(&connect_info, &"name".into(), &name.into())?;
+		}
+		let connect: Function = Reflect::get(&self.api, &"connect".into())?.dyn_into()?;
+		Ok(Port::from_js(connect.call2(&self.api, &extension_id.into(), &connect_info)?))
+	}
+
+	pub fn on_connect(&self) -> Result<OnPortEvent, ExtensionError> {
+		Ok(OnPortEvent::new(get_api_namespace(&self.api, "onConnect")?, None))
+	}
+
+	pub fn on_connect_external(&self, allowlist: SenderAllowlist) -> Result<OnPortEvent, ExtensionError> {
+		Ok(OnPortEvent::new(get_api_namespace(&self.api, "onConnectExternal")?, Some(allowlist)))
+	}
+
 	pub async fn open_options_page(&self) -> Result<(), ExtensionError> {
 		call_async_fn(&self.api, "openOptionsPage", &[]).await?;
 		Ok(())
 	}
+
+	pub fn get_url(&self, path: &str) -> Result<String, ExtensionError> {
+		let func: js_sys::Function = js_sys::Reflect::get(&self.api, &"getURL".into())?.dyn_into()?;
+		func.call1(&self.api, &path.into())?.as_string().ok_or_else(|| ExtensionError::ApiError("`runtime.getURL` did not return a string".to_owned()))
+	}
+
+	/// The extension's `manifest.json`, deserialized into whatever part of it `T` describes, e.g. a struct with just a `version`.
+	pub fn get_manifest<T: DeserializeOwned>(&self) -> Result<T, ExtensionError> {
+		Ok(serde_wasm_bindgen::from_value(self.require("getManifest")?.call0(&self.api)?)?)
+	}
+
+	pub async fn get_contexts(&self, filter: ContextFilter) -> Result<Vec<ExtensionContext>, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getContexts", &[to_value(&filter)?][..]).await
+	}
+
+	pub async fn get_platform_info(&self) -> Result<PlatformInfo, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getPlatformInfo", &[][..]).await
+	}
+
+	pub async fn request_update_check(&self) -> Result<UpdateCheck, ExtensionError> {
+		// Chrome resolves `{ status, version }`, Firefox `{ status, details: { version } }`
+		#[derive(Deserialize)]
+		struct RawUpdateCheck {
+			status: UpdateCheckStatus,
+			version: Option<String>,
+			details: Option<UpdateDetails>,
+		}
+		let raw: RawUpdateCheck = call_async_fn_and_de(&self.api, "requestUpdateCheck", &[][..]).await?;
+		Ok(UpdateCheck { status: raw.status, version: raw.version.or(raw.details.map(|details| details.version)) })
+	}
+
+	/// Fires once an update has been downloaded; it is applied on the next restart, or right away by calling `reload`.
+	pub fn on_update_available(&self) -> Result<OnUpdateAvailable, ExtensionError> {
+		Ok(OnUpdateAvailable(get_api_namespace(&self.api, "onUpdateAvailable")?))
+	}
+
+	pub fn on_update_available_stream(&self) -> Result<EventStream<String>, ExtensionError> {
+		let on_update_available = self.on_update_available()?;
+		EventStream::new(|sender| on_update_available.add_listener(move |version| drop(sender.unbounded_send(version))))
+	}
+
+	/// Fires when the extension is installed or updated, or the browser is. Register listeners synchronously at startup,
+	/// since the event has already passed by the time anything awaited resolves.
+	pub fn on_installed(&self) -> Result<OnInstalled, ExtensionError> {
+		Ok(OnInstalled(get_api_namespace(&self.api, "onInstalled")?))
+	}
+
+	pub fn on_installed_stream(&self) -> Result<EventStream<InstalledDetails>, ExtensionError> {
+		let on_installed = self.on_installed()?;
+		EventStream::new(|sender| on_installed.add_listener(move |details| drop(sender.unbounded_send(details))))
+	}
+
+	/// Reloads the extension, applying a pending update; nothing after this call gets to run.
+	pub fn reload(&self) -> Result<(), ExtensionError> {
+		self.call_sync("reload")
+	}
+
+	/// Restarts the device. Only ChromeOS kiosk apps can do this: other browsers lack it with [`ExtensionError::ApiNotFound`], and
+	/// elsewhere in Chrome, where `runtime.restart` would quietly do nothing, it rejects like [`Self::restart_after_delay`].
+	pub async fn restart(&self) -> Result<(), ExtensionError> {
+		let restart = self.require("restart")?;
+		// `restart` reports nothing back, so ask `restartAfterDelay` whether this is kiosk mode; cancelling a scheduled restart is moot
+		// when restarting now
+		self.restart_after_delay(None).await?;
+		restart.call0(&self.api)?;
+		Ok(())
+	}
+
+	/// Schedules a kiosk restart after `delay`, or cancels a scheduled one with `None`. Rejects outside ChromeOS kiosk mode.
+	pub async fn restart_after_delay(&self, delay: Option<Duration>) -> Result<(), ExtensionError> {
+		self.require("restartAfterDelay")?;
+		let seconds = delay.map_or(-1.0, |delay| delay.as_secs() as f64);
+		call_async_fn(&self.api, "restartAfterDelay", &[seconds.into()][..]).await?;
+		Ok(())
+	}
+
+	pub fn last_error(&self) -> Option<ExtensionError> {
+		last_error()
+	}
+
+	fn require(&self, method: &str) -> Result<Function, ExtensionError> {
+		Reflect::get(&self.api, &method.into())?.dyn_into().map_err(|_| ExtensionError::ApiNotFound(format!("runtime.{method}")))
+	}
+
+	fn call_sync(&self, method: &str) -> Result<(), ExtensionError> {
+		self.require(method)?.call0(&self.api)?;
+		Ok(())
+	}
+}
+
+#[derive(Deserialize)]
+struct UpdateDetails {
+	version: String,
+}
+
+pub struct OnUpdateAvailable(Object);
+
+impl OnUpdateAvailable {
+	pub fn add_listener(&self, mut callback: impl FnMut(String) + 'static) -> Result<ListenerHandle<dyn FnMut(JsValue)>, ExtensionError> {
+		attach_listener(
+			&self.0,
+			Closure::wrap(Box::new(move |details: JsValue| {
+				if let Ok(details) = serde_wasm_bindgen::from_value::<UpdateDetails>(details) {
+					callback(details.version);
+				}
+			}) as Box<dyn FnMut(JsValue)>),
+		)
+	}
+
+	pub fn once(&self) -> Result<Once<String>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |event| drop(sender.unbounded_send(event))))
+	}
+}
+
+pub struct OnInstalled(Object);
+
+impl OnInstalled {
+	pub fn add_listener(&self, mut callback: impl FnMut(InstalledDetails) + 'static) -> Result<ListenerHandle<dyn FnMut(JsValue)>, ExtensionError> {
+		attach_listener(
+			&self.0,
+			Closure::wrap(Box::new(move |details: JsValue| {
+				if let Ok(details) = serde_wasm_bindgen::from_value(details) {
+					callback(details);
+				}
+			}) as Box<dyn FnMut(JsValue)>),
+		)
+	}
+
+	pub fn once(&self) -> Result<Once<InstalledDetails>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |event| drop(sender.unbounded_send(event))))
+	}
 }
 
 pub struct OnMessage<T: DeserializeOwned + 'static> {
 	api: Object,
+	allowlist: Option<Rc<SenderAllowlist>>,
 	_phantom: PhantomData<T>,
 }
 
 impl<T: DeserializeOwned + 'static> OnMessage<T> {
 	fn new(api: Object) -> Self {
-		Self { api, _phantom: PhantomData }
+		Self { api, allowlist: None, _phantom: PhantomData }
+	}
+
+	pub(crate) fn target(&self) -> &Object {
+		&self.api
 	}
 
 	pub fn add_listener(
 		&self,
 		mut callback: impl FnMut(T, MessageSender) + 'static,
 	) -> Result<ListenerHandle<dyn FnMut(JsValue, JsValue, JsValue)>, ExtensionError> {
+		let allowlist = self.allowlist.clone();
 		attach_listener(
 			&self.api,
 			Closure::wrap(Box::new(move |message, sender, _| {
-				if let (Ok(msg), Ok(sender)) = (serde_wasm_bindgen::from_value(message), serde_wasm_bindgen::from_value(sender)) {
+				let Ok(sender) = serde_wasm_bindgen::from_value::<MessageSender>(sender) else {
+					return;
+				};
+				if allowlist.as_ref().is_some_and(|allowlist| !allowlist.allows(&sender)) {
+					return;
+				}
+				if let Ok(msg) = serde_wasm_bindgen::from_value(message) {
 					callback(msg, sender);
 				}
 			}) as Box<dyn FnMut(JsValue, JsValue, JsValue)>),
 		)
 	}
 
+	pub fn once(&self) -> Result<Once<(T, MessageSender)>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |message, message_sender| drop(sender.unbounded_send((message, message_sender)))))
+	}
+
 	pub fn add_listener_with_response<F, R, O>(&self, mut callback: F) -> Result<ListenerHandle<dyn FnMut(JsValue, JsValue, JsValue) -> Promise>, ExtensionError>
 	where
 		F: FnMut(T, MessageSender) -> R + 'static,
 		R: Future<Output = Result<O, JsValue>> + 'static,
 		O: Serialize,
 	{
+		let allowlist = self.allowlist.clone();
 		attach_listener(
 			&self.api,
 			Closure::wrap(Box::new(move |message, sender, _| {
-				if let (Ok(msg), Ok(sender)) = (serde_wasm_bindgen::from_value(message), serde_wasm_bindgen::from_value(sender)) {
+				if let (Ok(msg), Ok(sender)) = (serde_wasm_bindgen::from_value(message), serde_wasm_bindgen::from_value::<MessageSender>(sender))
+					&& allowlist.as_ref().is_none_or(|allowlist| allowlist.allows(&sender))
+				{
 					let future_from_callback = callback(msg, sender);
 					let processing_future = async move { future_from_callback.await.and_then(|val| to_value(&val).map_err(|e| e.into())) };
 					return future_to_promise(processing_future);
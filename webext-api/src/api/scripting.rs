@@ -1,9 +1,11 @@
 use crate::{
 	error::ExtensionError,
-	utils::{call_async_fn, get_api_namespace},
+	types::{CssInjection, InjectionResult, RegisteredContentScript, ScriptInjection, World},
+	utils::{call_async_fn, call_async_fn_and_de, get_api_namespace},
 };
 use js_sys::{Function, Object, Reflect};
 use serde::de::DeserializeOwned;
+use serde_wasm_bindgen::to_value;
 use wasm_bindgen::{JsCast, JsValue};
 
 #[derive(Clone)]
@@ -12,17 +14,18 @@ pub struct Scripting {
 }
 
 impl Scripting {
-	pub(crate) fn new(api_root: &Object) -> Self {
-		let api = get_api_namespace(api_root, "scripting").expect("`scripting` API not available");
-		Self { api }
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "scripting")?;
+		Ok(Self { api })
 	}
 
-	pub async fn execute_script<T: DeserializeOwned>(&self, tab_id: u32, func: &str) -> Result<T, ExtensionError> {
+	pub async fn execute_script<T: DeserializeOwned>(&self, tab_id: u32, func: &str, world: World) -> Result<T, ExtensionError> {
 		let config = Object::new();
 		let target = Object::new();
 		Reflect::set(&target, &"tabId".into(), &tab_id.into())?;
 		Reflect::set(&config, &"target".into(), &target)?;
 		Reflect::set(&config, &"func".into(), &Function::new_no_args(func))?;
+		Reflect::set(&config, &"world".into(), &to_value(&world)?)?;
 		let results = call_async_fn(&self.api, "executeScript", &[config.into()][..]).await?;
 		let results_array: js_sys::Array = results.dyn_into()?;
 		if let Some(result_obj) = results_array.iter().next() {
@@ -31,4 +34,45 @@ impl Scripting {
 			serde_wasm_bindgen::from_value(JsValue::NULL).map_err(Into::into)
 		}
 	}
+
+	pub async fn execute<T: DeserializeOwned>(&self, injection: ScriptInjection) -> Result<Vec<InjectionResult<T>>, ExtensionError> {
+		call_async_fn_and_de(&self.api, "executeScript", &[injection.to_js()?.into()][..]).await
+	}
+
+	pub async fn insert_css(&self, injection: CssInjection) -> Result<(), ExtensionError> {
+		call_async_fn(&self.api, "insertCSS", &[to_value(&injection)?][..]).await?;
+		Ok(())
+	}
+
+	pub async fn remove_css(&self, injection: CssInjection) -> Result<(), ExtensionError> {
+		call_async_fn(&self.api, "removeCSS", &[to_value(&injection)?][..]).await?;
+		Ok(())
+	}
+
+	pub async fn register_content_scripts(&self, scripts: &[RegisteredContentScript]) -> Result<(), ExtensionError> {
+		call_async_fn(&self.api, "registerContentScripts", &[to_value(scripts)?][..]).await?;
+		Ok(())
+	}
+
+	pub async fn update_content_scripts(&self, scripts: &[RegisteredContentScript]) -> Result<(), ExtensionError> {
+		call_async_fn(&self.api, "updateContentScripts", &[to_value(scripts)?][..]).await?;
+		Ok(())
+	}
+
+	pub async fn unregister_content_scripts(&self, ids: Option<&[&str]>) -> Result<(), ExtensionError> {
+		call_async_fn(&self.api, "unregisterContentScripts", &[script_filter(ids)?][..]).await?;
+		Ok(())
+	}
+
+	pub async fn get_registered_content_scripts(&self, ids: Option<&[&str]>) -> Result<Vec<RegisteredContentScript>, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getRegisteredContentScripts", &[script_filter(ids)?][..]).await
+	}
+}
+
+fn script_filter(ids: Option<&[&str]>) -> Result<JsValue, ExtensionError> {
+	let filter = Object::new();
+	if let Some(ids) = ids {
+		Reflect::set(&filter, &"ids".into(), &to_value(ids)?)?;
+	}
+	Ok(filter.into())
 }
@@ -0,0 +1,111 @@
+use crate::{
+	error::ExtensionError,
+	types::{EventStream, LevelOfControl, ListenerHandle, Once, SettingValue, attach_listener},
+	utils::{call_async_fn, get_api_namespace},
+};
+use js_sys::Object;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_wasm_bindgen::{from_value, to_value};
+use std::marker::PhantomData;
+use wasm_bindgen::{JsValue, prelude::*};
+
+/// The method names one setting is read, written and watched through.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SettingMethods {
+	pub get: &'static str,
+	pub set: &'static str,
+	pub clear: &'static str,
+	pub on_change: &'static str,
+	/// The key holding the value in the details objects, e.g. `value` for `types.ChromeSetting` or `pixelSize` for font sizes.
+	pub value_key: &'static str,
+}
+
+/// A browser-wide preference with a level of control: a `types.ChromeSetting`, or namespaces like `fontSettings` that follow the same
+/// get/set/clear/onChange shape under their own method names.
+#[derive(Clone)]
+pub struct BrowserSetting<T> {
+	api: Object,
+	methods: SettingMethods,
+	// extra keys sent with every call and used to filter change events, e.g. `genericFamily` and `script` for a font
+	details: Object,
+	_phantom: PhantomData<T>,
+}
+
+impl<T: Serialize + DeserializeOwned + 'static> BrowserSetting<T> {
+	pub(crate) fn new(api: Object, methods: SettingMethods, details: Object) -> Self {
+		Self { api, methods, details, _phantom: PhantomData }
+	}
+
+	pub async fn get(&self) -> Result<SettingValue<T>, ExtensionError> {
+		let result = call_async_fn(&self.api, self.methods.get, &[self.details().into()][..]).await?;
+		parse_setting(&result, self.methods.value_key)
+	}
+
+	pub async fn set(&self, value: &T) -> Result<(), ExtensionError> {
+		let details = self.details();
+		js_sys::Reflect::set(&details, &self.methods.value_key.into(), &to_value(value)?)?;
+		call_async_fn(&self.api, self.methods.set, &[details.into()][..]).await?;
+		Ok(())
+	}
+
+	/// Drops this extension's override so the user's (or another extension's) value applies again.
+	pub async fn clear(&self) -> Result<(), ExtensionError> {
+		call_async_fn(&self.api, self.methods.clear, &[self.details().into()][..]).await?;
+		Ok(())
+	}
+
+	pub fn on_change(&self) -> Result<OnSettingChanged<T>, ExtensionError> {
+		Ok(OnSettingChanged {
+			api: get_api_namespace(&self.api, self.methods.on_change)?,
+			value_key: self.methods.value_key,
+			details: self.details(),
+			_phantom: PhantomData,
+		})
+	}
+
+	pub fn on_change_stream(&self) -> Result<EventStream<SettingValue<T>>, ExtensionError> {
+		let on_change = self.on_change()?;
+		EventStream::new(|sender| on_change.add_listener(move |value| drop(sender.unbounded_send(value))))
+	}
+
+	fn details(&self) -> Object {
+		Object::assign(&Object::new(), &self.details)
+	}
+}
+
+pub struct OnSettingChanged<T> {
+	api: Object,
+	value_key: &'static str,
+	details: Object,
+	_phantom: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned + 'static> OnSettingChanged<T> {
+	pub fn add_listener(&self, mut callback: impl FnMut(SettingValue<T>) + 'static) -> Result<ListenerHandle<dyn FnMut(JsValue)>, ExtensionError> {
+		let (value_key, details) = (self.value_key, self.details.clone());
+		attach_listener(
+			&self.api,
+			Closure::wrap(Box::new(move |val: JsValue| {
+				// namespace-wide events (e.g. `onFontChanged`) fire for every family and script, so keep only the ones for this setting
+				let matches = Object::entries(&details)
+					.iter()
+					.map(|entry| js_sys::Array::from(&entry))
+					.all(|entry| js_sys::Reflect::get(&val, &entry.get(0)).is_ok_and(|v| Object::is(&v, &entry.get(1))));
+				if matches && let Ok(value) = parse_setting(&val, value_key) {
+					callback(value);
+				}
+			}) as Box<dyn FnMut(JsValue)>),
+		)
+	}
+
+	pub fn once(&self) -> Result<Once<SettingValue<T>>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |event| drop(sender.unbounded_send(event))))
+	}
+}
+
+fn parse_setting<T: DeserializeOwned>(details: &JsValue, value_key: &str) -> Result<SettingValue<T>, ExtensionError> {
+	let value = from_value(js_sys::Reflect::get(details, &value_key.into())?)?;
+	let level_of_control: LevelOfControl = from_value(js_sys::Reflect::get(details, &"levelOfControl".into())?)?;
+	let incognito_specific = js_sys::Reflect::get(details, &"incognitoSpecific".into())?.as_bool();
+	Ok(SettingValue { value, level_of_control, incognito_specific })
+}
@@ -1,37 +1,164 @@
 use crate::{
 	error::ExtensionError,
-	types::BrowserType,
-	utils::{call_async_fn, get_api_namespace},
+	types::{PanelBehavior, PanelOptions, SidePanelKind},
+	utils::{call_async_fn, call_async_fn_and_de, get_api_namespace, tab_details},
 };
-use js_sys::Object;
+use js_sys::{Object, Reflect};
+use serde_wasm_bindgen::to_value;
+#[cfg(feature = "firefox")]
+use std::collections::BTreeMap;
 
+/// One facade over Chromium's `sidePanel` and Firefox's `sidebarAction`. Which one is driven is decided by which namespace exists, not
+/// by the browser name, so Chromium forks that ship either one work too.
 #[derive(Clone)]
 pub struct SidePanel {
 	api_root: Object,
-	browser_type: BrowserType,
+}
+
+enum Backend {
+	SidePanel(Object),
+	SidebarAction(Object),
 }
 
 impl SidePanel {
-	pub(crate) fn new(api_root: &Object, browser_type: BrowserType) -> Self {
-		Self { api_root: api_root.clone(), browser_type }
+	pub(crate) fn new(api_root: &Object) -> Self {
+		Self { api_root: api_root.clone() }
+	}
+
+	/// Which side UI this browser offers, or `None` if it has neither (or the permission/manifest key is missing).
+	pub fn kind(&self) -> Option<SidePanelKind> {
+		self.backend().ok().map(|backend| match backend {
+			Backend::SidePanel(_) => SidePanelKind::SidePanel,
+			Backend::SidebarAction(_) => SidePanelKind::SidebarAction,
+		})
+	}
+
+	fn backend(&self) -> Result<Backend, ExtensionError> {
+		get_api_namespace(&self.api_root, "sidePanel")
+			.map(Backend::SidePanel)
+			.or_else(|_| get_api_namespace(&self.api_root, "sidebarAction").map(Backend::SidebarAction))
+			.map_err(|_| ExtensionError::ApiNotFound("sidePanel".into()))
+	}
+
+	#[cfg(feature = "firefox")]
+	fn sidebar_action(&self, method: &str) -> Result<Object, ExtensionError> {
+		match self.backend()? {
+			Backend::SidebarAction(api) => Ok(api),
+			Backend::SidePanel(_) => Err(ExtensionError::ApiNotFound(format!("sidebarAction.{method}"))),
+		}
 	}
 
 	pub async fn open(&self, tab_id: Option<u32>) -> Result<(), ExtensionError> {
-		match self.browser_type {
-			BrowserType::Chrome => {
-				let side_panel_api = get_api_namespace(&self.api_root, "sidePanel")?;
+		match self.backend()? {
+			Backend::SidePanel(api) => {
 				let options = Object::new();
 				if let Some(id) = tab_id {
 					js_sys::Reflect::set(&options, &"tabId".into(), &id.into())?;
 				}
-				call_async_fn(&side_panel_api, "open", &[options.into()][..]).await?;
-				Ok(())
+				call_async_fn(&api, "open", &[options.into()][..]).await?;
+			},
+			Backend::SidebarAction(api) => {
+				call_async_fn(&api, "open", &[][..]).await?;
 			},
-			BrowserType::Firefox => {
-				let sidebar_action_api = get_api_namespace(&self.api_root, "sidebarAction")?;
-				call_async_fn(&sidebar_action_api, "open", &[][..]).await?;
-				Ok(())
+		}
+		Ok(())
+	}
+
+	/// Sets the panel's page and, on `sidePanel`, whether it is enabled. `sidebarAction` can't disable the sidebar, so setting
+	/// `enabled` there fails with [`ExtensionError::UnsupportedBrowser`] rather than leaving it available.
+	pub async fn set_options(&self, options: PanelOptions) -> Result<(), ExtensionError> {
+		match self.backend()? {
+			Backend::SidePanel(api) => {
+				call_async_fn(&api, "setOptions", &[to_value(&options)?][..]).await?;
+			},
+			Backend::SidebarAction(_) if options.enabled.is_some() => return Err(ExtensionError::UnsupportedBrowser),
+			Backend::SidebarAction(api) => {
+				let details = tab_details(options.tab_id)?;
+				if let Some(path) = options.path {
+					Reflect::set(&details, &"panel".into(), &path.into())?;
+				}
+				call_async_fn(&api, "setPanel", &[details.into()][..]).await?;
+			},
+		}
+		Ok(())
+	}
+
+	pub async fn get_options(&self, tab_id: Option<u32>) -> Result<PanelOptions, ExtensionError> {
+		match self.backend()? {
+			Backend::SidePanel(api) => call_async_fn_and_de(&api, "getOptions", &[tab_details(tab_id)?.into()][..]).await,
+			Backend::SidebarAction(api) => {
+				let panel: String = call_async_fn_and_de(&api, "getPanel", &[tab_details(tab_id)?.into()][..]).await?;
+				Ok(PanelOptions { path: Some(panel), enabled: None, tab_id })
 			},
 		}
 	}
+
+	pub async fn set_panel_behavior(&self, behavior: PanelBehavior) -> Result<(), ExtensionError> {
+		let side_panel_api = get_api_namespace(&self.api_root, "sidePanel")?;
+		call_async_fn(&side_panel_api, "setPanelBehavior", &[to_value(&behavior)?][..]).await?;
+		Ok(())
+	}
+
+	pub async fn get_panel_behavior(&self) -> Result<PanelBehavior, ExtensionError> {
+		let side_panel_api = get_api_namespace(&self.api_root, "sidePanel")?;
+		call_async_fn_and_de(&side_panel_api, "getPanelBehavior", &[][..]).await
+	}
+
+	/// The sidebar's title in the sidebar switcher; `None` resets it to the manifest's `sidebar_action.default_title`.
+	#[cfg(feature = "firefox")]
+	pub async fn set_title(&self, title: Option<&str>, tab_id: Option<u32>) -> Result<(), ExtensionError> {
+		let api = self.sidebar_action("setTitle")?;
+		let details = tab_details(tab_id)?;
+		Reflect::set(&details, &"title".into(), &title.map_or(wasm_bindgen::JsValue::NULL, Into::into))?;
+		call_async_fn(&api, "setTitle", &[details.into()][..]).await?;
+		Ok(())
+	}
+
+	#[cfg(feature = "firefox")]
+	pub async fn get_title(&self, tab_id: Option<u32>) -> Result<String, ExtensionError> {
+		let api = self.sidebar_action("getTitle")?;
+		call_async_fn_and_de(&api, "getTitle", &[tab_details(tab_id)?.into()][..]).await
+	}
+
+	/// Icon paths keyed by size in pixels; an empty map resets to the manifest's `sidebar_action.default_icon`.
+	#[cfg(feature = "firefox")]
+	pub async fn set_icon(&self, paths: &BTreeMap<u32, String>, tab_id: Option<u32>) -> Result<(), ExtensionError> {
+		let api = self.sidebar_action("setIcon")?;
+		let details = tab_details(tab_id)?;
+		if !paths.is_empty() {
+			let sizes = Object::new();
+			for (size, path) in paths {
+				Reflect::set(&sizes, &(*size).into(), &path.into())?;
+			}
+			Reflect::set(&details, &"path".into(), &sizes)?;
+		}
+		call_async_fn(&api, "setIcon", &[details.into()][..]).await?;
+		Ok(())
+	}
+
+	/// Whether the sidebar is open in `window_id`, or in the current window.
+	#[cfg(feature = "firefox")]
+	pub async fn is_open(&self, window_id: Option<u32>) -> Result<bool, ExtensionError> {
+		let api = self.sidebar_action("isOpen")?;
+		let details = Object::new();
+		if let Some(id) = window_id {
+			Reflect::set(&details, &"windowId".into(), &id.into())?;
+		}
+		call_async_fn_and_de(&api, "isOpen", &[details.into()][..]).await
+	}
+
+	/// Like `open`, this must be called from a user action handler.
+	#[cfg(feature = "firefox")]
+	pub async fn toggle(&self) -> Result<(), ExtensionError> {
+		let api = self.sidebar_action("toggle")?;
+		call_async_fn(&api, "toggle", &[][..]).await?;
+		Ok(())
+	}
+
+	#[cfg(feature = "firefox")]
+	pub async fn close(&self) -> Result<(), ExtensionError> {
+		let api = self.sidebar_action("close")?;
+		call_async_fn(&api, "close", &[][..]).await?;
+		Ok(())
+	}
 }
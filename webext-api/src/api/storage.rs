@@ -1,10 +1,13 @@
 use crate::{
 	error::ExtensionError,
-	utils::{call_async_fn, get_api_namespace},
+	types::{ListenerHandle, Once, StorageChange, attach_listener},
+	utils::{call_async_fn, expect_namespace, get_api_namespace},
 };
 use js_sys::{Object, Reflect};
 use serde::{Serialize, de::DeserializeOwned};
 use serde_wasm_bindgen::to_value;
+use std::collections::BTreeMap;
+use wasm_bindgen::{JsValue, prelude::*};
 
 #[derive(Clone)]
 pub struct Storage {
@@ -12,19 +15,41 @@ pub struct Storage {
 }
 
 impl Storage {
-	pub(crate) fn new(api_root: &Object) -> Self {
-		let api = get_api_namespace(api_root, "storage").expect("`storage` API not available");
-		Self { api }
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "storage")?;
+		Ok(Self { api })
 	}
 
 	pub fn local(&self) -> StorageArea {
-		let local_api = get_api_namespace(&self.api, "local").expect("`storage.local` API not available");
-		StorageArea { api: local_api }
+		expect_namespace(self.try_local())
+	}
+
+	pub fn try_local(&self) -> Result<StorageArea, ExtensionError> {
+		self.area("local")
 	}
 
 	pub fn sync(&self) -> StorageArea {
-		let sync_api = get_api_namespace(&self.api, "sync").expect("`storage.sync` API not available");
-		StorageArea { api: sync_api }
+		expect_namespace(self.try_sync())
+	}
+
+	pub fn try_sync(&self) -> Result<StorageArea, ExtensionError> {
+		self.area("sync")
+	}
+
+	/// The in-memory area that lasts until the browser closes, surviving the background's service worker being stopped. Only
+	/// extension pages and the background can access it, not content scripts, which get [`ExtensionError::ApiNotFound`] from
+	/// [`Self::try_session`].
+	pub fn session(&self) -> StorageArea {
+		expect_namespace(self.try_session())
+	}
+
+	pub fn try_session(&self) -> Result<StorageArea, ExtensionError> {
+		self.area("session")
+	}
+
+	fn area(&self, name: &str) -> Result<StorageArea, ExtensionError> {
+		let api = get_api_namespace(&self.api, name).map_err(|_| ExtensionError::ApiNotFound(format!("storage.{name}")))?;
+		Ok(StorageArea { api })
 	}
 }
 
@@ -46,4 +71,37 @@ impl StorageArea {
 		call_async_fn(&self.api, "set", &[items.into()][..]).await?;
 		Ok(())
 	}
+
+	pub fn on_changed(&self) -> Result<OnStorageChanged, ExtensionError> {
+		Ok(OnStorageChanged(get_api_namespace(&self.api, "onChanged")?))
+	}
+}
+
+pub struct OnStorageChanged(Object);
+
+impl OnStorageChanged {
+	pub fn add_listener(
+		&self,
+		mut callback: impl FnMut(BTreeMap<String, StorageChange>) + 'static,
+	) -> Result<ListenerHandle<dyn FnMut(JsValue)>, ExtensionError> {
+		attach_listener(
+			&self.0,
+			Closure::wrap(Box::new(move |changes: JsValue| {
+				let changes = Object::entries(changes.unchecked_ref())
+					.iter()
+					.filter_map(|entry| {
+						let key = Reflect::get_u32(&entry, 0).ok()?.as_string()?;
+						let change = Reflect::get_u32(&entry, 1).ok()?;
+						let field = |name: &str| Reflect::get(&change, &name.into()).ok().filter(|v| !v.is_undefined());
+						Some((key, StorageChange { old_value: field("oldValue"), new_value: field("newValue") }))
+					})
+					.collect();
+				callback(changes);
+			}) as Box<dyn FnMut(JsValue)>),
+		)
+	}
+
+	pub fn once(&self) -> Result<Once<BTreeMap<String, StorageChange>>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |event| drop(sender.unbounded_send(event))))
+	}
 }
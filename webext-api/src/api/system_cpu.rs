@@ -0,0 +1,25 @@
+use crate::{
+	error::ExtensionError,
+	types::CpuInfo,
+	utils::{call_async_fn_and_de, get_api_namespace},
+};
+use js_sys::Object;
+
+#[derive(Clone)]
+pub struct SystemCpu {
+	api: Object,
+}
+
+impl SystemCpu {
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "system")
+			.and_then(|system| get_api_namespace(&system, "cpu"))
+			.map_err(|_| ExtensionError::ApiNotFound("system.cpu".to_owned()))?;
+		Ok(Self { api })
+	}
+
+	/// Processor times are cumulative since boot; diff two readings to get the current usage.
+	pub async fn get_info(&self) -> Result<CpuInfo, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getInfo", &[][..]).await
+	}
+}
@@ -0,0 +1,57 @@
+use crate::{
+	error::ExtensionError,
+	types::{DisplayLayout, DisplayUnitInfo, EventStream, ListenerHandle, Once, attach_listener},
+	utils::{call_async_fn_and_de, get_api_namespace},
+};
+use js_sys::{Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+#[derive(Clone)]
+pub struct SystemDisplay {
+	api: Object,
+}
+
+impl SystemDisplay {
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "system")
+			.and_then(|system| get_api_namespace(&system, "display"))
+			.map_err(|_| ExtensionError::ApiNotFound("system.display".to_owned()))?;
+		Ok(Self { api })
+	}
+
+	/// With `single_unified`, a unified desktop is reported as one display rather than one per monitor.
+	pub async fn get_info(&self, single_unified: bool) -> Result<Vec<DisplayUnitInfo>, ExtensionError> {
+		let flags = Object::new();
+		Reflect::set(&flags, &"singleUnified".into(), &single_unified.into())?;
+		call_async_fn_and_de(&self.api, "getInfo", &[flags.into()][..]).await
+	}
+
+	pub async fn get_display_layout(&self) -> Result<Vec<DisplayLayout>, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getDisplayLayout", &[][..]).await
+	}
+
+	pub async fn get_primary(&self) -> Result<Option<DisplayUnitInfo>, ExtensionError> {
+		Ok(self.get_info(false).await?.into_iter().find(|display| display.is_primary))
+	}
+
+	pub fn on_display_changed(&self) -> Result<OnDisplayChanged, ExtensionError> {
+		Ok(OnDisplayChanged(get_api_namespace(&self.api, "onDisplayChanged")?))
+	}
+
+	pub fn on_display_changed_stream(&self) -> Result<EventStream<()>, ExtensionError> {
+		let on_display_changed = self.on_display_changed()?;
+		EventStream::new(|sender| on_display_changed.add_listener(move || drop(sender.unbounded_send(()))))
+	}
+}
+
+pub struct OnDisplayChanged(Object);
+
+impl OnDisplayChanged {
+	pub fn add_listener(&self, callback: impl FnMut() + 'static) -> Result<ListenerHandle<dyn FnMut()>, ExtensionError> {
+		attach_listener(&self.0, Closure::wrap(Box::new(callback) as Box<dyn FnMut()>))
+	}
+
+	pub fn once(&self) -> Result<Once<()>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move || drop(sender.unbounded_send(()))))
+	}
+}
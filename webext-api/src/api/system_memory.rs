@@ -0,0 +1,24 @@
+use crate::{
+	error::ExtensionError,
+	types::MemoryInfo,
+	utils::{call_async_fn_and_de, get_api_namespace},
+};
+use js_sys::Object;
+
+#[derive(Clone)]
+pub struct SystemMemory {
+	api: Object,
+}
+
+impl SystemMemory {
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "system")
+			.and_then(|system| get_api_namespace(&system, "memory"))
+			.map_err(|_| ExtensionError::ApiNotFound("system.memory".to_owned()))?;
+		Ok(Self { api })
+	}
+
+	pub async fn get_info(&self) -> Result<MemoryInfo, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getInfo", &[][..]).await
+	}
+}
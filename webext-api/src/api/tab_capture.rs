@@ -0,0 +1,79 @@
+use crate::{
+	error::ExtensionError,
+	types::{CaptureInfo, EventStream, GetMediaStreamOptions, ListenerHandle, Once, attach_listener},
+	utils::{call_async_fn_and_de, get_api_namespace},
+};
+use js_sys::{Object, Reflect};
+use serde_wasm_bindgen::to_value;
+use wasm_bindgen::{JsCast, JsValue, prelude::*};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{MediaStream, MediaStreamConstraints};
+
+#[derive(Clone)]
+pub struct TabCapture {
+	api: Object,
+}
+
+impl TabCapture {
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "tabCapture")?;
+		Ok(Self { api })
+	}
+
+	/// An opaque id that [`media_stream_from_id`] turns into the tab's `MediaStream`. Must follow a user gesture such as an action click.
+	pub async fn get_media_stream_id(&self, options: GetMediaStreamOptions) -> Result<String, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getMediaStreamId", &[to_value(&options)?][..]).await
+	}
+
+	pub async fn get_captured_tabs(&self) -> Result<Vec<CaptureInfo>, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getCapturedTabs", &[][..]).await
+	}
+
+	pub fn on_status_changed(&self) -> Result<OnCaptureStatusChanged, ExtensionError> {
+		Ok(OnCaptureStatusChanged(get_api_namespace(&self.api, "onStatusChanged")?))
+	}
+
+	pub fn on_status_changed_stream(&self) -> Result<EventStream<CaptureInfo>, ExtensionError> {
+		let on_status_changed = self.on_status_changed()?;
+		EventStream::new(|sender| on_status_changed.add_listener(move |info| drop(sender.unbounded_send(info))))
+	}
+}
+
+/// Opens the stream behind an id from [`TabCapture::get_media_stream_id`]. Needs a document, so from an MV3 service worker run it in an
+/// offscreen document.
+pub async fn media_stream_from_id(stream_id: &str, audio: bool, video: bool) -> Result<MediaStream, ExtensionError> {
+	let media_devices = web_sys::window().ok_or(ExtensionError::ApiNotFound("window".to_owned()))?.navigator().media_devices()?;
+	// Chrome only understands tab sources through the legacy `mandatory` constraint syntax
+	let source = || -> Result<JsValue, ExtensionError> {
+		let mandatory = Object::new();
+		Reflect::set(&mandatory, &"chromeMediaSource".into(), &"tab".into())?;
+		Reflect::set(&mandatory, &"chromeMediaSourceId".into(), &stream_id.into())?;
+		let constraint = Object::new();
+		Reflect::set(&constraint, &"mandatory".into(), &mandatory)?;
+		Ok(constraint.into())
+	};
+	let constraints = Object::new();
+	Reflect::set(&constraints, &"audio".into(), &if audio { source()? } else { JsValue::FALSE })?;
+	Reflect::set(&constraints, &"video".into(), &if video { source()? } else { JsValue::FALSE })?;
+	let promise = media_devices.get_user_media_with_constraints(constraints.unchecked_ref::<MediaStreamConstraints>())?;
+	Ok(JsFuture::from(promise).await?.unchecked_into())
+}
+
+pub struct OnCaptureStatusChanged(Object);
+
+impl OnCaptureStatusChanged {
+	pub fn add_listener(&self, mut callback: impl FnMut(CaptureInfo) + 'static) -> Result<ListenerHandle<dyn FnMut(JsValue)>, ExtensionError> {
+		attach_listener(
+			&self.0,
+			Closure::wrap(Box::new(move |val: JsValue| {
+				if let Ok(info) = serde_wasm_bindgen::from_value(val) {
+					callback(info);
+				}
+			}) as Box<dyn FnMut(JsValue)>),
+		)
+	}
+
+	pub fn once(&self) -> Result<Once<CaptureInfo>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |event| drop(sender.unbounded_send(event))))
+	}
+}
@@ -1,11 +1,13 @@
 use crate::{
-	error::ExtensionError,
-	types::{ListenerHandle, TabChangeInfo, TabInfo, attach_listener},
+	error::{ErrorKind, ExtensionError},
+	retry::{RetryPolicy, with_retries},
+	types::{EventStream, ListenerHandle, MessageOptions, Once, TabChangeInfo, TabCreateProperties, TabInfo, TabQuery, attach_listener},
 	utils::{call_async_fn, call_async_fn_and_de, get_api_namespace},
 };
 use js_sys::Object;
 use serde::{Serialize, de::DeserializeOwned};
 use serde_wasm_bindgen::to_value;
+use std::collections::BTreeMap;
 use wasm_bindgen::{JsCast, prelude::*};
 
 #[derive(Clone)]
@@ -14,9 +16,9 @@ pub struct Tabs {
 }
 
 impl Tabs {
-	pub(crate) fn new(api_root: &Object) -> Self {
-		let api = get_api_namespace(api_root, "tabs").expect("`tabs` API not available");
-		Self { api }
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "tabs")?;
+		Ok(Self { api })
 	}
 
 	pub async fn get_active(&self) -> Result<TabInfo, ExtensionError> {
@@ -28,13 +30,66 @@ impl Tabs {
 		if let Some(tab) = tabs_array.iter().next() { serde_wasm_bindgen::from_value(tab).map_err(Into::into) } else { Err(ExtensionError::TabNotFound) }
 	}
 
+	pub async fn query(&self, query: &TabQuery) -> Result<Vec<TabInfo>, ExtensionError> {
+		call_async_fn_and_de(&self.api, "query", &[to_value(query)?][..]).await
+	}
+
+	/// Opens a new tab; unlike most of `tabs`, this needs neither the `tabs` permission nor host access.
+	pub async fn create(&self, properties: &TabCreateProperties) -> Result<TabInfo, ExtensionError> {
+		call_async_fn_and_de(&self.api, "create", &[to_value(properties)?][..]).await
+	}
+
 	pub async fn send_message<M: Serialize, R: DeserializeOwned>(&self, tab_id: u32, message: &M) -> Result<R, ExtensionError> {
 		call_async_fn_and_de(&self.api, "sendMessage", &[tab_id.into(), to_value(message)?][..]).await
 	}
 
+	/// Sends to a single frame or document of the tab rather than every frame.
+	pub async fn send_message_with_options<M: Serialize, R: DeserializeOwned>(
+		&self,
+		tab_id: u32,
+		message: &M,
+		options: &MessageOptions,
+	) -> Result<R, ExtensionError> {
+		call_async_fn_and_de(&self.api, "sendMessage", &[tab_id.into(), to_value(message)?, to_value(options)?][..]).await
+	}
+
+	/// [`Self::send_message`], retried under `policy` while the tab's content script isn't injected yet.
+	pub async fn send_message_with_retries<M: Serialize, R: DeserializeOwned>(
+		&self,
+		tab_id: u32,
+		message: &M,
+		policy: &RetryPolicy,
+	) -> Result<R, ExtensionError> {
+		with_retries(policy, "tabs.sendMessage", || self.send_message(tab_id, message)).await
+	}
+
+	/// Sends `message` to every tab matching `query` concurrently. Tabs without a listening content script map to `Ok(None)` rather than
+	/// an error, so one `chrome://` page doesn't spoil the batch.
+	pub async fn broadcast<M: Serialize, R: DeserializeOwned>(
+		&self,
+		query: &TabQuery,
+		message: &M,
+	) -> Result<BTreeMap<u32, Result<Option<R>, ExtensionError>>, ExtensionError> {
+		let tab_ids = self.query(query).await?.into_iter().filter_map(|tab| tab.id);
+		let sends = tab_ids.map(|tab_id| async move {
+			let result = match self.send_message(tab_id, message).await {
+				Ok(response) => Ok(Some(response)),
+				Err(err) if err.kind() == ErrorKind::NoReceivingEnd => Ok(None),
+				Err(err) => Err(err),
+			};
+			(tab_id, result)
+		});
+		Ok(futures::future::join_all(sends).await.into_iter().collect())
+	}
+
 	pub fn on_updated(&self) -> Result<OnTabUpdated, ExtensionError> {
 		Ok(OnTabUpdated(get_api_namespace(&self.api, "onUpdated")?))
 	}
+
+	pub fn on_updated_stream(&self) -> Result<EventStream<(u32, TabChangeInfo, TabInfo)>, ExtensionError> {
+		let on_updated = self.on_updated()?;
+		EventStream::new(|sender| on_updated.add_listener(move |tab_id, change_info, tab| drop(sender.unbounded_send((tab_id, change_info, tab)))))
+	}
 }
 
 pub struct OnTabUpdated(Object);
@@ -53,4 +108,8 @@ impl OnTabUpdated {
 			}) as Box<dyn FnMut(JsValue, JsValue, JsValue)>),
 		)
 	}
+
+	pub fn once(&self) -> Result<Once<(u32, TabChangeInfo, TabInfo)>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move |tab_id, change_info, tab| drop(sender.unbounded_send((tab_id, change_info, tab)))))
+	}
 }
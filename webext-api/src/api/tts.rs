@@ -0,0 +1,67 @@
+use crate::{
+	error::ExtensionError,
+	types::{TtsEvent, TtsOptions, TtsVoice},
+	utils::{call_async_fn, call_async_fn_and_de, get_api_namespace},
+};
+use js_sys::{Function, Object, Reflect};
+use serde_wasm_bindgen::to_value;
+use wasm_bindgen::{JsCast, JsValue, prelude::*};
+
+#[derive(Clone)]
+pub struct Tts {
+	api: Object,
+}
+
+impl Tts {
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "tts")?;
+		Ok(Self { api })
+	}
+
+	/// Resolves once the utterance is queued, not when it finishes; use [`Tts::speak_with_events`] to follow its progress.
+	pub async fn speak(&self, utterance: &str, options: TtsOptions) -> Result<(), ExtensionError> {
+		call_async_fn(&self.api, "speak", &[utterance.into(), to_value(&options)?][..]).await?;
+		Ok(())
+	}
+
+	/// Like [`Tts::speak`], calling `on_event` for each event of this utterance until one [`TtsEvent::is_final`].
+	pub async fn speak_with_events(&self, utterance: &str, options: TtsOptions, mut on_event: impl FnMut(TtsEvent) + 'static) -> Result<(), ExtensionError> {
+		let options = to_value(&options)?;
+		// owned by the options object from here on, so it's freed together with the utterance
+		let callback = Closure::wrap(Box::new(move |event: JsValue| {
+			if let Ok(event) = serde_wasm_bindgen::from_value(event) {
+				on_event(event);
+			}
+		}) as Box<dyn FnMut(JsValue)>)
+		.into_js_value();
+		Reflect::set(&options, &"onEvent".into(), &callback)?;
+		call_async_fn(&self.api, "speak", &[utterance.into(), options][..]).await?;
+		Ok(())
+	}
+
+	pub fn stop(&self) -> Result<(), ExtensionError> {
+		self.call_sync("stop")
+	}
+
+	pub fn pause(&self) -> Result<(), ExtensionError> {
+		self.call_sync("pause")
+	}
+
+	pub fn resume(&self) -> Result<(), ExtensionError> {
+		self.call_sync("resume")
+	}
+
+	pub async fn is_speaking(&self) -> Result<bool, ExtensionError> {
+		call_async_fn_and_de(&self.api, "isSpeaking", &[][..]).await
+	}
+
+	pub async fn get_voices(&self) -> Result<Vec<TtsVoice>, ExtensionError> {
+		call_async_fn_and_de(&self.api, "getVoices", &[][..]).await
+	}
+
+	fn call_sync(&self, method: &str) -> Result<(), ExtensionError> {
+		let func: Function = Reflect::get(&self.api, &method.into())?.dyn_into()?;
+		func.call0(&self.api)?;
+		Ok(())
+	}
+}
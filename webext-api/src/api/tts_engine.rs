@@ -0,0 +1,88 @@
+use crate::{
+	error::ExtensionError,
+	types::{ListenerHandle, Once, TtsEvent, TtsOptions, TtsVoice, attach_listener},
+	utils::get_api_namespace,
+};
+use js_sys::{Function, Object, Reflect};
+use serde_wasm_bindgen::to_value;
+use wasm_bindgen::{JsCast, JsValue, prelude::*};
+
+/// For extensions that provide a speech engine (declared under `tts_engine` in the manifest) rather than use one.
+#[derive(Clone)]
+pub struct TtsEngine {
+	api: Object,
+}
+
+impl TtsEngine {
+	pub(crate) fn new(api_root: &Object) -> Result<Self, ExtensionError> {
+		let api = get_api_namespace(api_root, "ttsEngine")?;
+		Ok(Self { api })
+	}
+
+	/// Replaces the voices declared in the manifest.
+	pub fn update_voices(&self, voices: &[TtsVoice]) -> Result<(), ExtensionError> {
+		let func: Function = Reflect::get(&self.api, &"updateVoices".into())?.dyn_into()?;
+		func.call1(&self.api, &to_value(voices)?)?;
+		Ok(())
+	}
+
+	pub fn on_speak(&self) -> Result<OnSpeak, ExtensionError> {
+		Ok(OnSpeak(get_api_namespace(&self.api, "onSpeak")?))
+	}
+
+	pub fn on_stop(&self) -> Result<OnTtsEngineSignal, ExtensionError> {
+		Ok(OnTtsEngineSignal(get_api_namespace(&self.api, "onStop")?))
+	}
+
+	pub fn on_pause(&self) -> Result<OnTtsEngineSignal, ExtensionError> {
+		Ok(OnTtsEngineSignal(get_api_namespace(&self.api, "onPause")?))
+	}
+
+	pub fn on_resume(&self) -> Result<OnTtsEngineSignal, ExtensionError> {
+		Ok(OnTtsEngineSignal(get_api_namespace(&self.api, "onResume")?))
+	}
+}
+
+/// Reports progress of the utterance an `onSpeak` listener is synthesizing.
+#[derive(Clone)]
+pub struct TtsEventSender(Function);
+
+impl TtsEventSender {
+	pub fn send(&self, event: &TtsEvent) -> Result<(), ExtensionError> {
+		self.0.call1(&JsValue::UNDEFINED, &to_value(event)?)?;
+		Ok(())
+	}
+}
+
+pub struct OnSpeak(Object);
+
+impl OnSpeak {
+	pub fn add_listener(
+		&self,
+		mut callback: impl FnMut(String, TtsOptions, TtsEventSender) + 'static,
+	) -> Result<ListenerHandle<dyn FnMut(JsValue, JsValue, JsValue)>, ExtensionError> {
+		attach_listener(
+			&self.0,
+			Closure::wrap(Box::new(move |utterance: JsValue, options: JsValue, send_tts_event: JsValue| {
+				if let (Some(utterance), Ok(options), Ok(send_tts_event)) =
+					(utterance.as_string(), serde_wasm_bindgen::from_value(options), send_tts_event.dyn_into::<Function>())
+				{
+					callback(utterance, options, TtsEventSender(send_tts_event));
+				}
+			}) as Box<dyn FnMut(JsValue, JsValue, JsValue)>),
+		)
+	}
+}
+
+/// `onStop`, `onPause` and `onResume`, which carry no arguments.
+pub struct OnTtsEngineSignal(Object);
+
+impl OnTtsEngineSignal {
+	pub fn add_listener(&self, callback: impl FnMut() + 'static) -> Result<ListenerHandle<dyn FnMut()>, ExtensionError> {
+		attach_listener(&self.0, Closure::wrap(Box::new(callback) as Box<dyn FnMut()>))
+	}
+
+	pub fn once(&self) -> Result<Once<()>, ExtensionError> {
+		Once::new(|sender| self.add_listener(move || drop(sender.unbounded_send(()))))
+	}
+}
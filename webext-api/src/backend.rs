@@ -0,0 +1,91 @@
+use crate::{
+	Browser,
+	api::{Runtime, StorageArea, Tabs},
+	error::ExtensionError,
+	types::TabInfo,
+};
+use async_trait::async_trait;
+use serde::{Serialize, de::DeserializeOwned};
+
+#[async_trait(?Send)]
+pub trait StorageBackend {
+	async fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, ExtensionError>;
+	async fn set<T: Serialize>(&self, key: &str, value: &T) -> Result<(), ExtensionError>;
+}
+
+#[async_trait(?Send)]
+pub trait TabsBackend {
+	async fn get_active(&self) -> Result<TabInfo, ExtensionError>;
+	async fn send_message<M: Serialize, R: DeserializeOwned>(&self, tab_id: u32, message: &M) -> Result<R, ExtensionError>;
+}
+
+#[async_trait(?Send)]
+pub trait RuntimeBackend {
+	async fn send_message<M: Serialize, R: DeserializeOwned>(&self, message: &M) -> Result<R, ExtensionError>;
+}
+
+/// The parts of [`Browser`] that application logic usually touches; write logic against this so it can also run on the host against
+/// `mock::MockBrowser`.
+pub trait BrowserBackend {
+	type Storage: StorageBackend;
+	type Tabs: TabsBackend;
+	type Runtime: RuntimeBackend;
+
+	fn local_storage(&self) -> Self::Storage;
+	fn sync_storage(&self) -> Self::Storage;
+	fn tabs(&self) -> Self::Tabs;
+	fn runtime(&self) -> Self::Runtime;
+}
+
+#[async_trait(?Send)]
+impl StorageBackend for StorageArea {
+	async fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, ExtensionError> {
+		StorageArea::get(self, key).await
+	}
+
+	async fn set<T: Serialize>(&self, key: &str, value: &T) -> Result<(), ExtensionError> {
+		StorageArea::set(self, key, value).await
+	}
+}
+
+#[async_trait(?Send)]
+impl TabsBackend for Tabs {
+	async fn get_active(&self) -> Result<TabInfo, ExtensionError> {
+		Tabs::get_active(self).await
+	}
+
+	async fn send_message<M: Serialize, R: DeserializeOwned>(&self, tab_id: u32, message: &M) -> Result<R, ExtensionError> {
+		Tabs::send_message(self, tab_id, message).await
+	}
+}
+
+#[async_trait(?Send)]
+impl RuntimeBackend for Runtime {
+	async fn send_message<M: Serialize, R: DeserializeOwned>(&self, message: &M) -> Result<R, ExtensionError> {
+		Runtime::send_message(self, message).await
+	}
+}
+
+// the manifest check is aimed at the extension's own call sites
+#[allow(deprecated)]
+impl BrowserBackend for Browser {
+	type Storage = StorageArea;
+	type Tabs = Tabs;
+	type Runtime = Runtime;
+
+	fn local_storage(&self) -> StorageArea {
+		self.storage().local()
+	}
+
+	fn sync_storage(&self) -> StorageArea {
+		self.storage().sync()
+	}
+
+	fn tabs(&self) -> Tabs {
+		Browser::tabs(self)
+	}
+
+	fn runtime(&self) -> Runtime {
+		Browser::runtime(self)
+	}
+}
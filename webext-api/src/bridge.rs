@@ -0,0 +1,84 @@
+use crate::{
+	error::ExtensionError,
+	types::{ListenerHandle, attach_event_listener},
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_wasm_bindgen::{from_value, to_value};
+use std::marker::PhantomData;
+use wasm_bindgen::{JsCast, prelude::*};
+use web_sys::{MessageEvent, Window};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BridgeSide {
+	/// The isolated world of a content script.
+	Content,
+	/// The page's MAIN world, e.g. a script injected with `world: "MAIN"`.
+	Page,
+}
+
+impl BridgeSide {
+	fn other(self) -> Self {
+		match self {
+			Self::Content => Self::Page,
+			Self::Page => Self::Content,
+		}
+	}
+}
+
+// the wire format, for a MAIN world script written in JS: `{ webextBridge: channel, from: "page", payload }`
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BridgeEnvelope<T> {
+	webext_bridge: String,
+	from: BridgeSide,
+	payload: T,
+}
+
+/// Typed `window.postMessage` channel between a content script and the page's MAIN world. Both worlds share the window, so messages are
+/// tagged with the channel and the sending side, and only same-window, same-origin messages from the other side are delivered.
+///
+/// Any script on the page can post on the channel too; treat what arrives on the content side as untrusted input.
+pub struct PageBridge<T> {
+	window: Window,
+	channel: String,
+	side: BridgeSide,
+	origin: String,
+	_phantom: PhantomData<T>,
+}
+
+impl<T: Serialize + DeserializeOwned + 'static> PageBridge<T> {
+	pub fn new(channel: &str, side: BridgeSide) -> Result<Self, ExtensionError> {
+		let window = web_sys::window().ok_or(ExtensionError::ApiNotFound("window".to_owned()))?;
+		let origin = window.location().origin()?;
+		Ok(Self { window, channel: channel.to_owned(), side, origin, _phantom: PhantomData })
+	}
+
+	pub fn post(&self, message: &T) -> Result<(), ExtensionError> {
+		let envelope = BridgeEnvelope { webext_bridge: self.channel.clone(), from: self.side, payload: message };
+		// opaque origins (sandboxed frames, `data:` pages) serialize as "null", which `postMessage` can't target
+		let target_origin = if self.origin == "null" { "*" } else { &self.origin };
+		self.window.post_message(&to_value(&envelope)?, target_origin)?;
+		Ok(())
+	}
+
+	pub fn on_message(&self, mut callback: impl FnMut(T) + 'static) -> Result<ListenerHandle<dyn FnMut(MessageEvent)>, ExtensionError> {
+		let (window, channel, origin, from) = (self.window.clone(), self.channel.clone(), self.origin.clone(), self.side.other());
+		attach_event_listener(
+			&self.window,
+			"message",
+			Closure::wrap(Box::new(move |event: MessageEvent| {
+				let same_window = event.source().is_some_and(|source| source.unchecked_ref::<JsValue>() == window.unchecked_ref::<JsValue>());
+				if !same_window || event.origin() != origin {
+					return;
+				}
+				if let Ok(envelope) = from_value::<BridgeEnvelope<T>>(event.data())
+					&& envelope.webext_bridge == channel
+					&& envelope.from == from
+				{
+					callback(envelope.payload);
+				}
+			}) as Box<dyn FnMut(MessageEvent)>),
+		)
+	}
+}
@@ -21,11 +21,21 @@ pub enum ExtensionError {
 	#[error("The browser API returned an error: {0}")]
 	ApiError(String),
 
+	#[error("The call to `{0}` timed out.")]
+	Timeout(String),
+
+	#[error("The remote handler failed: {0}")]
+	RemoteError(String),
+
 	#[error("A JavaScript error occurred: {message}")]
 	JsError { message: String, js_value: JsValue },
 
 	#[error("An unexpected JavaScript value was thrown: {0:?}")]
 	JsValue(JsValue),
+
+	#[cfg(feature = "gcm")]
+	#[error(transparent)]
+	GcmPayload(#[from] GcmPayloadError),
 }
 
 impl From<JsValue> for ExtensionError {
@@ -44,3 +54,94 @@ impl From<JsValue> for ExtensionError {
 		}
 	}
 }
+
+/// Well-known failure cases, parsed out of the browser's error messages so callers don't have to string-match them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+	/// The listener didn't call `sendResponse` before its port closed.
+	MessagePortClosed,
+	/// Nothing is listening, e.g. the content script isn't injected yet.
+	NoReceivingEnd,
+	QuotaExceeded,
+	PermissionMissing,
+	/// The extension was reloaded or updated underneath this context.
+	ContextInvalidated,
+	TabNotFound,
+	Timeout,
+	Other,
+}
+
+impl ExtensionError {
+	pub fn kind(&self) -> ErrorKind {
+		match self {
+			Self::TabNotFound => ErrorKind::TabNotFound,
+			Self::Timeout(_) => ErrorKind::Timeout,
+			Self::ApiError(message) | Self::JsError { message, .. } => ErrorKind::from_message(message),
+			_ => ErrorKind::Other,
+		}
+	}
+
+	/// Whether the same call may succeed if made again later, e.g. once a content script has loaded. Only errors raised before
+	/// anything received the call count, so retrying never delivers a message twice.
+	pub fn is_retryable(&self) -> bool {
+		self.kind().is_retryable()
+	}
+}
+
+impl ErrorKind {
+	fn from_message(message: &str) -> Self {
+		let message = message.to_lowercase();
+		// Chrome says the port or, since a listener returned `true`, the channel closed; Firefox that the response went out of scope
+		if message.contains("message port closed") || message.contains("message channel closed") || message.contains("went out of scope") {
+			Self::MessagePortClosed
+		} else if message.contains("receiving end does not exist") {
+			Self::NoReceivingEnd
+		} else if message.contains("quota") || message.contains("max_write_operations") {
+			Self::QuotaExceeded
+		} else if message.contains("context invalidated") || message.contains("can't find extension context") || message.contains("dead object") {
+			Self::ContextInvalidated
+		} else if message.contains("permission") {
+			Self::PermissionMissing
+		} else if message.contains("no tab with id") || message.contains("invalid tab id") {
+			Self::TabNotFound
+		} else {
+			Self::Other
+		}
+	}
+
+	/// Only [`Self::NoReceivingEnd`]: after a closed port or a timeout the receiver may already have handled the call.
+	pub fn is_retryable(self) -> bool {
+		matches!(self, Self::NoReceivingEnd)
+	}
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MatchPatternError {
+	#[error("The pattern has no `://` separating the scheme from the host.")]
+	MissingSchemeSeparator,
+
+	#[error("`{0}` is not a valid scheme.")]
+	InvalidScheme(String),
+
+	#[error("`{0}` is not a valid host; `*` may only appear alone or as a leading `*.`.")]
+	InvalidHost(String),
+
+	#[error("`{0}` is not a valid port.")]
+	InvalidPort(String),
+
+	#[error("The pattern has no path; use `/*` to match every path.")]
+	MissingPath,
+}
+
+#[cfg(feature = "gcm")]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum GcmPayloadError {
+	#[error("The message data is {size} bytes, over the {limit} byte limit.")]
+	TooLarge { size: usize, limit: usize },
+
+	#[error("`{0}` uses a reserved key prefix (`goog.`, `google` or `collapse_key`).")]
+	ReservedKey(String),
+
+	#[error("A time to live of {0} seconds is longer than the four week maximum.")]
+	TimeToLiveTooLong(u32),
+}
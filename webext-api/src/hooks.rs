@@ -0,0 +1,125 @@
+#[cfg(feature = "commands")]
+use crate::{api::Commands, types::TabInfo};
+use crate::{api::Runtime, types::MessageSender};
+#[cfg(feature = "storage")]
+use crate::{api::StorageArea, utils::sleep};
+use dioxus::prelude::*;
+#[cfg(feature = "storage")]
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::rc::Rc;
+#[cfg(feature = "storage")]
+use std::{
+	cell::{Cell, RefCell},
+	time::Duration,
+};
+
+/// Binds a signal to `key` in `area`: it starts at `T::default()` and is then kept in sync as by [`sync_signal_to_storage`].
+#[cfg(feature = "storage")]
+pub fn use_ext_storage<T>(area: StorageArea, key: &str) -> Signal<T>
+where
+	T: Serialize + DeserializeOwned + Default + Clone + PartialEq + 'static,
+{
+	let value = use_signal(T::default);
+	sync_signal_to_storage(value, area, key);
+	value
+}
+
+/// How long a signal has to stay unchanged before [`sync_signal_to_storage`] writes it, so typing into a bound input doesn't hit the
+/// storage write quota.
+#[cfg(feature = "storage")]
+pub const STORAGE_SYNC_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Keeps an existing signal and `key` in `area` in step: the signal is filled from storage once loaded, local changes are written back
+/// after [`STORAGE_SYNC_DEBOUNCE`], and `storage.onChanged` updates from other pages or the background flow back into it. Whichever change
+/// happens last wins; a remote update cancels a local write still waiting out its debounce. Call it unconditionally, like a hook.
+#[cfg(feature = "storage")]
+pub fn sync_signal_to_storage<T>(mut signal: Signal<T>, area: StorageArea, key: &str)
+where
+	T: Serialize + DeserializeOwned + Clone + PartialEq + 'static,
+{
+	let key = use_hook(|| key.to_string());
+	// the last value known to match storage; `None` until the initial load finishes so the initial value never overwrites a stored one
+	let synced = use_hook(|| Rc::new(RefCell::new(None::<T>)));
+	// bumped by every local edit and remote update, so a pending write only lands if nothing happened since it was scheduled
+	let generation = use_hook(|| Rc::new(Cell::new(0_u64)));
+
+	use_hook({
+		let (area, key, synced, generation) = (area.clone(), key.clone(), synced.clone(), generation.clone());
+		move || {
+			spawn({
+				let (area, key, synced) = (area.clone(), key.clone(), synced.clone());
+				async move {
+					let stored = area.get::<T>(&key).await.ok().flatten();
+					*synced.borrow_mut() = Some(stored.clone().unwrap_or_else(|| signal.peek().clone()));
+					if let Some(stored) = stored {
+						signal.set(stored);
+					}
+				}
+			});
+			let handle = area.on_changed().and_then(|on_changed| {
+				on_changed.add_listener(move |changes| {
+					if let Some(new_value) = changes.get(&key).and_then(|change| change.new_value_as::<T>()) {
+						generation.set(generation.get() + 1);
+						*synced.borrow_mut() = Some(new_value.clone());
+						if *signal.peek() != new_value {
+							signal.set(new_value);
+						}
+					}
+				})
+			});
+			handle.ok().map(Rc::new)
+		}
+	});
+
+	use_effect(move || {
+		let current = signal();
+		if synced.borrow().as_ref().is_none_or(|last| *last == current) {
+			return;
+		}
+		let scheduled = generation.get() + 1;
+		generation.set(scheduled);
+		let (area, key, synced, generation) = (area.clone(), key.clone(), synced.clone(), generation.clone());
+		spawn(async move {
+			sleep(STORAGE_SYNC_DEBOUNCE).await;
+			if generation.get() != scheduled {
+				return;
+			}
+			*synced.borrow_mut() = Some(current.clone());
+			let _ = area.set(&key, &current).await;
+		});
+	});
+}
+
+/// Registers an `onMessage` listener for the lifetime of the component; it is removed again on unmount. Forward into a coroutine with
+/// `use_ext_message_handler(runtime, move |message, _| coroutine.send(message))`.
+pub fn use_ext_message_handler<T>(runtime: Runtime, handler: impl FnMut(T, MessageSender) + 'static)
+where
+	T: DeserializeOwned + 'static,
+{
+	use_hook(move || runtime.on_message::<T>().and_then(|on_message| on_message.add_listener(handler)).ok().map(Rc::new));
+}
+
+/// Holds the most recent message of type `T` received while the component is mounted.
+pub fn use_ext_messages<T>(runtime: Runtime) -> Signal<Option<T>>
+where
+	T: DeserializeOwned + 'static,
+{
+	let mut last_message = use_signal(|| None);
+	use_ext_message_handler(runtime, move |message: T, _| last_message.set(Some(message)));
+	last_message
+}
+
+/// Runs `handler` for every manifest-declared shortcut fired while the component is mounted.
+#[cfg(feature = "commands")]
+pub fn use_command_handler(commands: Commands, handler: impl FnMut(String, Option<TabInfo>) + 'static) {
+	use_hook(move || commands.on_command().and_then(|on_command| on_command.add_listener(handler)).ok().map(Rc::new));
+}
+
+/// Holds the name of the most recent command fired while the component is mounted.
+#[cfg(feature = "commands")]
+pub fn use_command(commands: Commands) -> Signal<Option<String>> {
+	let mut last_command = use_signal(|| None);
+	use_command_handler(commands, move |command, _| last_command.set(Some(command)));
+	last_command
+}
@@ -0,0 +1,81 @@
+use js_sys::{Function, Reflect};
+use std::{
+	cell::{Cell, RefCell},
+	future::Future,
+	time::Duration,
+};
+use wasm_bindgen::{JsCast, prelude::*};
+use wasm_bindgen_futures::spawn_local;
+
+/// Chrome stops an MV3 service worker after 30 seconds without extension API activity; pinging more often than that keeps it running.
+pub const PING_INTERVAL: Duration = Duration::from_secs(20);
+
+thread_local! {
+	static HOLDERS: Cell<usize> = const { Cell::new(0) };
+	static TIMER: RefCell<Option<(JsValue, Closure<dyn FnMut()>)>> = const { RefCell::new(None) };
+}
+
+/// Keeps the service worker alive while any guard is held. Guards are counted, so overlapping pieces of work can each hold their own.
+#[must_use = "the service worker is only kept alive while the guard is held"]
+pub struct KeepAlive {
+	_private: (),
+}
+
+impl Drop for KeepAlive {
+	fn drop(&mut self) {
+		let holders = HOLDERS.with(|holders| {
+			holders.set(holders.get().saturating_sub(1));
+			holders.get()
+		});
+		if holders == 0 {
+			stop();
+		}
+	}
+}
+
+/// Starts pinging `runtime.getPlatformInfo` every [`PING_INTERVAL`] until the last guard is dropped.
+///
+/// ```ignore
+/// let _ka = keepalive::hold();
+/// let summary = fetch_summary(&text).await?;
+/// ```
+pub fn hold() -> KeepAlive {
+	let holders = HOLDERS.with(|holders| {
+		holders.set(holders.get() + 1);
+		holders.get()
+	});
+	if holders == 1 {
+		start();
+	}
+	KeepAlive { _private: () }
+}
+
+pub async fn while_held<F: Future>(future: F) -> F::Output {
+	let _keepalive = hold();
+	future.await
+}
+
+fn start() {
+	let ping = Closure::wrap(Box::new(|| {
+		spawn_local(async {
+			if let Ok(browser) = crate::Browser::global() {
+				let _ = browser.runtime().get_platform_info().await;
+			}
+		});
+	}) as Box<dyn FnMut()>);
+	let Ok(set_interval) = Reflect::get(&js_sys::global(), &"setInterval".into()).and_then(|v| v.dyn_into::<Function>()) else {
+		return;
+	};
+	if let Ok(timer_id) = set_interval.call2(&JsValue::UNDEFINED, ping.as_ref(), &JsValue::from_f64(PING_INTERVAL.as_millis() as f64)) {
+		TIMER.with(|timer| *timer.borrow_mut() = Some((timer_id, ping)));
+	}
+}
+
+fn stop() {
+	let Some((timer_id, _ping)) = TIMER.with(|timer| timer.borrow_mut().take()) else {
+		return;
+	};
+	if let Ok(clear_interval) = Reflect::get(&js_sys::global(), &"clearInterval".into()).and_then(|v| v.dyn_into::<Function>()) {
+		let _ = clear_interval.call1(&JsValue::UNDEFINED, &timer_id);
+	}
+}
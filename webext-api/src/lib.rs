@@ -1,72 +1,632 @@
 pub mod api;
+#[cfg(all(feature = "storage", feature = "tabs"))]
+pub mod backend;
+pub mod bridge;
 pub mod error;
+#[cfg(feature = "dioxus")]
+pub mod hooks;
+pub mod keepalive;
+#[cfg(feature = "logging")]
+pub mod logging;
+pub mod match_pattern;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod permission;
+pub mod retry;
+pub mod router;
+pub mod rpc;
 pub mod types;
 mod utils;
 
 use api::*;
 use error::ExtensionError;
 use js_sys::Object;
+pub use permission::Permission;
+use std::{cell::OnceCell, rc::Rc};
 pub use types::*;
+use utils::expect_namespace;
 use wasm_bindgen::prelude::*;
 
+/// The namespace accessors panic when their API is missing, e.g. in a content script or without its permission; each of them
+/// has a `try_` variant, such as [`Self::try_tabs`], that returns [`ExtensionError::ApiNotFound`] instead. A namespace is looked
+/// up on first use and then kept, for this `Browser` and its clones.
 #[derive(Clone)]
 pub struct Browser {
 	api_root: Object,
 	browser_type: BrowserType,
+	namespaces: Rc<Namespaces>,
+}
+
+thread_local! {
+	static GLOBAL: OnceCell<Browser> = const { OnceCell::new() };
+}
+
+// every namespace that was found, shared by the clones of a `Browser`; missing ones are looked up again on the next call, as an
+// optional permission may have been granted since
+#[derive(Default)]
+struct Namespaces {
+	#[cfg(feature = "action")]
+	action: OnceCell<Action>,
+	#[cfg(feature = "alarms")]
+	alarms: OnceCell<Alarms>,
+	#[cfg(feature = "firefox")]
+	browser_settings: OnceCell<BrowserSettings>,
+	#[cfg(feature = "commands")]
+	commands: OnceCell<Commands>,
+	#[cfg(feature = "context_menus")]
+	context_menus: OnceCell<ContextMenus>,
+	#[cfg(feature = "cookies")]
+	cookies: OnceCell<Cookies>,
+	#[cfg(feature = "declarative_content")]
+	declarative_content: OnceCell<DeclarativeContent>,
+	#[cfg(feature = "downloads")]
+	downloads: OnceCell<Downloads>,
+	#[cfg(feature = "extension")]
+	extension: OnceCell<Extension>,
+	#[cfg(feature = "font_settings")]
+	font_settings: OnceCell<FontSettings>,
+	#[cfg(feature = "gcm")]
+	gcm: OnceCell<Gcm>,
+	#[cfg(feature = "gcm")]
+	instance_id: OnceCell<InstanceId>,
+	#[cfg(feature = "i18n")]
+	i18n: OnceCell<I18n>,
+	#[cfg(feature = "printing")]
+	printing: OnceCell<Printing>,
+	#[cfg(feature = "printing_metrics")]
+	printing_metrics: OnceCell<PrintingMetrics>,
+	#[cfg(feature = "notifications")]
+	notifications: OnceCell<Notifications>,
+	#[cfg(feature = "permissions")]
+	permissions: OnceCell<Permissions>,
+	#[cfg(feature = "reading_list")]
+	reading_list: OnceCell<ReadingList>,
+	runtime: OnceCell<Runtime>,
+	#[cfg(feature = "scripting")]
+	scripting: OnceCell<Scripting>,
+	#[cfg(feature = "storage")]
+	storage: OnceCell<Storage>,
+	#[cfg(feature = "system_cpu")]
+	system_cpu: OnceCell<SystemCpu>,
+	#[cfg(feature = "system_display")]
+	system_display: OnceCell<SystemDisplay>,
+	#[cfg(feature = "system_memory")]
+	system_memory: OnceCell<SystemMemory>,
+	#[cfg(feature = "tab_capture")]
+	tab_capture: OnceCell<TabCapture>,
+	#[cfg(feature = "tabs")]
+	tabs: OnceCell<Tabs>,
+	#[cfg(feature = "tts")]
+	tts: OnceCell<Tts>,
+	#[cfg(feature = "tts_engine")]
+	tts_engine: OnceCell<TtsEngine>,
+}
+
+fn cached<T: Clone>(cell: &OnceCell<T>, init: impl FnOnce() -> Result<T, ExtensionError>) -> Result<T, ExtensionError> {
+	if let Some(namespace) = cell.get() {
+		return Ok(namespace.clone());
+	}
+	let namespace = init()?;
+	Ok(cell.get_or_init(|| namespace).clone())
 }
 
 impl Browser {
+	/// The `Browser` of this context, created by the first call and shared with every later one, along with the namespaces it has
+	/// looked up. Prefer it over [`init`] on hot paths such as message handlers.
+	pub fn global() -> Result<Self, ExtensionError> {
+		GLOBAL.with(|global| cached(global, init))
+	}
+
 	pub fn browser_type(&self) -> BrowserType {
 		self.browser_type.clone()
 	}
 
+	pub fn supports_side_panel(&self) -> bool {
+		has_namespace(&self.api_root, "sidePanel") || has_namespace(&self.api_root, "sidebarAction")
+	}
+
+	pub fn supports_dnr(&self) -> bool {
+		has_namespace(&self.api_root, "declarativeNetRequest")
+	}
+
+	/// Whether [`Self::notifications`] can be used; `false` while `notifications` is an optional permission that isn't granted yet.
+	pub fn supports_notifications(&self) -> bool {
+		has_namespace(&self.api_root, "notifications")
+	}
+
+	/// A URL for `page_url`'s site icon at `size` pixels. Chromium serves it from the extension's `_favicon/` endpoint, which needs the
+	/// `favicon` permission; elsewhere it falls back to the site's `/favicon.ico` (prefer `favIconUrl` from a tab where one is at hand).
+	pub fn favicon_url(&self, page_url: &str, size: u32) -> Result<String, ExtensionError> {
+		if self.browser_type.is_chromium() {
+			let base = self.try_runtime()?.get_url("/_favicon/")?;
+			return Ok(format!("{base}?pageUrl={}&size={size}", js_sys::encode_uri_component(page_url)));
+		}
+		Ok(format!("{}/favicon.ico", web_sys::Url::new(page_url)?.origin()))
+	}
+
+	#[cfg(feature = "action")]
 	pub fn action(&self) -> Action {
-		Action::new(&self.api_root, self.browser_type.clone())
+		expect_namespace(self.try_action())
+	}
+
+	#[cfg(feature = "action")]
+	pub fn try_action(&self) -> Result<Action, ExtensionError> {
+		cached(&self.namespaces.action, || Action::new(&self.api_root, self.browser_type.clone()))
 	}
 
+	#[cfg(feature = "alarms")]
+	#[cfg_attr(all(webext_manifest_checked, not(webext_permission = "alarms")), deprecated(note = "the `alarms` permission is not declared in manifest.json"))]
+	// each accessor calls its `try_` twin, which carries the same deprecation; only calls from outside the crate should warn
+	#[allow(deprecated)]
 	pub fn alarms(&self) -> Alarms {
-		Alarms::new(&self.api_root)
+		expect_namespace(self.try_alarms())
+	}
+
+	#[cfg(feature = "alarms")]
+	#[cfg_attr(all(webext_manifest_checked, not(webext_permission = "alarms")), deprecated(note = "the `alarms` permission is not declared in manifest.json"))]
+	pub fn try_alarms(&self) -> Result<Alarms, ExtensionError> {
+		cached(&self.namespaces.alarms, || Alarms::new(&self.api_root))
+	}
+
+	#[cfg(feature = "firefox")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "browserSettings")),
+		deprecated(note = "the `browserSettings` permission is not declared in manifest.json")
+	)]
+	#[allow(deprecated)]
+	pub fn browser_settings(&self) -> BrowserSettings {
+		expect_namespace(self.try_browser_settings())
 	}
 
+	#[cfg(feature = "firefox")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "browserSettings")),
+		deprecated(note = "the `browserSettings` permission is not declared in manifest.json")
+	)]
+	pub fn try_browser_settings(&self) -> Result<BrowserSettings, ExtensionError> {
+		cached(&self.namespaces.browser_settings, || BrowserSettings::new(&self.api_root))
+	}
+
+	#[cfg(feature = "clipboard")]
+	pub fn clipboard(&self) -> Clipboard {
+		Clipboard::new(&self.api_root)
+	}
+
+	#[cfg(feature = "commands")]
 	pub fn commands(&self) -> Commands {
-		Commands::new(&self.api_root)
+		expect_namespace(self.try_commands())
 	}
 
+	#[cfg(feature = "commands")]
+	pub fn try_commands(&self) -> Result<Commands, ExtensionError> {
+		cached(&self.namespaces.commands, || Commands::new(&self.api_root))
+	}
+
+	#[cfg(feature = "context_menus")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(any(webext_permission = "contextMenus", webext_permission = "menus"))),
+		deprecated(note = "the `contextMenus` permission is not declared in manifest.json")
+	)]
+	#[allow(deprecated)]
 	pub fn context_menus(&self) -> ContextMenus {
-		ContextMenus::new(&self.api_root)
+		expect_namespace(self.try_context_menus())
+	}
+
+	#[cfg(feature = "context_menus")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(any(webext_permission = "contextMenus", webext_permission = "menus"))),
+		deprecated(note = "the `contextMenus` permission is not declared in manifest.json")
+	)]
+	pub fn try_context_menus(&self) -> Result<ContextMenus, ExtensionError> {
+		cached(&self.namespaces.context_menus, || ContextMenus::new(&self.api_root))
+	}
+
+	#[cfg(feature = "cookies")]
+	#[cfg_attr(all(webext_manifest_checked, not(webext_permission = "cookies")), deprecated(note = "the `cookies` permission is not declared in manifest.json"))]
+	#[allow(deprecated)]
+	pub fn cookies(&self) -> Cookies {
+		expect_namespace(self.try_cookies())
+	}
+
+	#[cfg(feature = "cookies")]
+	#[cfg_attr(all(webext_manifest_checked, not(webext_permission = "cookies")), deprecated(note = "the `cookies` permission is not declared in manifest.json"))]
+	pub fn try_cookies(&self) -> Result<Cookies, ExtensionError> {
+		cached(&self.namespaces.cookies, || Cookies::new(&self.api_root))
+	}
+
+	#[cfg(feature = "declarative_content")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "declarativeContent")),
+		deprecated(note = "the `declarativeContent` permission is not declared in manifest.json")
+	)]
+	#[allow(deprecated)]
+	pub fn declarative_content(&self) -> DeclarativeContent {
+		expect_namespace(self.try_declarative_content())
+	}
+
+	#[cfg(feature = "declarative_content")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "declarativeContent")),
+		deprecated(note = "the `declarativeContent` permission is not declared in manifest.json")
+	)]
+	pub fn try_declarative_content(&self) -> Result<DeclarativeContent, ExtensionError> {
+		cached(&self.namespaces.declarative_content, || DeclarativeContent::new(&self.api_root))
+	}
+
+	#[cfg(feature = "dom")]
+	pub fn dom(&self) -> Dom {
+		Dom::new(&self.api_root, self.browser_type.clone())
+	}
+
+	#[cfg(feature = "downloads")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "downloads")),
+		deprecated(note = "the `downloads` permission is not declared in manifest.json")
+	)]
+	#[allow(deprecated)]
+	pub fn downloads(&self) -> Downloads {
+		expect_namespace(self.try_downloads())
+	}
+
+	#[cfg(feature = "downloads")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "downloads")),
+		deprecated(note = "the `downloads` permission is not declared in manifest.json")
+	)]
+	pub fn try_downloads(&self) -> Result<Downloads, ExtensionError> {
+		cached(&self.namespaces.downloads, || Downloads::new(&self.api_root))
+	}
+
+	#[cfg(feature = "extension")]
+	pub fn extension(&self) -> Extension {
+		expect_namespace(self.try_extension())
+	}
+
+	#[cfg(feature = "extension")]
+	pub fn try_extension(&self) -> Result<Extension, ExtensionError> {
+		cached(&self.namespaces.extension, || Extension::new(&self.api_root))
+	}
+
+	#[cfg(feature = "font_settings")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "fontSettings")),
+		deprecated(note = "the `fontSettings` permission is not declared in manifest.json")
+	)]
+	#[allow(deprecated)]
+	pub fn font_settings(&self) -> FontSettings {
+		expect_namespace(self.try_font_settings())
+	}
+
+	#[cfg(feature = "font_settings")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "fontSettings")),
+		deprecated(note = "the `fontSettings` permission is not declared in manifest.json")
+	)]
+	pub fn try_font_settings(&self) -> Result<FontSettings, ExtensionError> {
+		cached(&self.namespaces.font_settings, || FontSettings::new(&self.api_root))
+	}
+
+	#[cfg(feature = "gcm")]
+	#[cfg_attr(all(webext_manifest_checked, not(webext_permission = "gcm")), deprecated(note = "the `gcm` permission is not declared in manifest.json"))]
+	#[allow(deprecated)]
+	pub fn gcm(&self) -> Gcm {
+		expect_namespace(self.try_gcm())
+	}
+
+	#[cfg(feature = "gcm")]
+	#[cfg_attr(all(webext_manifest_checked, not(webext_permission = "gcm")), deprecated(note = "the `gcm` permission is not declared in manifest.json"))]
+	pub fn try_gcm(&self) -> Result<Gcm, ExtensionError> {
+		cached(&self.namespaces.gcm, || Gcm::new(&self.api_root))
+	}
+
+	#[cfg(feature = "gcm")]
+	#[cfg_attr(all(webext_manifest_checked, not(webext_permission = "gcm")), deprecated(note = "the `gcm` permission is not declared in manifest.json"))]
+	#[allow(deprecated)]
+	pub fn instance_id(&self) -> InstanceId {
+		expect_namespace(self.try_instance_id())
+	}
+
+	#[cfg(feature = "gcm")]
+	#[cfg_attr(all(webext_manifest_checked, not(webext_permission = "gcm")), deprecated(note = "the `gcm` permission is not declared in manifest.json"))]
+	pub fn try_instance_id(&self) -> Result<InstanceId, ExtensionError> {
+		cached(&self.namespaces.instance_id, || InstanceId::new(&self.api_root))
+	}
+
+	#[cfg(feature = "i18n")]
+	pub fn i18n(&self) -> I18n {
+		expect_namespace(self.try_i18n())
+	}
+
+	#[cfg(feature = "i18n")]
+	pub fn try_i18n(&self) -> Result<I18n, ExtensionError> {
+		cached(&self.namespaces.i18n, || I18n::new(&self.api_root))
+	}
+
+	#[cfg(feature = "printing")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "printing")),
+		deprecated(note = "the `printing` permission is not declared in manifest.json")
+	)]
+	#[allow(deprecated)]
+	pub fn printing(&self) -> Printing {
+		expect_namespace(self.try_printing())
+	}
+
+	#[cfg(feature = "printing")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "printing")),
+		deprecated(note = "the `printing` permission is not declared in manifest.json")
+	)]
+	pub fn try_printing(&self) -> Result<Printing, ExtensionError> {
+		cached(&self.namespaces.printing, || Printing::new(&self.api_root))
+	}
+
+	#[cfg(feature = "printing_metrics")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "printingMetrics")),
+		deprecated(note = "the `printingMetrics` permission is not declared in manifest.json")
+	)]
+	#[allow(deprecated)]
+	pub fn printing_metrics(&self) -> PrintingMetrics {
+		expect_namespace(self.try_printing_metrics())
+	}
+
+	#[cfg(feature = "printing_metrics")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "printingMetrics")),
+		deprecated(note = "the `printingMetrics` permission is not declared in manifest.json")
+	)]
+	pub fn try_printing_metrics(&self) -> Result<PrintingMetrics, ExtensionError> {
+		cached(&self.namespaces.printing_metrics, || PrintingMetrics::new(&self.api_root))
+	}
+
+	#[cfg(feature = "notifications")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "notifications")),
+		deprecated(note = "the `notifications` permission is not declared in manifest.json")
+	)]
+	#[allow(deprecated)]
+	pub fn notifications(&self) -> Notifications {
+		expect_namespace(self.try_notifications())
+	}
+
+	#[cfg(feature = "notifications")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "notifications")),
+		deprecated(note = "the `notifications` permission is not declared in manifest.json")
+	)]
+	pub fn try_notifications(&self) -> Result<Notifications, ExtensionError> {
+		cached(&self.namespaces.notifications, || Notifications::new(&self.api_root))
+	}
+
+	#[cfg(feature = "permissions")]
+	pub fn permissions(&self) -> Permissions {
+		expect_namespace(self.try_permissions())
+	}
+
+	#[cfg(feature = "permissions")]
+	pub fn try_permissions(&self) -> Result<Permissions, ExtensionError> {
+		cached(&self.namespaces.permissions, || Permissions::new(&self.api_root))
+	}
+
+	#[cfg(feature = "reading_list")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "readingList")),
+		deprecated(note = "the `readingList` permission is not declared in manifest.json")
+	)]
+	#[allow(deprecated)]
+	pub fn reading_list(&self) -> ReadingList {
+		expect_namespace(self.try_reading_list())
+	}
+
+	#[cfg(feature = "reading_list")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "readingList")),
+		deprecated(note = "the `readingList` permission is not declared in manifest.json")
+	)]
+	pub fn try_reading_list(&self) -> Result<ReadingList, ExtensionError> {
+		cached(&self.namespaces.reading_list, || ReadingList::new(&self.api_root))
 	}
 
 	pub fn runtime(&self) -> Runtime {
-		Runtime::new(&self.api_root)
+		expect_namespace(self.try_runtime())
 	}
 
+	pub fn try_runtime(&self) -> Result<Runtime, ExtensionError> {
+		cached(&self.namespaces.runtime, || Runtime::new(&self.api_root))
+	}
+
+	#[cfg(feature = "scripting")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "scripting")),
+		deprecated(note = "the `scripting` permission is not declared in manifest.json")
+	)]
+	#[allow(deprecated)]
 	pub fn scripting(&self) -> Scripting {
-		Scripting::new(&self.api_root)
+		expect_namespace(self.try_scripting())
+	}
+
+	#[cfg(feature = "scripting")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "scripting")),
+		deprecated(note = "the `scripting` permission is not declared in manifest.json")
+	)]
+	pub fn try_scripting(&self) -> Result<Scripting, ExtensionError> {
+		cached(&self.namespaces.scripting, || Scripting::new(&self.api_root))
 	}
 
+	#[cfg(feature = "storage")]
+	#[cfg_attr(all(webext_manifest_checked, not(webext_permission = "storage")), deprecated(note = "the `storage` permission is not declared in manifest.json"))]
+	#[allow(deprecated)]
 	pub fn storage(&self) -> Storage {
-		Storage::new(&self.api_root)
+		expect_namespace(self.try_storage())
 	}
 
+	#[cfg(feature = "storage")]
+	#[cfg_attr(all(webext_manifest_checked, not(webext_permission = "storage")), deprecated(note = "the `storage` permission is not declared in manifest.json"))]
+	pub fn try_storage(&self) -> Result<Storage, ExtensionError> {
+		cached(&self.namespaces.storage, || Storage::new(&self.api_root))
+	}
+
+	#[cfg(feature = "system_cpu")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "system.cpu")),
+		deprecated(note = "the `system.cpu` permission is not declared in manifest.json")
+	)]
+	#[allow(deprecated)]
+	pub fn system_cpu(&self) -> SystemCpu {
+		expect_namespace(self.try_system_cpu())
+	}
+
+	#[cfg(feature = "system_cpu")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "system.cpu")),
+		deprecated(note = "the `system.cpu` permission is not declared in manifest.json")
+	)]
+	pub fn try_system_cpu(&self) -> Result<SystemCpu, ExtensionError> {
+		cached(&self.namespaces.system_cpu, || SystemCpu::new(&self.api_root))
+	}
+
+	#[cfg(feature = "system_display")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "system.display")),
+		deprecated(note = "the `system.display` permission is not declared in manifest.json")
+	)]
+	#[allow(deprecated)]
+	pub fn system_display(&self) -> SystemDisplay {
+		expect_namespace(self.try_system_display())
+	}
+
+	#[cfg(feature = "system_display")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "system.display")),
+		deprecated(note = "the `system.display` permission is not declared in manifest.json")
+	)]
+	pub fn try_system_display(&self) -> Result<SystemDisplay, ExtensionError> {
+		cached(&self.namespaces.system_display, || SystemDisplay::new(&self.api_root))
+	}
+
+	#[cfg(feature = "system_memory")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "system.memory")),
+		deprecated(note = "the `system.memory` permission is not declared in manifest.json")
+	)]
+	#[allow(deprecated)]
+	pub fn system_memory(&self) -> SystemMemory {
+		expect_namespace(self.try_system_memory())
+	}
+
+	#[cfg(feature = "system_memory")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "system.memory")),
+		deprecated(note = "the `system.memory` permission is not declared in manifest.json")
+	)]
+	pub fn try_system_memory(&self) -> Result<SystemMemory, ExtensionError> {
+		cached(&self.namespaces.system_memory, || SystemMemory::new(&self.api_root))
+	}
+
+	#[cfg(feature = "tab_capture")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "tabCapture")),
+		deprecated(note = "the `tabCapture` permission is not declared in manifest.json")
+	)]
+	#[allow(deprecated)]
+	pub fn tab_capture(&self) -> TabCapture {
+		expect_namespace(self.try_tab_capture())
+	}
+
+	#[cfg(feature = "tab_capture")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "tabCapture")),
+		deprecated(note = "the `tabCapture` permission is not declared in manifest.json")
+	)]
+	pub fn try_tab_capture(&self) -> Result<TabCapture, ExtensionError> {
+		cached(&self.namespaces.tab_capture, || TabCapture::new(&self.api_root))
+	}
+
+	#[cfg(feature = "tabs")]
 	pub fn tabs(&self) -> Tabs {
-		Tabs::new(&self.api_root)
+		expect_namespace(self.try_tabs())
 	}
 
+	#[cfg(feature = "tabs")]
+	pub fn try_tabs(&self) -> Result<Tabs, ExtensionError> {
+		cached(&self.namespaces.tabs, || Tabs::new(&self.api_root))
+	}
+
+	#[cfg(feature = "tts")]
+	#[cfg_attr(all(webext_manifest_checked, not(webext_permission = "tts")), deprecated(note = "the `tts` permission is not declared in manifest.json"))]
+	#[allow(deprecated)]
+	pub fn tts(&self) -> Tts {
+		expect_namespace(self.try_tts())
+	}
+
+	#[cfg(feature = "tts")]
+	#[cfg_attr(all(webext_manifest_checked, not(webext_permission = "tts")), deprecated(note = "the `tts` permission is not declared in manifest.json"))]
+	pub fn try_tts(&self) -> Result<Tts, ExtensionError> {
+		cached(&self.namespaces.tts, || Tts::new(&self.api_root))
+	}
+
+	#[cfg(feature = "tts_engine")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "ttsEngine")),
+		deprecated(note = "the `ttsEngine` permission is not declared in manifest.json")
+	)]
+	#[allow(deprecated)]
+	pub fn tts_engine(&self) -> TtsEngine {
+		expect_namespace(self.try_tts_engine())
+	}
+
+	#[cfg(feature = "tts_engine")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "ttsEngine")),
+		deprecated(note = "the `ttsEngine` permission is not declared in manifest.json")
+	)]
+	pub fn try_tts_engine(&self) -> Result<TtsEngine, ExtensionError> {
+		cached(&self.namespaces.tts_engine, || TtsEngine::new(&self.api_root))
+	}
+
+	#[cfg(feature = "side_panel")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(webext_permission = "sidePanel")),
+		deprecated(note = "the `sidePanel` permission is not declared in manifest.json")
+	)]
 	pub fn side_panel(&self) -> SidePanel {
-		SidePanel::new(&self.api_root, self.browser_type.clone())
+		SidePanel::new(&self.api_root)
 	}
 
-	#[cfg(feature = "chrome")]
+	#[cfg(feature = "declarative_net_request")]
+	#[cfg_attr(
+		all(webext_manifest_checked, not(any(webext_permission = "declarativeNetRequest", webext_permission = "declarativeNetRequestWithHostAccess"))),
+		deprecated(note = "the `declarativeNetRequest` permission is not declared in manifest.json")
+	)]
 	pub fn declarative_net_request(&self) -> DeclarativeNetRequest {
 		DeclarativeNetRequest::new(&self.api_root, self.browser_type.clone())
 	}
 }
 
 pub fn init() -> Result<Browser, ExtensionError> {
+	// `globalThis` is the window in extension pages and content scripts, and the `ServiceWorkerGlobalScope` in MV3 backgrounds where `web_sys::window()` is `None`.
 	let global = js_sys::global();
-	if let Ok(api_root) = js_sys::Reflect::get(&global, &"chrome".into()).and_then(|v| v.dyn_into::<Object>()) {
-		Ok(Browser { api_root, browser_type: BrowserType::Chrome })
-	} else {
-		Err(ExtensionError::UnsupportedBrowser)
-	}
+	let user_agent = js_sys::Reflect::get(&global, &"navigator".into())
+		.and_then(|navigator| js_sys::Reflect::get(&navigator, &"userAgent".into()))
+		.ok()
+		.and_then(|ua| ua.as_string())
+		.unwrap_or_default();
+	["browser", "chrome"]
+		.into_iter()
+		.find_map(|name| {
+			resolve_api_root(&global, name).map(|api_root| Browser { api_root, browser_type: BrowserType::detect(name, &user_agent), namespaces: Rc::default() })
+		})
+		.ok_or(ExtensionError::UnsupportedBrowser)
+}
+
+fn resolve_api_root(global: &Object, name: &str) -> Option<Object> {
+	let api_root = js_sys::Reflect::get(global, &name.into()).ok()?.dyn_into::<Object>().ok()?;
+	// plain web pages can expose a `chrome` object without any extension namespaces on it
+	has_namespace(&api_root, "runtime").then_some(api_root)
+}
+
+fn has_namespace(api_root: &Object, name: &str) -> bool {
+	js_sys::Reflect::get(api_root, &name.into()).is_ok_and(|v| v.is_object())
 }
@@ -0,0 +1,269 @@
+use crate::{
+	api::Runtime,
+	error::ExtensionError,
+	types::{ListenerHandle, MessageSender},
+};
+#[cfg(feature = "storage")]
+use crate::{api::StorageArea, utils::sleep};
+use serde::{Deserialize, Serialize, de::IgnoredAny};
+#[cfg(feature = "storage")]
+use std::time::Duration;
+use std::{
+	cell::RefCell,
+	collections::{BTreeMap, VecDeque},
+	fmt::{self, Write},
+	rc::Rc,
+	sync::atomic::{AtomicU64, Ordering},
+};
+use tracing::{
+	Event, Level, Metadata, Subscriber,
+	field::{Field, Visit},
+	span::{Attributes, Id, Record},
+	subscriber::set_global_default,
+};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::spawn_local;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+	Trace,
+	Debug,
+	Info,
+	Warn,
+	Error,
+}
+
+impl From<&Level> for LogLevel {
+	fn from(level: &Level) -> Self {
+		match *level {
+			Level::TRACE => Self::Trace,
+			Level::DEBUG => Self::Debug,
+			Level::INFO => Self::Info,
+			Level::WARN => Self::Warn,
+			Level::ERROR => Self::Error,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogRecord {
+	pub level: LogLevel,
+	pub target: String,
+	/// The event's message followed by its other fields as `key=value`.
+	pub message: String,
+	/// Milliseconds since the epoch, taken in the sending context.
+	pub timestamp: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForwardedLog {
+	pub record: LogRecord,
+	pub tab_id: Option<u32>,
+	pub url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LogEnvelope {
+	webext_log: LogRecord,
+}
+
+/// Installs a global `tracing` subscriber that prints events up to `max_level` to this context's console and also sends them to the
+/// background, where a [`LogCollector`] gathers them. Call it once at startup of a content script, popup or other extension page.
+pub fn forward_to_background(max_level: Level) -> Result<(), ExtensionError> {
+	set_global_default(ForwardingSubscriber { max_level, next_span: AtomicU64::new(1) }).map_err(|e| ExtensionError::ApiError(e.to_string()))
+}
+
+// holds no JS handles since a global subscriber has to be `Send + Sync`; the runtime is looked up per event instead
+struct ForwardingSubscriber {
+	max_level: Level,
+	next_span: AtomicU64,
+}
+
+impl Subscriber for ForwardingSubscriber {
+	fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+		*metadata.level() <= self.max_level
+	}
+
+	fn max_level_hint(&self) -> Option<tracing::level_filters::LevelFilter> {
+		Some(self.max_level.into())
+	}
+
+	fn new_span(&self, _: &Attributes<'_>) -> Id {
+		Id::from_u64(self.next_span.fetch_add(1, Ordering::Relaxed))
+	}
+
+	fn record(&self, _: &Id, _: &Record<'_>) {}
+
+	fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+	fn event(&self, event: &Event<'_>) {
+		let mut visitor = MessageVisitor::default();
+		event.record(&mut visitor);
+		let metadata = event.metadata();
+		let record = LogRecord { level: metadata.level().into(), target: metadata.target().to_owned(), message: visitor.finish(), timestamp: js_sys::Date::now() };
+		let line = JsValue::from_str(&format!("{} {}: {}", metadata.level(), record.target, record.message));
+		match record.level {
+			LogLevel::Error => web_sys::console::error_1(&line),
+			LogLevel::Warn => web_sys::console::warn_1(&line),
+			LogLevel::Info => web_sys::console::info_1(&line),
+			LogLevel::Debug | LogLevel::Trace => web_sys::console::debug_1(&line),
+		}
+		let Ok(browser) = crate::Browser::global() else {
+			return;
+		};
+		spawn_local(async move {
+			// failures can't be logged without looping back in here, and nobody listening is normal before the background is up
+			let _ = browser.runtime().send_message::<_, IgnoredAny>(&LogEnvelope { webext_log: record }).await;
+		});
+	}
+
+	fn enter(&self, _: &Id) {}
+
+	fn exit(&self, _: &Id) {}
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+	message: String,
+	fields: String,
+}
+
+impl MessageVisitor {
+	fn finish(self) -> String {
+		if self.fields.is_empty() { self.message } else { format!("{}{}", self.message, self.fields) }
+	}
+}
+
+impl Visit for MessageVisitor {
+	fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+		if field.name() == "message" {
+			let _ = write!(self.message, "{value:?}");
+		} else {
+			let _ = write!(self.fields, " {}={value:?}", field.name());
+		}
+	}
+
+	fn record_str(&mut self, field: &Field, value: &str) {
+		if field.name() == "message" {
+			self.message.push_str(value);
+		} else {
+			let _ = write!(self.fields, " {}={value}", field.name());
+		}
+	}
+}
+
+/// The background side of [`forward_to_background`]: keeps the most recent records in a ring buffer, drops records from senders that
+/// exceed the per-second limit, and can mirror the buffer into storage so it survives service worker restarts.
+#[derive(Clone)]
+pub struct LogCollector {
+	state: Rc<RefCell<CollectorState>>,
+}
+
+struct CollectorState {
+	records: VecDeque<ForwardedLog>,
+	capacity: usize,
+	max_per_second: u32,
+	// per sender: the current second and how many records it sent in it
+	windows: BTreeMap<String, (u64, u32)>,
+	dropped: u64,
+	#[cfg(feature = "storage")]
+	persist: Option<(StorageArea, String)>,
+	#[cfg(feature = "storage")]
+	flush_pending: bool,
+}
+
+impl LogCollector {
+	pub const DEFAULT_MAX_PER_SECOND: u32 = 50;
+
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			state: Rc::new(RefCell::new(CollectorState {
+				records: VecDeque::with_capacity(capacity),
+				capacity,
+				max_per_second: Self::DEFAULT_MAX_PER_SECOND,
+				windows: BTreeMap::new(),
+				dropped: 0,
+				#[cfg(feature = "storage")]
+				persist: None,
+				#[cfg(feature = "storage")]
+				flush_pending: false,
+			})),
+		}
+	}
+
+	pub fn max_per_second(self, max_per_second: u32) -> Self {
+		self.state.borrow_mut().max_per_second = max_per_second;
+		self
+	}
+
+	/// Writes the buffer to `key` in `area` at most once a second while records keep arriving.
+	#[cfg(feature = "storage")]
+	pub fn persist_to(self, area: StorageArea, key: &str) -> Self {
+		self.state.borrow_mut().persist = Some((area, key.to_owned()));
+		self
+	}
+
+	pub fn listen(&self, runtime: &Runtime) -> Result<ListenerHandle<dyn FnMut(JsValue, JsValue, JsValue)>, ExtensionError> {
+		let collector = self.clone();
+		runtime.on_message::<LogEnvelope>()?.add_listener(move |envelope, sender| collector.push(envelope.webext_log, &sender))
+	}
+
+	pub fn records(&self) -> Vec<ForwardedLog> {
+		self.state.borrow().records.iter().cloned().collect()
+	}
+
+	/// How many records were rejected by the rate limit so far.
+	pub fn dropped(&self) -> u64 {
+		self.state.borrow().dropped
+	}
+
+	pub fn clear(&self) {
+		self.state.borrow_mut().records.clear();
+	}
+
+	fn push(&self, record: LogRecord, sender: &MessageSender) {
+		let tab_id = sender.tab.as_ref().and_then(|tab| tab.id);
+		let sender_key = tab_id.map_or_else(|| sender.url.clone().unwrap_or_default(), |id| id.to_string());
+		let second = (js_sys::Date::now() / 1000.0) as u64;
+		let mut state = self.state.borrow_mut();
+		let max_per_second = state.max_per_second;
+		// closed tabs never come back, so forget senders that have been quiet for a while
+		if state.windows.len() > 256 {
+			state.windows.retain(|_, (window_second, _)| *window_second == second);
+		}
+		let window = state.windows.entry(sender_key).or_insert((second, 0));
+		if window.0 != second {
+			*window = (second, 0);
+		}
+		if window.1 >= max_per_second {
+			state.dropped += 1;
+			return;
+		}
+		window.1 += 1;
+		while state.records.len() >= state.capacity.max(1) {
+			state.records.pop_front();
+		}
+		state.records.push_back(ForwardedLog { record, tab_id, url: sender.url.clone() });
+		#[cfg(feature = "storage")]
+		if state.persist.is_some() && !state.flush_pending {
+			state.flush_pending = true;
+			let collector = self.clone();
+			spawn_local(async move {
+				sleep(Duration::from_secs(1)).await;
+				let (area, key, records) = {
+					let mut state = collector.state.borrow_mut();
+					state.flush_pending = false;
+					let Some((area, key)) = state.persist.clone() else {
+						return;
+					};
+					(area, key, state.records.iter().cloned().collect::<Vec<_>>())
+				};
+				let _ = area.set(&key, &records).await;
+			});
+		}
+	}
+}
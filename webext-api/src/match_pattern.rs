@@ -0,0 +1,203 @@
+use crate::error::MatchPatternError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+use std::{fmt, str::FromStr};
+
+const WILDCARD_SCHEMES: &[&str] = &["http", "https", "ws", "wss"];
+const ALL_URLS_SCHEMES: &[&str] = &["http", "https", "ws", "wss", "ftp", "file", "urn"];
+
+/// A parsed WebExtension match pattern such as `*://*.example.com/*` or `<all_urls>`, matched against URLs without calling into the
+/// browser. Like Chrome, a pattern without a port matches every port, and the path part is matched against the URL's path and query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchPattern {
+	source: String,
+	kind: PatternKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PatternKind {
+	AllUrls,
+	Parts { scheme: Option<String>, host: HostPattern, port: Option<u16>, path: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HostPattern {
+	Any,
+	/// `*.example.com`: the domain itself and every subdomain.
+	Subdomains(String),
+	Exact(String),
+}
+
+impl MatchPattern {
+	pub fn parse(pattern: &str) -> Result<Self, MatchPatternError> {
+		if pattern == "<all_urls>" {
+			return Ok(Self { source: pattern.to_owned(), kind: PatternKind::AllUrls });
+		}
+		let (scheme, rest) = pattern.split_once("://").ok_or(MatchPatternError::MissingSchemeSeparator)?;
+		let scheme = match scheme {
+			"*" => None,
+			scheme if is_valid_scheme(scheme) => Some(scheme.to_ascii_lowercase()),
+			scheme => return Err(MatchPatternError::InvalidScheme(scheme.to_owned())),
+		};
+		let path_start = rest.find('/').ok_or(MatchPatternError::MissingPath)?;
+		let (authority, path) = rest.split_at(path_start);
+		let (host, port) = split_host_port(authority);
+		let port = match port {
+			None | Some("*") => None,
+			Some(port) => Some(port.parse().map_err(|_| MatchPatternError::InvalidPort(port.to_owned()))?),
+		};
+		let host = match host {
+			"*" => HostPattern::Any,
+			// only `file` URLs have no host
+			"" if scheme.as_deref() == Some("file") => HostPattern::Exact(String::new()),
+			host if host.starts_with("*.") && is_valid_host(&host[2..]) => HostPattern::Subdomains(host[2..].to_ascii_lowercase()),
+			host if is_valid_host(host) => HostPattern::Exact(host.to_ascii_lowercase()),
+			host => return Err(MatchPatternError::InvalidHost(host.to_owned())),
+		};
+		Ok(Self { source: pattern.to_owned(), kind: PatternKind::Parts { scheme, host, port, path: path.to_owned() } })
+	}
+
+	pub fn all_urls() -> Self {
+		Self { source: "<all_urls>".to_owned(), kind: PatternKind::AllUrls }
+	}
+
+	/// The pattern for every page on `url`'s host, e.g. `*://example.com/*` for `https://example.com/a?b`, or `None` for URLs
+	/// without a host such as `file:` or `data:` ones.
+	pub fn for_site(url: &str) -> Option<Self> {
+		let url = ParsedUrl::parse(url).filter(|url| is_valid_host(&url.host))?;
+		let scheme = if WILDCARD_SCHEMES.contains(&url.scheme.as_str()) { "*" } else { url.scheme.as_str() };
+		Self::parse(&format!("{scheme}://{}/*", url.host)).ok()
+	}
+
+	pub fn as_str(&self) -> &str {
+		&self.source
+	}
+
+	/// Whether `url` matches; URLs that can't be parsed never do.
+	pub fn matches(&self, url: &str) -> bool {
+		let Some(url) = ParsedUrl::parse(url) else {
+			return false;
+		};
+		match &self.kind {
+			PatternKind::AllUrls => ALL_URLS_SCHEMES.contains(&url.scheme.as_str()),
+			PatternKind::Parts { scheme, host, port, path } => {
+				let scheme_matches = match scheme {
+					None => WILDCARD_SCHEMES.contains(&url.scheme.as_str()),
+					Some(scheme) => *scheme == url.scheme,
+				};
+				let host_matches = match host {
+					HostPattern::Any => true,
+					HostPattern::Subdomains(domain) => url.host == *domain || url.host.strip_suffix(domain.as_str()).is_some_and(|sub| sub.ends_with('.')),
+					HostPattern::Exact(exact) => url.host == *exact,
+				};
+				scheme_matches && host_matches && port.is_none_or(|port| url.port == Some(port)) && glob_match(path, &url.path)
+			},
+		}
+	}
+}
+
+impl FromStr for MatchPattern {
+	type Err = MatchPatternError;
+
+	fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+		Self::parse(pattern)
+	}
+}
+
+impl fmt::Display for MatchPattern {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.source)
+	}
+}
+
+impl Serialize for MatchPattern {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&self.source)
+	}
+}
+
+impl<'de> Deserialize<'de> for MatchPattern {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		Self::parse(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+	}
+}
+
+fn is_valid_scheme(scheme: &str) -> bool {
+	let mut chars = scheme.chars();
+	chars.next().is_some_and(|c| c.is_ascii_alphabetic()) && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+fn is_valid_host(host: &str) -> bool {
+	!host.is_empty() && !host.contains(['*', '/', '?', '#', '@'])
+}
+
+// keeps the brackets of IPv6 literals on the host, e.g. `[::1]:8080` -> (`[::1]`, `8080`)
+fn split_host_port(authority: &str) -> (&str, Option<&str>) {
+	let host_end =
+		if authority.starts_with('[') { authority.find(']').map_or(authority.len(), |end| end + 1) } else { authority.find(':').unwrap_or(authority.len()) };
+	let (host, port) = authority.split_at(host_end);
+	(host, port.strip_prefix(':'))
+}
+
+struct ParsedUrl {
+	scheme: String,
+	host: String,
+	port: Option<u16>,
+	/// Path and query, without the fragment.
+	path: String,
+}
+
+impl ParsedUrl {
+	fn parse(url: &str) -> Option<Self> {
+		let (scheme, rest) = url.split_once(':')?;
+		if !is_valid_scheme(scheme) {
+			return None;
+		}
+		let rest = rest.split('#').next().unwrap_or_default();
+		let Some(rest) = rest.strip_prefix("//") else {
+			// `data:`, `urn:` and the like have no authority
+			return Some(Self { scheme: scheme.to_ascii_lowercase(), host: String::new(), port: None, path: rest.to_owned() });
+		};
+		let authority_end = rest.find(['/', '?']).unwrap_or(rest.len());
+		let (authority, path) = rest.split_at(authority_end);
+		let authority = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+		let (host, port) = split_host_port(authority);
+		let port = match port {
+			Some(port) => Some(port.parse().ok()?),
+			None => default_port(scheme),
+		};
+		let path = if path.starts_with('/') { path.to_owned() } else { format!("/{path}") };
+		Some(Self { scheme: scheme.to_ascii_lowercase(), host: host.to_ascii_lowercase(), port, path })
+	}
+}
+
+fn default_port(scheme: &str) -> Option<u16> {
+	match scheme.to_ascii_lowercase().as_str() {
+		"http" | "ws" => Some(80),
+		"https" | "wss" => Some(443),
+		"ftp" => Some(21),
+		_ => None,
+	}
+}
+
+// `*` matches any run of characters, everything else matches literally
+fn glob_match(pattern: &str, text: &str) -> bool {
+	let (pattern, text) = (pattern.as_bytes(), text.as_bytes());
+	let (mut p, mut t) = (0, 0);
+	let mut backtrack = None;
+	while t < text.len() {
+		if p < pattern.len() && pattern[p] == b'*' {
+			backtrack = Some((p, t));
+			p += 1;
+		} else if p < pattern.len() && pattern[p] == text[t] {
+			p += 1;
+			t += 1;
+		} else if let Some((star, matched)) = backtrack {
+			p = star + 1;
+			t = matched + 1;
+			backtrack = Some((star, matched + 1));
+		} else {
+			return false;
+		}
+	}
+	pattern[p..].iter().all(|&c| c == b'*')
+}
@@ -0,0 +1,154 @@
+use crate::{
+	backend::{BrowserBackend, RuntimeBackend, StorageBackend, TabsBackend},
+	error::ExtensionError,
+	types::TabInfo,
+};
+use async_trait::async_trait;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+type MessageHandler = Box<dyn FnMut(Option<u32>, Value) -> Result<Value, String>>;
+
+/// In-memory stand-in for [`crate::Browser`]: clones share the same storage, tab list and message bus, so a test can keep one handle to
+/// script and inspect while the code under test uses another.
+#[derive(Clone, Default)]
+pub struct MockBrowser {
+	local: MockStorage,
+	sync: MockStorage,
+	tabs: Rc<RefCell<Vec<TabInfo>>>,
+	bus: MessageBus,
+}
+
+impl MockBrowser {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn with_tabs(self, tabs: Vec<TabInfo>) -> Self {
+		*self.tabs.borrow_mut() = tabs;
+		self
+	}
+
+	/// Answers every `send_message`; the tab id is `None` for runtime messages. Returning `Err` surfaces as [`ExtensionError::ApiError`].
+	pub fn on_message(&self, handler: impl FnMut(Option<u32>, Value) -> Result<Value, String> + 'static) {
+		*self.bus.handler.borrow_mut() = Some(Box::new(handler));
+	}
+
+	pub fn sent_messages(&self) -> Vec<(Option<u32>, Value)> {
+		self.bus.sent.borrow().clone()
+	}
+
+	pub fn local(&self) -> MockStorage {
+		self.local.clone()
+	}
+
+	pub fn sync(&self) -> MockStorage {
+		self.sync.clone()
+	}
+}
+
+impl BrowserBackend for MockBrowser {
+	type Storage = MockStorage;
+	type Tabs = MockTabs;
+	type Runtime = MockRuntime;
+
+	fn local_storage(&self) -> MockStorage {
+		self.local.clone()
+	}
+
+	fn sync_storage(&self) -> MockStorage {
+		self.sync.clone()
+	}
+
+	fn tabs(&self) -> MockTabs {
+		MockTabs { tabs: self.tabs.clone(), bus: self.bus.clone() }
+	}
+
+	fn runtime(&self) -> MockRuntime {
+		MockRuntime { bus: self.bus.clone() }
+	}
+}
+
+#[derive(Clone, Default)]
+pub struct MockStorage {
+	items: Rc<RefCell<BTreeMap<String, Value>>>,
+}
+
+impl MockStorage {
+	pub fn snapshot(&self) -> BTreeMap<String, Value> {
+		self.items.borrow().clone()
+	}
+}
+
+#[async_trait(?Send)]
+impl StorageBackend for MockStorage {
+	async fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, ExtensionError> {
+		match self.items.borrow().get(key) {
+			Some(Value::Null) | None => Ok(None),
+			Some(value) => from_json(value.clone()).map(Some),
+		}
+	}
+
+	async fn set<T: Serialize>(&self, key: &str, value: &T) -> Result<(), ExtensionError> {
+		self.items.borrow_mut().insert(key.to_owned(), to_json(value)?);
+		Ok(())
+	}
+}
+
+#[derive(Clone)]
+pub struct MockTabs {
+	tabs: Rc<RefCell<Vec<TabInfo>>>,
+	bus: MessageBus,
+}
+
+#[async_trait(?Send)]
+impl TabsBackend for MockTabs {
+	async fn get_active(&self) -> Result<TabInfo, ExtensionError> {
+		self.tabs.borrow().iter().find(|tab| tab.active).cloned().ok_or(ExtensionError::TabNotFound)
+	}
+
+	async fn send_message<M: Serialize, R: DeserializeOwned>(&self, tab_id: u32, message: &M) -> Result<R, ExtensionError> {
+		if !self.tabs.borrow().iter().any(|tab| tab.id == Some(tab_id)) {
+			return Err(ExtensionError::TabNotFound);
+		}
+		self.bus.dispatch(Some(tab_id), message)
+	}
+}
+
+#[derive(Clone)]
+pub struct MockRuntime {
+	bus: MessageBus,
+}
+
+#[async_trait(?Send)]
+impl RuntimeBackend for MockRuntime {
+	async fn send_message<M: Serialize, R: DeserializeOwned>(&self, message: &M) -> Result<R, ExtensionError> {
+		self.bus.dispatch(None, message)
+	}
+}
+
+#[derive(Clone, Default)]
+struct MessageBus {
+	sent: Rc<RefCell<Vec<(Option<u32>, Value)>>>,
+	handler: Rc<RefCell<Option<MessageHandler>>>,
+}
+
+impl MessageBus {
+	fn dispatch<M: Serialize, R: DeserializeOwned>(&self, tab_id: Option<u32>, message: &M) -> Result<R, ExtensionError> {
+		let message = to_json(message)?;
+		self.sent.borrow_mut().push((tab_id, message.clone()));
+		let mut handler = self.handler.borrow_mut();
+		// mirrors the browser: with nobody listening the sender gets "Receiving end does not exist"
+		let handler = handler.as_mut().ok_or_else(|| ExtensionError::ApiError("Could not establish connection. Receiving end does not exist.".to_owned()))?;
+		from_json(handler(tab_id, message).map_err(ExtensionError::ApiError)?)
+	}
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<Value, ExtensionError> {
+	serde_json::to_value(value).map_err(|e| ExtensionError::ApiError(e.to_string()))
+}
+
+fn from_json<T: DeserializeOwned>(value: Value) -> Result<T, ExtensionError> {
+	serde_json::from_value(value).map_err(|e| ExtensionError::ApiError(e.to_string()))
+}
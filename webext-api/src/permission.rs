@@ -0,0 +1,110 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+use std::fmt;
+
+macro_rules! permissions {
+	($($variant:ident => $name:literal,)*) => {
+		/// A manifest `permissions` entry. Host permissions are match patterns and are not listed here.
+		#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+		pub enum Permission {
+			$($variant,)*
+		}
+
+		impl Permission {
+			pub const ALL: &[Permission] = &[$(Permission::$variant,)*];
+
+			/// The string used in manifest.json and `permissions.request`.
+			pub fn as_str(&self) -> &'static str {
+				match self {
+					$(Self::$variant => $name,)*
+				}
+			}
+
+			pub fn from_name(name: &str) -> Option<Self> {
+				match name {
+					$($name => Some(Self::$variant),)*
+					_ => None,
+				}
+			}
+		}
+	};
+}
+
+permissions! {
+	ActiveTab => "activeTab",
+	Alarms => "alarms",
+	Background => "background",
+	Bookmarks => "bookmarks",
+	BrowserSettings => "browserSettings",
+	BrowsingData => "browsingData",
+	ClipboardRead => "clipboardRead",
+	ClipboardWrite => "clipboardWrite",
+	ContentSettings => "contentSettings",
+	ContextMenus => "contextMenus",
+	Cookies => "cookies",
+	Debugger => "debugger",
+	DeclarativeContent => "declarativeContent",
+	DeclarativeNetRequest => "declarativeNetRequest",
+	DeclarativeNetRequestFeedback => "declarativeNetRequestFeedback",
+	DeclarativeNetRequestWithHostAccess => "declarativeNetRequestWithHostAccess",
+	DesktopCapture => "desktopCapture",
+	Dns => "dns",
+	Downloads => "downloads",
+	DownloadsOpen => "downloads.open",
+	Favicon => "favicon",
+	FontSettings => "fontSettings",
+	Gcm => "gcm",
+	Geolocation => "geolocation",
+	History => "history",
+	Identity => "identity",
+	Idle => "idle",
+	Management => "management",
+	Menus => "menus",
+	NativeMessaging => "nativeMessaging",
+	Notifications => "notifications",
+	Offscreen => "offscreen",
+	PageCapture => "pageCapture",
+	Power => "power",
+	Printing => "printing",
+	PrintingMetrics => "printingMetrics",
+	Privacy => "privacy",
+	Proxy => "proxy",
+	ReadingList => "readingList",
+	Scripting => "scripting",
+	Search => "search",
+	Sessions => "sessions",
+	SidePanel => "sidePanel",
+	Storage => "storage",
+	SystemCpu => "system.cpu",
+	SystemDisplay => "system.display",
+	SystemMemory => "system.memory",
+	SystemStorage => "system.storage",
+	TabCapture => "tabCapture",
+	TabGroups => "tabGroups",
+	Tabs => "tabs",
+	TopSites => "topSites",
+	Tts => "tts",
+	TtsEngine => "ttsEngine",
+	UnlimitedStorage => "unlimitedStorage",
+	WebNavigation => "webNavigation",
+	WebRequest => "webRequest",
+	WebRequestBlocking => "webRequestBlocking",
+}
+
+impl fmt::Display for Permission {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+impl Serialize for Permission {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(self.as_str())
+	}
+}
+
+impl<'de> Deserialize<'de> for Permission {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let name = String::deserialize(deserializer)?;
+		Self::from_name(&name).ok_or_else(|| D::Error::custom(format!("unknown permission `{name}`")))
+	}
+}
@@ -0,0 +1,93 @@
+use crate::{
+	error::{ErrorKind, ExtensionError},
+	utils::sleep,
+};
+use futures::future::{Either, select};
+use std::{future::Future, pin::pin, time::Duration};
+
+/// Bounded exponential backoff for calls that fail with a retryable error (see [`ExtensionError::is_retryable`]), typically
+/// "Receiving end does not exist" while a freshly opened tab's content script is still loading. Other kinds of errors are only
+/// retried once [`Self::retry_on`] opts in, for calls that are safe to repeat.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+	/// Including the first attempt.
+	pub max_attempts: u32,
+	pub initial_delay: Duration,
+	pub max_delay: Duration,
+	/// Gives up on a single attempt after this long with [`ErrorKind::Timeout`], which is only retried if it is in `also_retry`.
+	pub attempt_timeout: Option<Duration>,
+	/// Kinds retried besides the retryable ones, e.g. [`ErrorKind::MessagePortClosed`] for an idempotent message.
+	pub also_retry: Vec<ErrorKind>,
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		Self { max_attempts: 5, initial_delay: Duration::from_millis(100), max_delay: Duration::from_secs(2), attempt_timeout: None, also_retry: Vec::new() }
+	}
+}
+
+impl RetryPolicy {
+	pub fn new(max_attempts: u32) -> Self {
+		Self { max_attempts, ..Default::default() }
+	}
+
+	pub fn initial_delay(mut self, delay: Duration) -> Self {
+		self.initial_delay = delay;
+		self
+	}
+
+	pub fn max_delay(mut self, delay: Duration) -> Self {
+		self.max_delay = delay;
+		self
+	}
+
+	pub fn attempt_timeout(mut self, timeout: Duration) -> Self {
+		self.attempt_timeout = Some(timeout);
+		self
+	}
+
+	/// Also retries errors of `kind`; only for calls the receiver can safely handle more than once.
+	pub fn retry_on(mut self, kind: ErrorKind) -> Self {
+		self.also_retry.push(kind);
+		self
+	}
+
+	pub fn retries(&self, err: &ExtensionError) -> bool {
+		err.is_retryable() || self.also_retry.contains(&err.kind())
+	}
+
+	/// The wait before retry number `retry` (starting at 1): the initial delay, doubled each time, capped at `max_delay`.
+	pub fn delay(&self, retry: u32) -> Duration {
+		self.initial_delay.saturating_mul(2_u32.saturating_pow(retry.saturating_sub(1))).min(self.max_delay)
+	}
+}
+
+/// Fails with [`ExtensionError::Timeout`] naming `name` if `future` hasn't finished within `duration`.
+pub async fn with_timeout<T>(name: &str, duration: Duration, future: impl Future<Output = Result<T, ExtensionError>>) -> Result<T, ExtensionError> {
+	match select(pin!(future), pin!(sleep(duration))).await {
+		Either::Left((result, _)) => result,
+		Either::Right(_) => Err(ExtensionError::Timeout(name.to_owned())),
+	}
+}
+
+/// Runs `call` until it succeeds, fails with an error `policy` doesn't retry, or `policy.max_attempts` is used up; the last error is returned.
+pub async fn with_retries<T, F, Fut>(policy: &RetryPolicy, name: &str, mut call: F) -> Result<T, ExtensionError>
+where
+	F: FnMut() -> Fut,
+	Fut: Future<Output = Result<T, ExtensionError>>,
+{
+	let mut attempt = 1;
+	loop {
+		let result = match policy.attempt_timeout {
+			Some(timeout) => with_timeout(name, timeout, call()).await,
+			None => call().await,
+		};
+		match result {
+			Err(err) if policy.retries(&err) && attempt < policy.max_attempts => {
+				sleep(policy.delay(attempt)).await;
+				attempt += 1;
+			},
+			result => return result,
+		}
+	}
+}
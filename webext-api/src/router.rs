@@ -0,0 +1,78 @@
+use crate::{
+	api::Runtime,
+	error::ExtensionError,
+	types::{ListenerHandle, MessageSender, attach_listener},
+};
+use futures::future::LocalBoxFuture;
+use js_sys::Function;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_wasm_bindgen::{from_value, to_value};
+use std::future::Future;
+use wasm_bindgen::{JsCast, prelude::*};
+use wasm_bindgen_futures::spawn_local;
+
+/// A message that can be carved out of the protocol enum `P`, e.g. a unit struct standing in for `ExtMessage::GetPageContent`.
+pub trait RoutedMessage<P>: Sized {
+	type Response: Serialize;
+
+	/// Returns the message back unchanged when it belongs to another variant, so the next handler can try it.
+	fn extract(message: P) -> Result<Self, P>;
+}
+
+type Handler<P> = Box<dyn Fn(P, MessageSender) -> Result<LocalBoxFuture<'static, Result<JsValue, ExtensionError>>, P>>;
+
+pub struct MessageRouter<P> {
+	handlers: Vec<Handler<P>>,
+}
+
+impl<P: DeserializeOwned + 'static> Default for MessageRouter<P> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<P: DeserializeOwned + 'static> MessageRouter<P> {
+	pub fn new() -> Self {
+		Self { handlers: vec![] }
+	}
+
+	pub fn on<M, F, Fut>(mut self, handler: F) -> Self
+	where
+		M: RoutedMessage<P> + 'static,
+		F: Fn(M, MessageSender) -> Fut + 'static,
+		Fut: Future<Output = M::Response> + 'static,
+	{
+		self.handlers.push(Box::new(move |message, sender| {
+			let response = handler(M::extract(message)?, sender);
+			Ok(Box::pin(async move { to_value(&response.await).map_err(Into::into) }))
+		}));
+		self
+	}
+
+	pub fn listen(self, runtime: &Runtime) -> Result<ListenerHandle<dyn FnMut(JsValue, JsValue, JsValue) -> JsValue>, ExtensionError> {
+		let on_message = runtime.on_message::<P>()?;
+		let handlers = self.handlers;
+		attach_listener(
+			on_message.target(),
+			Closure::wrap(Box::new(move |message: JsValue, sender: JsValue, send_response: JsValue| {
+				let (Ok(mut message), Ok(sender)) = (from_value::<P>(message), from_value::<MessageSender>(sender)) else {
+					return JsValue::FALSE;
+				};
+				for handler in &handlers {
+					match handler(message, sender.clone()) {
+						Ok(response) => {
+							let send_response: Function = send_response.unchecked_into();
+							spawn_local(async move {
+								let _ = send_response.call1(&JsValue::UNDEFINED, &response.await.unwrap_or(JsValue::UNDEFINED));
+							});
+							// keeps the message channel open until `sendResponse` is called
+							return JsValue::TRUE;
+						},
+						Err(unhandled) => message = unhandled,
+					}
+				}
+				JsValue::FALSE
+			}) as Box<dyn FnMut(JsValue, JsValue, JsValue) -> JsValue>),
+		)
+	}
+}
@@ -0,0 +1,103 @@
+#[cfg(feature = "tabs")]
+use crate::api::Tabs;
+use crate::{
+	api::Runtime,
+	error::ExtensionError,
+	retry::with_timeout,
+	types::{ListenerHandle, MessageSender, attach_listener},
+};
+use js_sys::Function;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_wasm_bindgen::{from_value, to_value};
+use std::{
+	fmt::Display,
+	future::Future,
+	sync::atomic::{AtomicU32, Ordering},
+	time::Duration,
+};
+use wasm_bindgen::{JsCast, prelude::*};
+use wasm_bindgen_futures::spawn_local;
+
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+static NEXT_CALL: AtomicU32 = AtomicU32::new(0);
+
+pub trait RpcRequest: Serialize + DeserializeOwned + 'static {
+	const METHOD: &'static str;
+	type Response: Serialize + DeserializeOwned + 'static;
+}
+
+#[derive(Serialize, Deserialize)]
+struct RpcEnvelope<T> {
+	rpc: String,
+	id: String,
+	payload: T,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RpcReply<R> {
+	id: String,
+	result: Result<R, String>,
+}
+
+#[derive(Clone, Copy)]
+pub enum RpcTarget<'a> {
+	Runtime(&'a Runtime),
+	#[cfg(feature = "tabs")]
+	Tab(&'a Tabs, u32),
+}
+
+fn correlation_id() -> String {
+	format!("{:x}-{:x}-{:x}", js_sys::Date::now() as u64, NEXT_CALL.fetch_add(1, Ordering::Relaxed), (js_sys::Math::random() * f64::from(u32::MAX)) as u32)
+}
+
+pub async fn call<Req: RpcRequest>(target: RpcTarget<'_>, request: &Req) -> Result<Req::Response, ExtensionError> {
+	call_with_timeout(target, request, DEFAULT_TIMEOUT).await
+}
+
+pub async fn call_with_timeout<Req: RpcRequest>(target: RpcTarget<'_>, request: &Req, timeout: Duration) -> Result<Req::Response, ExtensionError> {
+	let id = correlation_id();
+	let envelope = RpcEnvelope { rpc: Req::METHOD.to_string(), id: id.clone(), payload: request };
+	let send = async {
+		match target {
+			RpcTarget::Runtime(runtime) => runtime.send_message::<_, RpcReply<Req::Response>>(&envelope).await,
+			#[cfg(feature = "tabs")]
+			RpcTarget::Tab(tabs, tab_id) => tabs.send_message::<_, RpcReply<Req::Response>>(tab_id, &envelope).await,
+		}
+	};
+	let reply = with_timeout(Req::METHOD, timeout, send).await?;
+	if reply.id != id {
+		return Err(ExtensionError::ApiError(format!("Mismatched reply for `{}`: expected call {id}, got {}", Req::METHOD, reply.id)));
+	}
+	reply.result.map_err(ExtensionError::RemoteError)
+}
+
+pub fn serve<Req, F, Fut, E>(runtime: &Runtime, handler: F) -> Result<ListenerHandle<dyn FnMut(JsValue, JsValue, JsValue) -> JsValue>, ExtensionError>
+where
+	Req: RpcRequest,
+	F: Fn(Req, MessageSender) -> Fut + 'static,
+	Fut: Future<Output = Result<Req::Response, E>> + 'static,
+	E: Display,
+{
+	let on_message = runtime.on_message::<RpcEnvelope<Req>>()?;
+	attach_listener(
+		on_message.target(),
+		Closure::wrap(Box::new(move |message: JsValue, sender: JsValue, send_response: JsValue| {
+			let (Ok(envelope), Ok(sender)) = (from_value::<RpcEnvelope<Req>>(message), from_value::<MessageSender>(sender)) else {
+				return JsValue::FALSE;
+			};
+			if envelope.rpc != Req::METHOD {
+				return JsValue::FALSE;
+			}
+			let response = handler(envelope.payload, sender);
+			let send_response: Function = send_response.unchecked_into();
+			spawn_local(async move {
+				let reply = RpcReply { id: envelope.id, result: response.await.map_err(|e| e.to_string()) };
+				if let Ok(reply) = to_value(&reply) {
+					let _ = send_response.call1(&JsValue::UNDEFINED, &reply);
+				}
+			});
+			JsValue::TRUE
+		}) as Box<dyn FnMut(JsValue, JsValue, JsValue) -> JsValue>),
+	)
+}
@@ -1,17 +1,62 @@
-use crate::error::ExtensionError;
+use crate::{error::ExtensionError, utils::sleep};
+use futures::{
+	Stream,
+	channel::mpsc::{UnboundedReceiver, UnboundedSender, unbounded},
+};
 use js_sys::{Function, Object};
 use serde::{Deserialize, Serialize};
-use wasm_bindgen::{JsCast, prelude::*};
+use std::{
+	any::Any,
+	pin::Pin,
+	task::{Context, Poll},
+	time::Duration,
+};
+use wasm_bindgen::{JsCast, closure::WasmClosure, prelude::*};
 
 pub struct ListenerHandle<T: ?Sized> {
 	target: Object,
-	closure: Closure<T>,
+	closure: Option<Closure<T>>,
+	// set for DOM listeners, which are removed through `removeEventListener(type, ..)` instead of `removeListener`
+	dom_event: Option<String>,
+}
+
+impl<T: ?Sized + WasmClosure> ListenerHandle<T> {
+	/// Keeps the JS listener registered forever and leaks the Rust closure backing it.
+	pub fn forget(mut self) {
+		if let Some(closure) = self.closure.take() {
+			closure.forget();
+		}
+	}
+
+	/// Keeps the JS listener registered and hands the closure over to the JS garbage collector, so it is freed once the browser drops the listener.
+	pub fn detach(mut self) {
+		if let Some(closure) = self.closure.take() {
+			let _ = closure.into_js_value();
+		}
+	}
+
+	pub async fn scoped<F: Future>(self, future: F) -> F::Output {
+		let output = future.await;
+		drop(self);
+		output
+	}
+
+	pub async fn scoped_for(self, duration: Duration) {
+		self.scoped(sleep(duration)).await;
+	}
 }
 
 impl<T: ?Sized> Drop for ListenerHandle<T> {
 	fn drop(&mut self) {
-		if let Ok(remove_listener_fn) = js_sys::Reflect::get(&self.target, &"removeListener".into()).and_then(|v| v.dyn_into::<Function>()) {
-			let _ = remove_listener_fn.call1(&self.target, self.closure.as_ref());
+		let Some(closure) = &self.closure else {
+			return;
+		};
+		let remove_fn = if self.dom_event.is_some() { "removeEventListener" } else { "removeListener" };
+		if let Ok(remove_listener_fn) = js_sys::Reflect::get(&self.target, &remove_fn.into()).and_then(|v| v.dyn_into::<Function>()) {
+			let _ = match &self.dom_event {
+				Some(event) => remove_listener_fn.call2(&self.target, &event.into(), closure.as_ref()),
+				None => remove_listener_fn.call1(&self.target, closure.as_ref()),
+			};
 		}
 	}
 }
@@ -20,25 +65,139 @@ pub(crate) fn attach_listener<T: ?Sized + 'static>(target: &Object, closure: Clo
 	let add_listener_fn: Function =
 		js_sys::Reflect::get(target, &"addListener".into())?.dyn_into().map_err(|_| ExtensionError::ApiNotFound("addListener".to_string()))?;
 	add_listener_fn.call1(target, closure.as_ref())?;
-	Ok(ListenerHandle { target: target.clone(), closure })
+	Ok(ListenerHandle { target: target.clone(), closure: Some(closure), dom_event: None })
+}
+
+pub(crate) fn attach_event_listener<T: ?Sized + 'static>(
+	target: &web_sys::EventTarget,
+	event: &str,
+	closure: Closure<T>,
+) -> Result<ListenerHandle<T>, ExtensionError> {
+	target.add_event_listener_with_callback(event, closure.as_ref().unchecked_ref())?;
+	Ok(ListenerHandle { target: target.clone().into(), closure: Some(closure), dom_event: Some(event.to_owned()) })
+}
+
+pub struct EventStream<T> {
+	receiver: UnboundedReceiver<T>,
+	_handle: Box<dyn Any>,
+}
+
+impl<T: 'static> EventStream<T> {
+	pub(crate) fn new<F: ?Sized + 'static>(
+		register: impl FnOnce(UnboundedSender<T>) -> Result<ListenerHandle<F>, ExtensionError>,
+	) -> Result<Self, ExtensionError> {
+		let (sender, receiver) = unbounded();
+		Ok(Self { receiver, _handle: Box::new(register(sender)?) })
+	}
+}
+
+impl<T> Stream for EventStream<T> {
+	type Item = T;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+		Pin::new(&mut self.receiver).poll_next(cx)
+	}
+}
+
+/// Resolves with the first event an event wrapper's `once()` sees. The listener is registered when `once()` is called, so an event
+/// triggered in between isn't missed, and removed once it fires or when this future is dropped.
+///
+/// ```ignore
+/// let ack = runtime.on_message::<Ack>()?.once()?;
+/// tabs.send_message::<_, ()>(tab_id, &Start).await?;
+/// let (ack, sender) = ack.await;
+/// ```
+#[must_use = "the listener is removed when this is dropped"]
+pub struct Once<T> {
+	events: EventStream<T>,
+}
+
+impl<T: 'static> Once<T> {
+	pub(crate) fn new<F: ?Sized + 'static>(
+		register: impl FnOnce(UnboundedSender<T>) -> Result<ListenerHandle<F>, ExtensionError>,
+	) -> Result<Self, ExtensionError> {
+		Ok(Self { events: EventStream::new(register)? })
+	}
+}
+
+impl<T> Future for Once<T> {
+	type Output = T;
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+		match Pin::new(&mut self.events).poll_next(cx) {
+			Poll::Ready(Some(event)) => Poll::Ready(event),
+			// the sender lives in the listener held by `events`, so the channel can't close while this future exists
+			Poll::Ready(None) | Poll::Pending => Poll::Pending,
+		}
+	}
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BrowserType {
 	Chrome,
 	Firefox,
+	Safari,
+	Edge,
+	Opera,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl BrowserType {
+	pub(crate) fn detect(api_root_name: &str, user_agent: &str) -> Self {
+		if user_agent.contains("Edg/") {
+			Self::Edge
+		} else if user_agent.contains("OPR/") {
+			Self::Opera
+		} else if api_root_name == "browser" {
+			if user_agent.contains("Safari/") && !user_agent.contains("Chrome/") && !user_agent.contains("Firefox/") { Self::Safari } else { Self::Firefox }
+		} else {
+			Self::Chrome
+		}
+	}
+
+	pub fn is_chromium(&self) -> bool {
+		matches!(self, Self::Chrome | Self::Edge | Self::Opera)
+	}
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TabInfo {
 	pub id: Option<u32>,
 	pub title: Option<String>,
+	/// Only visible with the `tabs` permission or a host permission for the tab.
 	pub url: Option<String>,
 	pub active: bool,
 	pub window_id: u32,
+	#[serde(default)]
+	pub index: u32,
+	#[serde(default)]
+	pub pinned: bool,
+	#[serde(default)]
+	pub highlighted: bool,
+	#[serde(default)]
+	pub incognito: bool,
+	/// `unloaded`, `loading` or `complete`.
+	pub status: Option<String>,
+	pub fav_icon_url: Option<String>,
+	pub audible: Option<bool>,
+	pub muted_info: Option<MutedInfo>,
+	/// Chrome only; -1 when the tab isn't in a group.
+	pub group_id: Option<i32>,
+	pub opener_tab_id: Option<u32>,
+	pub width: Option<u32>,
+	pub height: Option<u32>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MutedInfo {
+	pub muted: bool,
+	/// `user`, `capture` or `extension`.
+	pub reason: Option<String>,
+	pub extension_id: Option<String>,
 }
 
+#[cfg(feature = "tabs")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TabChangeInfo {
@@ -48,6 +207,106 @@ pub struct TabChangeInfo {
 	pub audible: Option<bool>,
 }
 
+#[cfg(feature = "tabs")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TabQuery {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub active: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub current_window: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub window_id: Option<u32>,
+	/// Match patterns; tabs whose URL matches any of them are returned. Needs host permission or `tabs` to see the URLs.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub url: Option<Vec<String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub title: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub status: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub audible: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub pinned: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub discarded: Option<bool>,
+}
+
+#[cfg(feature = "tabs")]
+impl TabQuery {
+	pub fn url(patterns: &[&str]) -> Self {
+		Self { url: Some(patterns.iter().map(ToString::to_string).collect()), ..Default::default() }
+	}
+}
+
+/// The new tab of `tabs.create`. Without a `url` it opens the browser's new tab page; extension pages are given by their path.
+#[cfg(feature = "tabs")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TabCreateProperties {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub url: Option<String>,
+	/// Whether the tab gets focus, `true` by default.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub active: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub window_id: Option<u32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub index: Option<u32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub pinned: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub opener_tab_id: Option<u32>,
+}
+
+#[cfg(feature = "tabs")]
+impl TabCreateProperties {
+	pub fn url(url: impl Into<String>) -> Self {
+		Self { url: Some(url.into()), ..Default::default() }
+	}
+}
+
+/// API permissions and host permission patterns, as requested, checked or removed at runtime through `permissions`.
+#[cfg(feature = "permissions")]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PermissionSet {
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub permissions: Vec<crate::Permission>,
+	/// Match patterns, see [`crate::match_pattern::MatchPattern`].
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub origins: Vec<String>,
+}
+
+#[cfg(feature = "permissions")]
+impl PermissionSet {
+	pub fn permissions(permissions: &[crate::Permission]) -> Self {
+		Self { permissions: permissions.to_vec(), origins: Vec::new() }
+	}
+
+	pub fn origins(patterns: &[&str]) -> Self {
+		Self { permissions: Vec::new(), origins: patterns.iter().map(ToString::to_string).collect() }
+	}
+}
+
+#[cfg(feature = "storage")]
+#[derive(Debug, Clone)]
+pub struct StorageChange {
+	pub old_value: Option<JsValue>,
+	pub new_value: Option<JsValue>,
+}
+
+#[cfg(feature = "storage")]
+impl StorageChange {
+	pub fn old_value_as<T: serde::de::DeserializeOwned>(&self) -> Option<T> {
+		self.old_value.clone().and_then(|v| serde_wasm_bindgen::from_value(v).ok())
+	}
+
+	pub fn new_value_as<T: serde::de::DeserializeOwned>(&self) -> Option<T> {
+		self.new_value.clone().and_then(|v| serde_wasm_bindgen::from_value(v).ok())
+	}
+}
+
+#[cfg(feature = "action")]
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BadgeConfig {
@@ -55,41 +314,258 @@ pub struct BadgeConfig {
 	pub text: Option<String>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub tab_id: Option<u32>,
+	/// Firefox only; scopes the badge to every tab of one window. Mutually exclusive with `tab_id`.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub window_id: Option<u32>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub background_color: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub text_color: Option<String>,
+}
+
+#[cfg(feature = "action")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionUserSettings {
+	/// `false` while the icon is only reachable through the extensions (puzzle) menu.
+	pub is_on_toolbar: bool,
+}
+
+#[cfg(feature = "context_menus")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContextMenuItemType {
+	#[default]
+	Normal,
+	Checkbox,
+	Radio,
+	Separator,
+}
+
+#[cfg(feature = "context_menus")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MenuContext {
+	All,
+	Page,
+	Frame,
+	Selection,
+	Link,
+	Editable,
+	Image,
+	Video,
+	Audio,
+	Action,
+	#[cfg(feature = "firefox")]
+	Tab,
+	#[cfg(feature = "firefox")]
+	ToolsMenu,
+	#[cfg(feature = "firefox")]
+	Bookmark,
+	#[cfg(feature = "firefox")]
+	#[serde(rename = "password")]
+	PasswordField,
+}
+
+#[cfg(feature = "context_menus")]
+impl MenuContext {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::All => "all",
+			Self::Page => "page",
+			Self::Frame => "frame",
+			Self::Selection => "selection",
+			Self::Link => "link",
+			Self::Editable => "editable",
+			Self::Image => "image",
+			Self::Video => "video",
+			Self::Audio => "audio",
+			Self::Action => "action",
+			#[cfg(feature = "firefox")]
+			Self::Tab => "tab",
+			#[cfg(feature = "firefox")]
+			Self::ToolsMenu => "tools_menu",
+			#[cfg(feature = "firefox")]
+			Self::Bookmark => "bookmark",
+			#[cfg(feature = "firefox")]
+			Self::PasswordField => "password",
+		}
+	}
+}
+
+#[cfg(feature = "action")]
+#[derive(Debug, Clone)]
+pub enum ActionIcon {
+	Path(std::collections::BTreeMap<u32, String>),
+	ImageData(std::collections::BTreeMap<u32, web_sys::ImageData>),
+}
+
+#[cfg(feature = "side_panel")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PanelOptions {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub path: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub enabled: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub tab_id: Option<u32>,
+}
+
+#[cfg(feature = "side_panel")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SidePanelKind {
+	/// Chromium's per-tab `sidePanel`.
+	SidePanel,
+	/// Firefox's (and Opera's) `sidebarAction`, which also has a title, an icon and `toggle`.
+	SidebarAction,
+}
+
+#[cfg(feature = "side_panel")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PanelBehavior {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub open_panel_on_action_click: Option<bool>,
 }
 
+#[cfg(feature = "context_menus")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ContextMenuConfig {
 	pub id: String,
 	pub title: String,
 	pub contexts: Vec<String>,
+	#[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+	pub item_type: Option<ContextMenuItemType>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub checked: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub parent_id: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub visible: Option<bool>,
+	#[cfg(feature = "firefox")]
+	#[serde(skip_serializing_if = "Option::is_none", serialize_with = "icon_sizes")]
+	pub icons: Option<std::collections::BTreeMap<u32, String>>,
 }
 
+#[cfg(feature = "context_menus")]
 impl ContextMenuConfig {
 	pub fn build(id: impl Into<String>, title: impl Into<String>) -> ContextMenuConfigBuilder {
-		ContextMenuConfigBuilder { id: id.into(), title: title.into(), contexts: vec![] }
+		ContextMenuConfigBuilder {
+			id: id.into(),
+			title: title.into(),
+			contexts: vec![],
+			item_type: None,
+			checked: None,
+			parent_id: None,
+			visible: None,
+			#[cfg(feature = "firefox")]
+			icons: None,
+		}
 	}
 }
 
+#[cfg(feature = "context_menus")]
 pub struct ContextMenuConfigBuilder {
 	id: String,
 	title: String,
 	contexts: Vec<String>,
+	item_type: Option<ContextMenuItemType>,
+	checked: Option<bool>,
+	parent_id: Option<String>,
+	visible: Option<bool>,
+	#[cfg(feature = "firefox")]
+	icons: Option<std::collections::BTreeMap<u32, String>>,
 }
 
+#[cfg(feature = "context_menus")]
 impl ContextMenuConfigBuilder {
 	pub fn contexts(mut self, contexts: &[&str]) -> Self {
 		self.contexts = contexts.iter().map(|s| s.to_string()).collect();
 		self
 	}
 
+	pub fn item_type(mut self, item_type: ContextMenuItemType) -> Self {
+		self.item_type = Some(item_type);
+		self
+	}
+
+	pub fn checked(mut self, checked: bool) -> Self {
+		self.checked = Some(checked);
+		self
+	}
+
+	pub fn parent_id(mut self, parent_id: impl Into<String>) -> Self {
+		self.parent_id = Some(parent_id.into());
+		self
+	}
+
+	pub fn menu_contexts(mut self, contexts: &[MenuContext]) -> Self {
+		self.contexts = contexts.iter().map(|context| context.as_str().to_owned()).collect();
+		self
+	}
+
+	pub fn visible(mut self, visible: bool) -> Self {
+		self.visible = Some(visible);
+		self
+	}
+
+	/// Icon paths keyed by size in pixels, shown next to the item's title.
+	#[cfg(feature = "firefox")]
+	pub fn icons(mut self, icons: &[(u32, &str)]) -> Self {
+		self.icons = Some(icons.iter().map(|(size, path)| (*size, path.to_string())).collect());
+		self
+	}
+
 	pub fn build(self) -> ContextMenuConfig {
-		ContextMenuConfig { id: self.id, title: self.title, contexts: self.contexts }
+		ContextMenuConfig {
+			id: self.id,
+			title: self.title,
+			contexts: self.contexts,
+			item_type: self.item_type,
+			checked: self.checked,
+			parent_id: self.parent_id,
+			visible: self.visible,
+			#[cfg(feature = "firefox")]
+			icons: self.icons,
+		}
+	}
+}
+
+#[cfg(feature = "context_menus")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextMenuUpdate {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub title: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub contexts: Option<Vec<String>>,
+	#[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+	pub item_type: Option<ContextMenuItemType>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub checked: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub enabled: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub parent_id: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub visible: Option<bool>,
+	#[cfg(feature = "firefox")]
+	#[serde(skip_serializing_if = "Option::is_none", serialize_with = "icon_sizes")]
+	pub icons: Option<std::collections::BTreeMap<u32, String>>,
+}
+
+// `menus` wants a plain `{ "16": "icon.png" }` object, and only string keys can become object keys
+#[cfg(all(feature = "context_menus", feature = "firefox"))]
+fn icon_sizes<S: serde::Serializer>(icons: &Option<std::collections::BTreeMap<u32, String>>, serializer: S) -> Result<S::Ok, S::Error> {
+	match icons {
+		Some(icons) => serializer.collect_map(icons.iter().map(|(size, path)| (size.to_string(), path))),
+		None => serializer.serialize_none(),
 	}
 }
 
+#[cfg(feature = "alarms")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AlarmInfo {
@@ -99,7 +575,9 @@ pub struct AlarmInfo {
 	pub period_in_minutes: Option<f64>,
 }
 
+#[cfg(feature = "alarms")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Alarm {
 	pub name: String,
 	pub scheduled_time: f64,
@@ -107,57 +585,2123 @@ pub struct Alarm {
 	pub period_in_minutes: Option<f64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct UpdateRulesOptions {
-	#[serde(skip_serializing_if = "Vec::is_empty")]
-	pub add_rules: Vec<Rule>,
-	#[serde(skip_serializing_if = "Vec::is_empty")]
-	pub remove_rule_ids: Vec<u32>,
+/// The layout of a notification; Firefox only shows `Basic` ones and falls back to it for the others.
+#[cfg(feature = "notifications")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationTemplate {
+	#[default]
+	Basic,
+	Image,
+	List,
+	Progress,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg(feature = "notifications")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct Rule {
-	pub id: u32,
-	pub priority: u32,
-	pub action: RuleAction,
-	pub condition: RuleCondition,
+pub struct NotificationOptions {
+	#[serde(rename = "type")]
+	pub template: NotificationTemplate,
+	/// Required by Chrome when creating a notification; an extension URL from `runtime.getURL` or a data URL.
+	pub icon_url: String,
+	pub title: String,
+	pub message: String,
+	/// A secondary line under the message, in a lighter font.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub context_message: Option<String>,
+	/// From -2 to 2; Chrome may hide notifications below 0.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub priority: Option<i8>,
+	/// Keeps the notification on screen until the user dismisses or clicks it (Chrome only).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub require_interaction: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub silent: Option<bool>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RuleAction {
-	#[serde(rename = "type")]
-	pub action_type: String,
+#[cfg(feature = "notifications")]
+impl NotificationOptions {
+	pub fn basic(icon_url: impl Into<String>, title: impl Into<String>, message: impl Into<String>) -> Self {
+		Self { icon_url: icon_url.into(), title: title.into(), message: message.into(), ..Default::default() }
+	}
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg(feature = "scripting")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct RuleCondition {
-	pub url_filter: String,
-	pub resource_types: Vec<String>,
+pub struct InjectionTarget {
+	pub tab_id: u32,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub frame_ids: Option<Vec<u32>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub all_frames: Option<bool>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Command {
-	pub name: String,
-	pub description: Option<String>,
-	pub shortcut: Option<String>,
+#[cfg(feature = "scripting")]
+impl InjectionTarget {
+	pub fn tab(tab_id: u32) -> Self {
+		Self { tab_id, ..Default::default() }
+	}
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// The JavaScript context a script runs in.
+#[cfg(feature = "scripting")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum World {
+	/// The extension's own context: shares the DOM with the page but not its globals.
+	#[default]
+	Isolated,
+	/// The page's own context, for scripts that must read or patch page globals. The page can see and tamper with anything running
+	/// here, and extension APIs are not available.
+	Main,
+	/// The context of the `userScripts` API; `executeScript` and `registerContentScripts` reject it.
+	UserScript,
+}
+
+#[cfg(feature = "scripting")]
+pub struct ScriptInjection {
+	target: InjectionTarget,
+	func: Option<(String, String)>,
+	args: Option<JsValue>,
+	files: Vec<String>,
+	inject_immediately: bool,
+	world: Option<World>,
+}
+
+#[cfg(feature = "scripting")]
+impl ScriptInjection {
+	pub fn build(target: InjectionTarget) -> ScriptInjectionBuilder {
+		ScriptInjectionBuilder { target, func: None, args: None, files: vec![], inject_immediately: false, world: None }
+	}
+
+	pub(crate) fn to_js(&self) -> Result<Object, ExtensionError> {
+		let details = Object::new();
+		js_sys::Reflect::set(&details, &"target".into(), &serde_wasm_bindgen::to_value(&self.target)?)?;
+		if let Some((params, body)) = &self.func {
+			js_sys::Reflect::set(&details, &"func".into(), &Function::new_with_args(params, body))?;
+		}
+		if let Some(args) = &self.args {
+			js_sys::Reflect::set(&details, &"args".into(), args)?;
+		}
+		if !self.files.is_empty() {
+			js_sys::Reflect::set(&details, &"files".into(), &serde_wasm_bindgen::to_value(&self.files)?)?;
+		}
+		if self.inject_immediately {
+			js_sys::Reflect::set(&details, &"injectImmediately".into(), &true.into())?;
+		}
+		if let Some(world) = self.world {
+			js_sys::Reflect::set(&details, &"world".into(), &serde_wasm_bindgen::to_value(&world)?)?;
+		}
+		Ok(details)
+	}
+}
+
+#[cfg(feature = "scripting")]
+pub struct ScriptInjectionBuilder {
+	target: InjectionTarget,
+	func: Option<(String, String)>,
+	args: Option<JsValue>,
+	files: Vec<String>,
+	inject_immediately: bool,
+	world: Option<World>,
+}
+
+#[cfg(feature = "scripting")]
+impl ScriptInjectionBuilder {
+	pub fn func(mut self, params: &str, body: &str) -> Self {
+		self.func = Some((params.to_string(), body.to_string()));
+		self
+	}
+
+	pub fn args<A: Serialize>(mut self, args: &A) -> Result<Self, ExtensionError> {
+		self.args = Some(serde_wasm_bindgen::to_value(args)?);
+		Ok(self)
+	}
+
+	pub fn files(mut self, files: &[&str]) -> Self {
+		self.files = files.iter().map(|s| s.to_string()).collect();
+		self
+	}
+
+	pub fn inject_immediately(mut self, inject_immediately: bool) -> Self {
+		self.inject_immediately = inject_immediately;
+		self
+	}
+
+	pub fn world(mut self, world: World) -> Self {
+		self.world = Some(world);
+		self
+	}
+
+	pub fn build(self) -> ScriptInjection {
+		ScriptInjection { target: self.target, func: self.func, args: self.args, files: self.files, inject_immediately: self.inject_immediately, world: self.world }
+	}
+}
+
+#[cfg(feature = "scripting")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CssOrigin {
+	Author,
+	User,
+}
+
+#[cfg(feature = "scripting")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct MessageSender {
-	pub id: Option<String>,
-	pub url: Option<String>,
-	pub tab: Option<TabInfo>,
+pub struct CssInjection {
+	pub target: InjectionTarget,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub css: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub files: Option<Vec<String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub origin: Option<CssOrigin>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[cfg(feature = "scripting")]
+impl CssInjection {
+	pub fn code(target: InjectionTarget, css: impl Into<String>) -> Self {
+		Self { target, css: Some(css.into()), ..Default::default() }
+	}
+
+	pub fn files(target: InjectionTarget, files: &[&str]) -> Self {
+		Self { target, files: Some(files.iter().map(|s| s.to_string()).collect()), ..Default::default() }
+	}
+
+	pub fn origin(mut self, origin: CssOrigin) -> Self {
+		self.origin = Some(origin);
+		self
+	}
+}
+
+#[cfg(feature = "scripting")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RunAt {
+	#[serde(rename = "document_start")]
+	Start,
+	#[serde(rename = "document_end")]
+	End,
+	#[serde(rename = "document_idle")]
+	Idle,
+}
+
+#[cfg(feature = "scripting")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisteredContentScript {
+	pub id: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub matches: Option<Vec<String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub exclude_matches: Option<Vec<String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub js: Option<Vec<String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub css: Option<Vec<String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub all_frames: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub run_at: Option<RunAt>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub persist_across_sessions: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub world: Option<World>,
+}
+
+#[cfg(feature = "scripting")]
+impl RegisteredContentScript {
+	pub fn build(id: impl Into<String>) -> RegisteredContentScriptBuilder {
+		RegisteredContentScriptBuilder {
+			script: Self {
+				id: id.into(),
+				matches: None,
+				exclude_matches: None,
+				js: None,
+				css: None,
+				all_frames: None,
+				run_at: None,
+				persist_across_sessions: None,
+				world: None,
+			},
+		}
+	}
+}
+
+#[cfg(feature = "scripting")]
+pub struct RegisteredContentScriptBuilder {
+	script: RegisteredContentScript,
+}
+
+#[cfg(feature = "scripting")]
+impl RegisteredContentScriptBuilder {
+	pub fn matches(mut self, matches: &[&str]) -> Self {
+		self.script.matches = Some(matches.iter().map(|s| s.to_string()).collect());
+		self
+	}
+
+	pub fn exclude_matches(mut self, exclude_matches: &[&str]) -> Self {
+		self.script.exclude_matches = Some(exclude_matches.iter().map(|s| s.to_string()).collect());
+		self
+	}
+
+	pub fn js(mut self, files: &[&str]) -> Self {
+		self.script.js = Some(files.iter().map(|s| s.to_string()).collect());
+		self
+	}
+
+	pub fn css(mut self, files: &[&str]) -> Self {
+		self.script.css = Some(files.iter().map(|s| s.to_string()).collect());
+		self
+	}
+
+	pub fn all_frames(mut self, all_frames: bool) -> Self {
+		self.script.all_frames = Some(all_frames);
+		self
+	}
+
+	pub fn run_at(mut self, run_at: RunAt) -> Self {
+		self.script.run_at = Some(run_at);
+		self
+	}
+
+	pub fn persist_across_sessions(mut self, persist: bool) -> Self {
+		self.script.persist_across_sessions = Some(persist);
+		self
+	}
+
+	pub fn world(mut self, world: World) -> Self {
+		self.script.world = Some(world);
+		self
+	}
+
+	pub fn build(self) -> RegisteredContentScript {
+		self.script
+	}
+}
+
+#[cfg(feature = "scripting")]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InjectionResult<T> {
+	pub frame_id: u32,
+	pub document_id: Option<String>,
+	pub result: Option<T>,
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRulesOptions {
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub add_rules: Vec<Rule>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub remove_rule_ids: Vec<u32>,
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRulesetOptions {
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub enable_ruleset_ids: Vec<String>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub disable_ruleset_ids: Vec<String>,
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rule {
+	pub id: u32,
+	pub priority: u32,
+	pub action: RuleAction,
+	pub condition: RuleCondition,
+}
+
+#[cfg(feature = "declarative_net_request")]
+impl Rule {
+	pub fn build(id: u32, action: RuleAction) -> RuleBuilder {
+		RuleBuilder { rule: Self { id, priority: 1, action, condition: RuleCondition::default() } }
+	}
+}
+
+#[cfg(feature = "declarative_net_request")]
+pub struct RuleBuilder {
+	rule: Rule,
+}
+
+#[cfg(feature = "declarative_net_request")]
+impl RuleBuilder {
+	pub fn priority(mut self, priority: u32) -> Self {
+		self.rule.priority = priority;
+		self
+	}
+
+	pub fn condition(mut self, condition: RuleCondition) -> Self {
+		self.rule.condition = condition;
+		self
+	}
+
+	pub fn build(self) -> Rule {
+		self.rule
+	}
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RuleActionType {
+	Block,
+	Redirect,
+	Allow,
+	UpgradeScheme,
+	ModifyHeaders,
+	AllowAllRequests,
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleAction {
+	#[serde(rename = "type")]
+	pub action_type: RuleActionType,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub redirect: Option<Redirect>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub request_headers: Option<Vec<ModifyHeaderInfo>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub response_headers: Option<Vec<ModifyHeaderInfo>>,
+}
+
+#[cfg(feature = "declarative_net_request")]
+impl RuleAction {
+	fn of(action_type: RuleActionType) -> Self {
+		Self { action_type, redirect: None, request_headers: None, response_headers: None }
+	}
+
+	pub fn block() -> Self {
+		Self::of(RuleActionType::Block)
+	}
+
+	pub fn allow() -> Self {
+		Self::of(RuleActionType::Allow)
+	}
+
+	pub fn allow_all_requests() -> Self {
+		Self::of(RuleActionType::AllowAllRequests)
+	}
+
+	pub fn upgrade_scheme() -> Self {
+		Self::of(RuleActionType::UpgradeScheme)
+	}
+
+	pub fn redirect(redirect: Redirect) -> Self {
+		Self { redirect: Some(redirect), ..Self::of(RuleActionType::Redirect) }
+	}
+
+	pub fn modify_headers(request_headers: Vec<ModifyHeaderInfo>, response_headers: Vec<ModifyHeaderInfo>) -> Self {
+		Self {
+			request_headers: (!request_headers.is_empty()).then_some(request_headers),
+			response_headers: (!response_headers.is_empty()).then_some(response_headers),
+			..Self::of(RuleActionType::ModifyHeaders)
+		}
+	}
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Redirect {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub url: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub extension_path: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub transform: Option<UrlTransform>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub regex_substitution: Option<String>,
+}
+
+#[cfg(feature = "declarative_net_request")]
+impl Redirect {
+	pub fn url(url: impl Into<String>) -> Self {
+		Self { url: Some(url.into()), ..Default::default() }
+	}
+
+	pub fn extension_path(path: impl Into<String>) -> Self {
+		Self { extension_path: Some(path.into()), ..Default::default() }
+	}
+
+	pub fn transform(transform: UrlTransform) -> Self {
+		Self { transform: Some(transform), ..Default::default() }
+	}
+
+	pub fn regex_substitution(substitution: impl Into<String>) -> Self {
+		Self { regex_substitution: Some(substitution.into()), ..Default::default() }
+	}
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlTransform {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub scheme: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub host: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub port: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub path: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub query: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub query_transform: Option<QueryTransform>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub fragment: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub username: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub password: Option<String>,
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryTransform {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub remove_params: Option<Vec<String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub add_or_replace_params: Option<Vec<QueryKeyValue>>,
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryKeyValue {
+	pub key: String,
+	pub value: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub replace_only: Option<bool>,
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HeaderOperation {
+	Append,
+	Set,
+	Remove,
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModifyHeaderInfo {
+	pub header: String,
+	pub operation: HeaderOperation,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub value: Option<String>,
+}
+
+#[cfg(feature = "declarative_net_request")]
+impl ModifyHeaderInfo {
+	pub fn append(header: impl Into<String>, value: impl Into<String>) -> Self {
+		Self { header: header.into(), operation: HeaderOperation::Append, value: Some(value.into()) }
+	}
+
+	pub fn set(header: impl Into<String>, value: impl Into<String>) -> Self {
+		Self { header: header.into(), operation: HeaderOperation::Set, value: Some(value.into()) }
+	}
+
+	pub fn remove(header: impl Into<String>) -> Self {
+		Self { header: header.into(), operation: HeaderOperation::Remove, value: None }
+	}
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceType {
+	MainFrame,
+	SubFrame,
+	Stylesheet,
+	Script,
+	Image,
+	Font,
+	Object,
+	#[serde(rename = "xmlhttprequest")]
+	XmlHttpRequest,
+	Ping,
+	CspReport,
+	Media,
+	Websocket,
+	Webtransport,
+	Webbundle,
+	Other,
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RequestMethod {
+	Connect,
+	Delete,
+	Get,
+	Head,
+	Options,
+	Patch,
+	Post,
+	Put,
+	Other,
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DomainType {
+	FirstParty,
+	ThirdParty,
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleCondition {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub url_filter: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub regex_filter: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub is_url_filter_case_sensitive: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub initiator_domains: Option<Vec<String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub excluded_initiator_domains: Option<Vec<String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub request_domains: Option<Vec<String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub excluded_request_domains: Option<Vec<String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub resource_types: Option<Vec<ResourceType>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub excluded_resource_types: Option<Vec<ResourceType>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub request_methods: Option<Vec<RequestMethod>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub excluded_request_methods: Option<Vec<RequestMethod>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub tab_ids: Option<Vec<i32>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub excluded_tab_ids: Option<Vec<i32>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub domain_type: Option<DomainType>,
+}
+
+#[cfg(feature = "declarative_net_request")]
+impl RuleCondition {
+	pub fn build() -> RuleConditionBuilder {
+		RuleConditionBuilder { condition: Self::default() }
+	}
+}
+
+#[cfg(feature = "declarative_net_request")]
+pub struct RuleConditionBuilder {
+	condition: RuleCondition,
+}
+
+#[cfg(feature = "declarative_net_request")]
+impl RuleConditionBuilder {
+	pub fn url_filter(mut self, filter: impl Into<String>) -> Self {
+		self.condition.url_filter = Some(filter.into());
+		self
+	}
+
+	pub fn regex_filter(mut self, filter: impl Into<String>) -> Self {
+		self.condition.regex_filter = Some(filter.into());
+		self
+	}
+
+	pub fn case_sensitive(mut self, case_sensitive: bool) -> Self {
+		self.condition.is_url_filter_case_sensitive = Some(case_sensitive);
+		self
+	}
+
+	pub fn initiator_domains(mut self, domains: &[&str]) -> Self {
+		self.condition.initiator_domains = Some(domains.iter().map(|s| s.to_string()).collect());
+		self
+	}
+
+	pub fn excluded_initiator_domains(mut self, domains: &[&str]) -> Self {
+		self.condition.excluded_initiator_domains = Some(domains.iter().map(|s| s.to_string()).collect());
+		self
+	}
+
+	pub fn request_domains(mut self, domains: &[&str]) -> Self {
+		self.condition.request_domains = Some(domains.iter().map(|s| s.to_string()).collect());
+		self
+	}
+
+	pub fn excluded_request_domains(mut self, domains: &[&str]) -> Self {
+		self.condition.excluded_request_domains = Some(domains.iter().map(|s| s.to_string()).collect());
+		self
+	}
+
+	pub fn resource_types(mut self, types: &[ResourceType]) -> Self {
+		self.condition.resource_types = Some(types.to_vec());
+		self
+	}
+
+	pub fn excluded_resource_types(mut self, types: &[ResourceType]) -> Self {
+		self.condition.excluded_resource_types = Some(types.to_vec());
+		self
+	}
+
+	pub fn request_methods(mut self, methods: &[RequestMethod]) -> Self {
+		self.condition.request_methods = Some(methods.to_vec());
+		self
+	}
+
+	pub fn excluded_request_methods(mut self, methods: &[RequestMethod]) -> Self {
+		self.condition.excluded_request_methods = Some(methods.to_vec());
+		self
+	}
+
+	pub fn tab_ids(mut self, tab_ids: &[i32]) -> Self {
+		self.condition.tab_ids = Some(tab_ids.to_vec());
+		self
+	}
+
+	pub fn excluded_tab_ids(mut self, tab_ids: &[i32]) -> Self {
+		self.condition.excluded_tab_ids = Some(tab_ids.to_vec());
+		self
+	}
+
+	pub fn domain_type(mut self, domain_type: DomainType) -> Self {
+		self.condition.domain_type = Some(domain_type);
+		self
+	}
+
+	pub fn build(self) -> RuleCondition {
+		self.condition
+	}
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestRequestDetails {
+	pub url: String,
+	#[serde(rename = "type")]
+	pub resource_type: ResourceType,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub initiator: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub method: Option<RequestMethod>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub tab_id: Option<i32>,
+}
+
+#[cfg(feature = "declarative_net_request")]
+impl TestRequestDetails {
+	pub fn new(url: impl Into<String>, resource_type: ResourceType) -> Self {
+		Self { url: url.into(), resource_type, initiator: None, method: None, tab_id: None }
+	}
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchedRule {
+	pub rule_id: u32,
+	pub ruleset_id: String,
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TestMatchOutcomeResult {
+	pub matched_rules: Vec<MatchedRule>,
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchedRulesFilter {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub tab_id: Option<i32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub min_time_stamp: Option<f64>,
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchedRuleInfo {
+	pub rule: MatchedRule,
+	pub tab_id: i32,
+	pub time_stamp: f64,
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RulesMatchedDetails {
+	pub rules_info: Vec<MatchedRuleInfo>,
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestDetails {
+	pub request_id: String,
+	pub url: String,
+	pub method: String,
+	#[serde(rename = "type")]
+	pub resource_type: ResourceType,
+	pub frame_id: i32,
+	pub parent_frame_id: i32,
+	pub tab_id: i32,
+	pub initiator: Option<String>,
+	pub document_id: Option<String>,
+	pub parent_document_id: Option<String>,
+}
+
+#[cfg(feature = "declarative_net_request")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchedRuleInfoDebug {
+	pub request: RequestDetails,
+	pub rule: MatchedRule,
+}
+
+#[cfg(feature = "commands")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Command {
+	pub name: String,
+	pub description: Option<String>,
+	pub shortcut: Option<String>,
+}
+
+#[cfg(all(feature = "commands", feature = "firefox"))]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandUpdate {
+	pub name: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub description: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub shortcut: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageSender {
+	pub id: Option<String>,
+	pub url: Option<String>,
+	/// Set by the browser rather than the page, so unlike `url` it can't be spoofed from a compromised renderer; prefer it for checks.
+	pub origin: Option<String>,
+	pub tab: Option<TabInfo>,
+	/// Only set when `tab` is; 0 for the top-level document.
+	pub frame_id: Option<i32>,
+	pub document_id: Option<String>,
+	/// `prerender`, `active`, `cached` or `pending_deletion`.
+	pub document_lifecycle: Option<String>,
+	pub tls_channel_id: Option<String>,
+}
+
+#[cfg(feature = "tabs")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageOptions {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub frame_id: Option<i32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub document_id: Option<String>,
+}
+
+#[cfg(feature = "tabs")]
+impl MessageOptions {
+	/// Targets the frame a message came from, so the reply doesn't go to every frame of the tab.
+	pub fn reply_to(sender: &MessageSender) -> Self {
+		Self { frame_id: sender.frame_id, document_id: sender.document_id.clone() }
+	}
+}
+
+/// The senders accepted on `onMessageExternal`/`onConnectExternal`: other extensions by id, and web pages by origin
+/// (e.g. `https://app.example.com`, as listed under `externally_connectable.matches`).
+#[derive(Debug, Default, Clone)]
+pub struct SenderAllowlist {
+	pub extension_ids: Vec<String>,
+	pub origins: Vec<String>,
+}
+
+impl SenderAllowlist {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn extension_id(mut self, id: &str) -> Self {
+		self.extension_ids.push(id.to_owned());
+		self
+	}
+
+	pub fn origin(mut self, origin: &str) -> Self {
+		self.origins.push(origin.trim_end_matches('/').to_owned());
+		self
+	}
+
+	pub fn allows(&self, sender: &MessageSender) -> bool {
+		let origin = sender.origin.clone().or_else(|| sender.url.as_deref().and_then(|url| web_sys::Url::new(url).ok()).map(|url| url.origin()));
+		match origin {
+			// the sender id is the extension's own for pages, so only trust it when the message comes from an extension context
+			Some(origin) if !["chrome-extension:", "moz-extension:", "safari-web-extension:"].iter().any(|scheme| origin.starts_with(scheme)) => {
+				self.origins.contains(&origin)
+			},
+			_ => sender.id.as_ref().is_some_and(|id| self.extension_ids.contains(id)),
+		}
+	}
+}
+
+#[cfg(all(feature = "context_menus", feature = "firefox"))]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MenuShownInfo {
+	pub menu_ids: Vec<String>,
+	pub contexts: Vec<String>,
+	pub editable: bool,
+	pub page_url: Option<String>,
+	pub frame_url: Option<String>,
+	pub link_url: Option<String>,
+	pub src_url: Option<String>,
+	pub selection_text: Option<String>,
+	pub media_type: Option<String>,
+	pub target_element_id: Option<i64>,
+}
+
+#[cfg(feature = "context_menus")]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OnClickData {
 	pub menu_item_id: String,
+	pub parent_menu_item_id: Option<String>,
 	pub page_url: Option<String>,
+	/// 0 for the top-level document.
+	pub frame_id: Option<i32>,
+	pub frame_url: Option<String>,
+	pub link_url: Option<String>,
+	pub src_url: Option<String>,
+	/// `image`, `video` or `audio` when the click was on such an element.
+	pub media_type: Option<String>,
 	pub selection_text: Option<String>,
+	#[serde(default)]
+	pub editable: bool,
+	/// The state of a checkbox or radio item after the click.
+	pub checked: Option<bool>,
+	pub was_checked: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ContextType {
+	Tab,
+	Popup,
+	Background,
+	OffscreenDocument,
+	SidePanel,
+	DeveloperTools,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextFilter {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub context_types: Option<Vec<ContextType>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub context_ids: Option<Vec<String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub tab_ids: Option<Vec<i32>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub window_ids: Option<Vec<i32>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub document_urls: Option<Vec<String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub incognito: Option<bool>,
+}
+
+impl ContextFilter {
+	pub fn of_type(context_type: ContextType) -> Self {
+		Self { context_types: Some(vec![context_type]), ..Default::default() }
+	}
+}
+
+/// An open extension page or worker as reported by `runtime.getContexts`. `tab_id`, `window_id` and `frame_id` are `-1` where they don't apply.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionContext {
+	pub context_type: ContextType,
+	pub context_id: String,
+	pub tab_id: i32,
+	pub window_id: i32,
+	pub frame_id: i32,
+	pub document_id: Option<String>,
+	pub document_url: Option<String>,
+	pub document_origin: Option<String>,
+	pub incognito: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateCheckStatus {
+	/// Checked too often; try again later.
+	Throttled,
+	NoUpdate,
+	UpdateAvailable,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCheck {
+	pub status: UpdateCheckStatus,
+	/// The available version, when `status` is `UpdateAvailable`.
+	pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallReason {
+	Install,
+	Update,
+	/// Chrome itself was updated; Firefox reports this as `BrowserUpdate`.
+	ChromeUpdate,
+	BrowserUpdate,
+	/// A shared module this extension imports was updated.
+	SharedModuleUpdate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledDetails {
+	pub reason: InstallReason,
+	/// The version before the update, when `reason` is `Update`.
+	pub previous_version: Option<String>,
+	/// The updated shared module's id, when `reason` is `SharedModuleUpdate`.
+	pub id: Option<String>,
+	/// Firefox only; set when the extension was installed or updated temporarily, e.g. from `about:debugging`.
+	#[serde(default)]
+	pub temporary: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformInfo {
+	/// `mac`, `win`, `android`, `cros`, `linux` or `openbsd`.
+	pub os: String,
+	/// `arm`, `arm64`, `x86-32`, `x86-64`, `mips` or `mips64`.
+	pub arch: String,
+}
+
+#[cfg(feature = "extension")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ViewType {
+	Tab,
+	Popup,
+}
+
+#[cfg(feature = "extension")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewFilter {
+	#[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+	pub view_type: Option<ViewType>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub window_id: Option<i32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub tab_id: Option<i32>,
+}
+
+#[cfg(feature = "i18n")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedLanguages {
+	/// Whether the detector is confident enough in the result to act on it.
+	pub is_reliable: bool,
+	pub languages: Vec<DetectedLanguage>,
+}
+
+#[cfg(feature = "i18n")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectedLanguage {
+	/// An ISO language code such as `en` or `zh-TW`, or `und` when unknown.
+	pub language: String,
+	/// The share of the text in this language, 0 to 100.
+	pub percentage: u8,
+}
+
+#[cfg(feature = "reading_list")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingListEntry {
+	pub url: String,
+	pub title: String,
+	pub has_been_read: bool,
+	pub creation_time: f64,
+	pub last_update_time: f64,
+}
+
+#[cfg(feature = "reading_list")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingListEntryOptions {
+	pub url: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub title: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub has_been_read: Option<bool>,
+}
+
+#[cfg(feature = "reading_list")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingListQuery {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub url: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub title: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub has_been_read: Option<bool>,
+}
+
+#[cfg(any(feature = "font_settings", feature = "firefox"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LevelOfControl {
+	NotControllable,
+	ControlledByOtherExtensions,
+	ControllableByThisExtension,
+	ControlledByThisExtension,
+}
+
+#[cfg(any(feature = "font_settings", feature = "firefox"))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingValue<T> {
+	pub value: T,
+	pub level_of_control: LevelOfControl,
+	pub incognito_specific: Option<bool>,
+}
+
+#[cfg(feature = "font_settings")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GenericFamily {
+	Standard,
+	SansSerif,
+	Serif,
+	Fixed,
+	Cursive,
+	Fantasy,
+	Math,
+}
+
+#[cfg(feature = "font_settings")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FontName {
+	pub font_id: String,
+	pub display_name: String,
+}
+
+#[cfg(feature = "firefox")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContextMenuShowEvent {
+	Mouseup,
+	Mousedown,
+}
+
+#[cfg(feature = "firefox")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageAnimationBehavior {
+	Normal,
+	None,
+	Once,
+}
+
+#[cfg(feature = "firefox")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NewTabPosition {
+	AfterCurrent,
+	RelatedAfterCurrent,
+	AtEnd,
+}
+
+#[cfg(feature = "firefox")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OverrideDocumentColors {
+	Never,
+	Always,
+	HighContrastOnly,
+}
+
+#[cfg(feature = "firefox")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorScheme {
+	Light,
+	Dark,
+	Auto,
+}
+
+#[cfg(any(feature = "tts", feature = "tts_engine"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TtsEventType {
+	Start,
+	End,
+	Word,
+	Sentence,
+	Marker,
+	Interrupted,
+	Cancelled,
+	Error,
+	Pause,
+	Resume,
+}
+
+#[cfg(any(feature = "tts", feature = "tts_engine"))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TtsEvent {
+	#[serde(rename = "type")]
+	pub event_type: TtsEventType,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub char_index: Option<u32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub length: Option<u32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error_message: Option<String>,
+}
+
+#[cfg(any(feature = "tts", feature = "tts_engine"))]
+impl TtsEvent {
+	pub fn new(event_type: TtsEventType) -> Self {
+		Self { event_type, char_index: None, length: None, error_message: None }
+	}
+
+	/// Whether no further events follow for the utterance.
+	pub fn is_final(&self) -> bool {
+		matches!(self.event_type, TtsEventType::End | TtsEventType::Interrupted | TtsEventType::Cancelled | TtsEventType::Error)
+	}
+}
+
+#[cfg(any(feature = "tts", feature = "tts_engine"))]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TtsOptions {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub lang: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub voice_name: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub extension_id: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub rate: Option<f64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub pitch: Option<f64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub volume: Option<f64>,
+	/// Queue behind whatever is already speaking instead of interrupting it.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub enqueue: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub required_event_types: Option<Vec<TtsEventType>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub desired_event_types: Option<Vec<TtsEventType>>,
+}
+
+#[cfg(any(feature = "tts", feature = "tts_engine"))]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TtsVoice {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub voice_name: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub lang: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub remote: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub extension_id: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub event_types: Option<Vec<TtsEventType>>,
+}
+
+#[cfg(feature = "system_cpu")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CpuInfo {
+	pub num_of_processors: u32,
+	pub arch_name: String,
+	pub model_name: String,
+	pub features: Vec<String>,
+	pub processors: Vec<ProcessorInfo>,
+	/// Degrees Celsius, where the platform reports them.
+	#[serde(default)]
+	pub temperatures: Vec<f64>,
+}
+
+#[cfg(feature = "system_cpu")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessorInfo {
+	pub usage: CpuTime,
+}
+
+#[cfg(feature = "system_cpu")]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CpuTime {
+	pub user: f64,
+	pub kernel: f64,
+	pub idle: f64,
+	pub total: f64,
+}
+
+#[cfg(feature = "system_memory")]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryInfo {
+	/// Bytes.
+	pub capacity: f64,
+	/// Bytes.
+	pub available_capacity: f64,
+}
+
+#[cfg(feature = "system_display")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayBounds {
+	pub left: i32,
+	pub top: i32,
+	pub width: i32,
+	pub height: i32,
+}
+
+#[cfg(feature = "system_display")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayInsets {
+	pub left: i32,
+	pub top: i32,
+	pub right: i32,
+	pub bottom: i32,
+}
+
+#[cfg(feature = "system_display")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayUnitInfo {
+	pub id: String,
+	pub name: String,
+	pub is_primary: bool,
+	pub is_internal: bool,
+	pub is_enabled: bool,
+	#[serde(default)]
+	pub mirroring_source_id: String,
+	pub dpi_x: f64,
+	pub dpi_y: f64,
+	pub rotation: i32,
+	pub bounds: DisplayBounds,
+	pub overscan: DisplayInsets,
+	/// The usable area within `bounds`, excluding OS shelves and task bars.
+	pub work_area: DisplayBounds,
+}
+
+#[cfg(feature = "system_display")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LayoutPosition {
+	Top,
+	Right,
+	Bottom,
+	Left,
+}
+
+#[cfg(feature = "system_display")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayLayout {
+	pub id: String,
+	pub parent_id: String,
+	pub position: LayoutPosition,
+	pub offset: i32,
+}
+
+#[cfg(any(feature = "printing", feature = "printing_metrics"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PrinterSource {
+	User,
+	Policy,
+}
+
+#[cfg(feature = "printing")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Printer {
+	pub id: String,
+	pub name: String,
+	pub description: String,
+	pub uri: String,
+	pub source: PrinterSource,
+	pub is_default: bool,
+	pub recently_used_rank: Option<u32>,
+}
+
+#[cfg(any(feature = "printing", feature = "printing_metrics"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PrinterStatus {
+	DoorOpen,
+	TrayMissing,
+	OutOfInk,
+	OutOfPaper,
+	OutputFull,
+	PaperJam,
+	GenericIssue,
+	Stopped,
+	Unreachable,
+	ExpiredCertificate,
+	Available,
+}
+
+#[cfg(feature = "printing")]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrinterInfo {
+	/// The printer's capabilities in Cloud Device Description format, left untyped as it is vendor-extensible.
+	#[serde(with = "serde_wasm_bindgen::preserve", default = "JsValue::undefined")]
+	pub capabilities: JsValue,
+	pub status: PrinterStatus,
+}
+
+#[cfg(feature = "printing")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum JobStatus {
+	Pending,
+	InProgress,
+	Failed,
+	Canceled,
+	Printed,
+}
+
+#[cfg(feature = "printing")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SubmitJobStatus {
+	Ok,
+	UserRejected,
+}
+
+#[cfg(feature = "printing")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitJobResponse {
+	pub status: SubmitJobStatus,
+	pub job_id: Option<String>,
+}
+
+/// A Cloud Job Ticket; the printer must support every option that is set. Field names are snake_case as in the CJT format itself.
+#[cfg(feature = "printing")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrintTicket {
+	pub version: String,
+	pub print: PrintTicketSection,
+}
+
+#[cfg(feature = "printing")]
+impl Default for PrintTicket {
+	fn default() -> Self {
+		Self { version: "1.0".to_owned(), print: PrintTicketSection::default() }
+	}
+}
+
+#[cfg(feature = "printing")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct PrintTicketSection {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub color: Option<TicketType<ColorType>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub duplex: Option<TicketType<DuplexType>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub page_orientation: Option<TicketType<PageOrientation>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub copies: Option<TicketCopies>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub dpi: Option<TicketDpi>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub media_size: Option<TicketMediaSize>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub collate: Option<TicketCollate>,
+}
+
+#[cfg(feature = "printing")]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TicketType<T> {
+	#[serde(rename = "type")]
+	pub value: T,
+}
+
+#[cfg(feature = "printing")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ColorType {
+	StandardColor,
+	StandardMonochrome,
+}
+
+#[cfg(feature = "printing")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DuplexType {
+	NoDuplex,
+	LongEdge,
+	ShortEdge,
+}
+
+#[cfg(feature = "printing")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PageOrientation {
+	Portrait,
+	Landscape,
+	Auto,
+}
+
+#[cfg(feature = "printing")]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TicketCopies {
+	pub copies: u32,
+}
+
+#[cfg(feature = "printing")]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TicketDpi {
+	pub horizontal_dpi: u32,
+	pub vertical_dpi: u32,
+}
+
+#[cfg(feature = "printing")]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TicketMediaSize {
+	pub width_microns: u32,
+	pub height_microns: u32,
+}
+
+#[cfg(feature = "printing")]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TicketCollate {
+	pub collate: bool,
+}
+
+#[cfg(feature = "printing_metrics")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PrintJobSource {
+	PrintPreview,
+	AndroidApp,
+	Extension,
+	IsolatedWebApp,
+}
+
+#[cfg(feature = "printing_metrics")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PrintJobStatus {
+	Failed,
+	Canceled,
+	Printed,
+}
+
+#[cfg(feature = "printing_metrics")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrintJobPrinter {
+	pub name: String,
+	pub uri: String,
+	pub source: PrinterSource,
+}
+
+#[cfg(feature = "printing_metrics")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrintJobInfo {
+	pub id: String,
+	pub title: String,
+	pub source: PrintJobSource,
+	pub source_id: Option<String>,
+	pub status: PrintJobStatus,
+	pub creation_time: f64,
+	pub completion_time: f64,
+	pub printer: PrintJobPrinter,
+	pub printer_status: PrinterStatus,
+	pub number_of_pages: u32,
+}
+
+#[cfg(feature = "tab_capture")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TabCaptureState {
+	Pending,
+	Active,
+	Stopped,
+	Error,
+}
+
+#[cfg(feature = "tab_capture")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureInfo {
+	pub tab_id: u32,
+	pub status: TabCaptureState,
+	pub fullscreen: bool,
+}
+
+#[cfg(feature = "tab_capture")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetMediaStreamOptions {
+	/// The tab that will consume the stream; defaults to the caller, so set it when a service worker requests an id for a page to use.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub consumer_tab_id: Option<u32>,
+	/// The tab to capture; defaults to the active tab.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub target_tab_id: Option<u32>,
+}
+
+#[cfg(feature = "declarative_content")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlFilter {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub host_equals: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub host_contains: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub host_prefix: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub host_suffix: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub path_equals: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub path_contains: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub path_prefix: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub path_suffix: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub query_contains: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub url_equals: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub url_contains: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub url_prefix: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub url_suffix: Option<String>,
+	/// RE2 syntax.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub url_matches: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub schemes: Option<Vec<String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub ports: Option<Vec<u16>>,
+}
+
+#[cfg(feature = "declarative_content")]
+impl UrlFilter {
+	pub fn host_equals(host: &str) -> Self {
+		Self { host_equals: Some(host.to_owned()), ..Default::default() }
+	}
+
+	pub fn host_suffix(suffix: &str) -> Self {
+		Self { host_suffix: Some(suffix.to_owned()), ..Default::default() }
+	}
+
+	pub fn url_prefix(prefix: &str) -> Self {
+		Self { url_prefix: Some(prefix.to_owned()), ..Default::default() }
+	}
+}
+
+#[cfg(feature = "declarative_content")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageStateMatcher {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub page_url: Option<UrlFilter>,
+	/// Matches when every selector here matches an element of the page; only compound selectors are supported.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub css: Option<Vec<String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub is_bookmarked: Option<bool>,
+}
+
+#[cfg(feature = "declarative_content")]
+impl PageStateMatcher {
+	pub fn page_url(filter: UrlFilter) -> Self {
+		Self { page_url: Some(filter), ..Default::default() }
+	}
+
+	pub fn css(mut self, selectors: &[&str]) -> Self {
+		self.css = Some(selectors.iter().map(ToString::to_string).collect());
+		self
+	}
+}
+
+#[cfg(feature = "declarative_content")]
+#[derive(Debug, Clone)]
+pub enum ContentAction {
+	/// Enables the action icon on matching pages; pair with `action.disable()` so it stays disabled everywhere else.
+	ShowAction,
+	RequestContentScript {
+		js: Vec<String>,
+		css: Vec<String>,
+		all_frames: bool,
+		match_about_blank: bool,
+	},
+}
+
+#[cfg(feature = "declarative_content")]
+#[derive(Debug, Clone)]
+pub struct ContentRule {
+	pub id: Option<String>,
+	pub priority: Option<u32>,
+	pub conditions: Vec<PageStateMatcher>,
+	pub actions: Vec<ContentAction>,
+}
+
+#[cfg(feature = "declarative_content")]
+impl ContentRule {
+	pub fn show_action_when(conditions: Vec<PageStateMatcher>) -> Self {
+		Self { id: None, priority: None, conditions, actions: vec![ContentAction::ShowAction] }
+	}
+
+	pub fn id(mut self, id: &str) -> Self {
+		self.id = Some(id.to_owned());
+		self
+	}
+}
+
+#[cfg(feature = "cookies")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SameSiteStatus {
+	NoRestriction,
+	Lax,
+	Strict,
+	Unspecified,
+}
+
+/// Identifies the partition of a CHIPS (`Partitioned`) cookie: the top-level site it was set under.
+#[cfg(feature = "cookies")]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CookiePartitionKey {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub top_level_site: Option<String>,
+	/// Chrome only.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub has_cross_site_ancestor: Option<bool>,
+}
+
+#[cfg(feature = "cookies")]
+impl CookiePartitionKey {
+	pub fn top_level_site(site: &str) -> Self {
+		Self { top_level_site: Some(site.to_owned()), ..Default::default() }
+	}
+}
+
+#[cfg(feature = "cookies")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cookie {
+	pub name: String,
+	pub value: String,
+	pub domain: String,
+	pub host_only: bool,
+	pub path: String,
+	pub secure: bool,
+	pub http_only: bool,
+	pub same_site: SameSiteStatus,
+	pub session: bool,
+	/// Seconds since the epoch; absent for session cookies.
+	pub expiration_date: Option<f64>,
+	pub store_id: String,
+	pub partition_key: Option<CookiePartitionKey>,
+}
+
+#[cfg(feature = "cookies")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CookieDetails {
+	pub url: String,
+	pub name: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub store_id: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub partition_key: Option<CookiePartitionKey>,
+}
+
+#[cfg(feature = "cookies")]
+impl CookieDetails {
+	pub fn new(url: &str, name: &str) -> Self {
+		Self { url: url.to_owned(), name: name.to_owned(), store_id: None, partition_key: None }
+	}
+
+	pub fn partition_key(mut self, partition_key: CookiePartitionKey) -> Self {
+		self.partition_key = Some(partition_key);
+		self
+	}
+}
+
+#[cfg(feature = "cookies")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CookieQuery {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub url: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub domain: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub path: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub secure: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub session: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub store_id: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub partition_key: Option<CookiePartitionKey>,
+}
+
+#[cfg(feature = "cookies")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetCookieDetails {
+	pub url: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub value: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub domain: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub path: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub secure: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub http_only: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub same_site: Option<SameSiteStatus>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub expiration_date: Option<f64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub store_id: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub partition_key: Option<CookiePartitionKey>,
+}
+
+#[cfg(feature = "cookies")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CookieChangeCause {
+	/// Removed by garbage collection.
+	Evicted,
+	Expired,
+	/// Set or removed by an extension, a page script or a response header.
+	Explicit,
+	/// Overwritten with an already expired date.
+	ExpiredOverwrite,
+	Overwrite,
+}
+
+#[cfg(feature = "cookies")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CookieChangeInfo {
+	pub removed: bool,
+	pub cookie: Cookie,
+	pub cause: CookieChangeCause,
+}
+
+#[cfg(feature = "downloads")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadState {
+	InProgress,
+	Interrupted,
+	Complete,
+}
+
+#[cfg(feature = "downloads")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilenameConflictAction {
+	Uniquify,
+	Overwrite,
+	Prompt,
+}
+
+#[cfg(feature = "downloads")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadItem {
+	pub id: u32,
+	pub url: String,
+	pub final_url: Option<String>,
+	#[serde(default)]
+	pub referrer: Option<String>,
+	/// Absolute local path.
+	pub filename: String,
+	#[serde(default)]
+	pub incognito: bool,
+	#[serde(default)]
+	pub mime: Option<String>,
+	/// ISO 8601.
+	pub start_time: String,
+	pub end_time: Option<String>,
+	pub estimated_end_time: Option<String>,
+	pub state: DownloadState,
+	pub paused: bool,
+	#[serde(default)]
+	pub can_resume: bool,
+	/// Why the download was interrupted, e.g. `NETWORK_FAILED` or `USER_CANCELED`.
+	pub error: Option<String>,
+	pub bytes_received: f64,
+	/// `-1` when the size isn't known.
+	pub total_bytes: f64,
+	pub file_size: f64,
+	#[serde(default)]
+	pub exists: bool,
+	pub by_extension_id: Option<String>,
+}
+
+#[cfg(feature = "downloads")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadOptions {
+	pub url: String,
+	/// Relative to the downloads directory.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub filename: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub conflict_action: Option<FilenameConflictAction>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub save_as: Option<bool>,
+}
+
+#[cfg(feature = "downloads")]
+impl DownloadOptions {
+	pub fn new(url: &str) -> Self {
+		Self { url: url.to_owned(), ..Default::default() }
+	}
+
+	pub fn filename(mut self, filename: &str) -> Self {
+		self.filename = Some(filename.to_owned());
+		self
+	}
+
+	pub fn conflict_action(mut self, conflict_action: FilenameConflictAction) -> Self {
+		self.conflict_action = Some(conflict_action);
+		self
+	}
+
+	pub fn save_as(mut self, save_as: bool) -> Self {
+		self.save_as = Some(save_as);
+		self
+	}
+}
+
+#[cfg(feature = "downloads")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadQuery {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub id: Option<u32>,
+	/// Search terms, each of which must appear in the url or filename; a leading `-` excludes the term instead.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub query: Option<Vec<String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub url: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub state: Option<DownloadState>,
+	/// Field names to sort by, prefixed with `-` for descending, e.g. `-startTime`.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub order_by: Option<Vec<String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub limit: Option<u32>,
+}
+
+/// The previous and current value of one changed field.
+#[cfg(feature = "downloads")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Delta<T> {
+	pub previous: Option<T>,
+	pub current: Option<T>,
+}
+
+/// Only the fields that changed are set.
+#[cfg(feature = "downloads")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadDelta {
+	pub id: u32,
+	pub url: Option<Delta<String>>,
+	pub final_url: Option<Delta<String>>,
+	pub filename: Option<Delta<String>>,
+	pub mime: Option<Delta<String>>,
+	pub start_time: Option<Delta<String>>,
+	pub end_time: Option<Delta<String>>,
+	pub state: Option<Delta<DownloadState>>,
+	pub paused: Option<Delta<bool>>,
+	pub can_resume: Option<Delta<bool>>,
+	pub error: Option<Delta<String>>,
+	pub total_bytes: Option<Delta<f64>>,
+	pub file_size: Option<Delta<f64>>,
+	pub exists: Option<Delta<bool>>,
+}
+
+#[cfg(feature = "downloads")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadProgress {
+	pub id: u32,
+	pub bytes_received: f64,
+	/// `None` while the size isn't known.
+	pub total_bytes: Option<f64>,
+	pub state: DownloadState,
+	pub paused: bool,
+	pub error: Option<String>,
+}
+
+#[cfg(feature = "downloads")]
+impl DownloadProgress {
+	/// Between 0 and 1, or `None` for indeterminate progress.
+	pub fn fraction(&self) -> Option<f64> {
+		self.total_bytes.filter(|total| *total > 0.0).map(|total| (self.bytes_received / total).clamp(0.0, 1.0))
+	}
+}
+
+#[cfg(feature = "downloads")]
+impl From<&DownloadItem> for DownloadProgress {
+	fn from(item: &DownloadItem) -> Self {
+		Self {
+			id: item.id,
+			bytes_received: item.bytes_received,
+			total_bytes: (item.total_bytes >= 0.0).then_some(item.total_bytes),
+			state: item.state,
+			paused: item.paused,
+			error: item.error.clone(),
+		}
+	}
+}
+
+#[cfg(feature = "gcm")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GcmMessage {
+	/// The sender id with `@gcm.googleapis.com` appended.
+	pub destination_id: String,
+	/// Unique per message, so `onSendError` can be matched up with it.
+	pub message_id: String,
+	/// Seconds the server may hold the message while the destination is offline; `0` means deliver now or drop.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub time_to_live: Option<u32>,
+	pub data: std::collections::BTreeMap<String, String>,
+}
+
+#[cfg(feature = "gcm")]
+impl GcmMessage {
+	/// `gcm.MAX_MESSAGE_SIZE`: the limit on the keys and values of `data` taken together, in bytes.
+	pub const MAX_MESSAGE_SIZE: usize = 4096;
+	pub const MAX_TIME_TO_LIVE: u32 = 2_419_200;
+
+	pub fn new(destination_id: &str, message_id: &str) -> Self {
+		Self { destination_id: destination_id.to_owned(), message_id: message_id.to_owned(), time_to_live: None, data: Default::default() }
+	}
+
+	pub fn time_to_live(mut self, seconds: u32) -> Self {
+		self.time_to_live = Some(seconds);
+		self
+	}
+
+	pub fn data(mut self, key: &str, value: &str) -> Self {
+		self.data.insert(key.to_owned(), value.to_owned());
+		self
+	}
+
+	/// The checks Chrome applies when sending, so a bad payload fails before it reaches the browser.
+	pub fn validate(&self) -> Result<(), crate::error::GcmPayloadError> {
+		use crate::error::GcmPayloadError;
+		if let Some(key) = self.data.keys().find(|key| {
+			let lower = key.to_ascii_lowercase();
+			lower.starts_with("goog.") || lower.starts_with("google") || key.starts_with("collapse_key")
+		}) {
+			return Err(GcmPayloadError::ReservedKey(key.clone()));
+		}
+		let size = self.data.iter().map(|(key, value)| key.len() + value.len()).sum::<usize>();
+		if size > Self::MAX_MESSAGE_SIZE {
+			return Err(GcmPayloadError::TooLarge { size, limit: Self::MAX_MESSAGE_SIZE });
+		}
+		match self.time_to_live {
+			Some(ttl) if ttl > Self::MAX_TIME_TO_LIVE => Err(GcmPayloadError::TimeToLiveTooLong(ttl)),
+			_ => Ok(()),
+		}
+	}
+}
+
+#[cfg(feature = "gcm")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomingGcmMessage {
+	pub data: std::collections::BTreeMap<String, String>,
+	pub from: Option<String>,
+	pub collapse_key: Option<String>,
+}
+
+#[cfg(feature = "gcm")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GcmSendError {
+	pub error_message: String,
+	pub message_id: Option<String>,
+	#[serde(default)]
+	pub details: std::collections::BTreeMap<String, String>,
+}
+
+#[cfg(feature = "gcm")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GcmTokenOptions {
+	pub authorized_entity: String,
+	pub scope: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub options: Option<std::collections::BTreeMap<String, String>>,
+}
+
+#[cfg(feature = "gcm")]
+impl GcmTokenOptions {
+	pub fn new(authorized_entity: &str, scope: &str) -> Self {
+		Self { authorized_entity: authorized_entity.to_owned(), scope: scope.to_owned(), options: None }
+	}
 }
@@ -1,6 +1,7 @@
 use crate::error::ExtensionError;
 use js_sys::{Function, Object, Promise, Reflect};
 use serde::de::DeserializeOwned;
+use std::{cell::OnceCell, time::Duration};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::JsFuture;
 
@@ -11,10 +12,74 @@ pub fn get_api_namespace(root: &JsValue, name: &str) -> Result<Object, Extension
 		.map_err(|_| ExtensionError::ApiNotFound(name.to_string()))
 }
 
+// for the accessors that keep panicking where their `try_` variant returns the error
+#[track_caller]
+pub(crate) fn expect_namespace<T>(namespace: Result<T, ExtensionError>) -> T {
+	namespace.unwrap_or_else(|e| panic!("{e}"))
+}
+
+thread_local! {
+	static CALLBACKS: OnceCell<bool> = const { OnceCell::new() };
+}
+
+// `browser` (Firefox, Safari) returns promises everywhere and takes no callbacks; `chrome` takes a callback everywhere, but only
+// returns promises from MV3 on, and not from every method there
+fn takes_callbacks() -> bool {
+	CALLBACKS.with(|callbacks| {
+		*callbacks.get_or_init(|| {
+			let browser = Reflect::get(&js_sys::global(), &"browser".into()).unwrap_or_default();
+			!Reflect::get(&browser, &"runtime".into()).is_ok_and(|runtime| runtime.is_object())
+		})
+	})
+}
+
 pub async fn call_async_fn(api: &Object, method: &str, args: &[JsValue]) -> Result<JsValue, ExtensionError> {
+	if takes_callbacks() {
+		return call_with_callback(api, method, args).await;
+	}
 	let func: Function = Reflect::get(api, &method.into())?.dyn_into()?;
 	let js_args = args.iter().cloned().collect::<js_sys::Array>();
-	let promise: Promise = func.apply(&api.into(), &js_args)?.dyn_into()?;
+	// `Promise.resolve` adopts promises from other realms and wraps the plain values returned by non-promisified methods; a
+	// failure rejects the promise, as `lastError` is only set for callbacks
+	let promise = Promise::resolve(&func.apply(&api.into(), &js_args)?);
+	JsFuture::from(promise).await.map_err(Into::into)
+}
+
+// the browser takes maps such as menu icons and GCM payloads as plain objects, not the `Map`s `to_value` makes of them
+#[cfg(any(feature = "context_menus", feature = "gcm"))]
+pub fn to_object_value<T: serde::Serialize + ?Sized>(value: &T) -> Result<JsValue, ExtensionError> {
+	Ok(value.serialize(&serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true))?)
+}
+
+pub async fn sleep(duration: Duration) {
+	let promise = Promise::new(&mut |resolve, _| {
+		if let Ok(set_timeout) = Reflect::get(&js_sys::global(), &"setTimeout".into()).and_then(|v| v.dyn_into::<Function>()) {
+			let _ = set_timeout.call2(&JsValue::UNDEFINED, &resolve, &JsValue::from_f64(duration.as_millis() as f64));
+		}
+	});
+	let _ = JsFuture::from(promise).await;
+}
+
+// settles with whatever comes first: the callback, or the promise a method returns instead of calling it; anything else it returns,
+// such as the id from `contextMenus.create`, is ignored in favour of the callback
+pub async fn call_with_callback(api: &Object, method: &str, args: &[JsValue]) -> Result<JsValue, ExtensionError> {
+	let func: Function = Reflect::get(api, &method.into())?.dyn_into()?;
+	let promise = Promise::new(&mut |resolve, reject| {
+		let (on_resolve, on_reject) = (resolve.clone(), reject.clone());
+		// `lastError` is only populated while the callback runs, so it has to be read from inside it
+		let callback = Closure::once_into_js(move |value: JsValue| {
+			let _ = match last_error_value() {
+				Some(err) => on_reject.call1(&JsValue::UNDEFINED, &err),
+				None => on_resolve.call1(&JsValue::UNDEFINED, &value),
+			};
+		});
+		let js_args = args.iter().cloned().chain(std::iter::once(callback)).collect::<js_sys::Array>();
+		let _ = match func.apply(&api.into(), &js_args) {
+			Ok(returned) if is_thenable(&returned) => resolve.call1(&JsValue::UNDEFINED, &returned),
+			Ok(_) => Ok(JsValue::UNDEFINED),
+			Err(err) => reject.call1(&JsValue::UNDEFINED, &err),
+		};
+	});
 	JsFuture::from(promise).await.map_err(Into::into)
 }
 
@@ -22,3 +87,28 @@ pub async fn call_async_fn_and_de<T: DeserializeOwned>(api: &Object, method: &st
 	let result = call_async_fn(api, method, args).await?;
 	serde_wasm_bindgen::from_value(result).map_err(Into::into)
 }
+
+#[cfg(any(feature = "action", feature = "side_panel"))]
+pub fn tab_details(tab_id: Option<u32>) -> Result<Object, ExtensionError> {
+	let details = Object::new();
+	if let Some(id) = tab_id {
+		Reflect::set(&details, &"tabId".into(), &id.into())?;
+	}
+	Ok(details)
+}
+
+fn is_thenable(value: &JsValue) -> bool {
+	value.is_object() && Reflect::get(value, &"then".into()).is_ok_and(|then| then.is_function())
+}
+
+pub(crate) fn last_error_value() -> Option<JsValue> {
+	let global = js_sys::global();
+	["chrome", "browser"].into_iter().find_map(|root| {
+		let runtime = Reflect::get(&Reflect::get(&global, &root.into()).ok()?, &"runtime".into()).ok()?;
+		Reflect::get(&runtime, &"lastError".into()).ok().filter(|err| !err.is_undefined() && !err.is_null())
+	})
+}
+
+pub fn last_error() -> Option<ExtensionError> {
+	last_error_value().map(Into::into)
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "declarative_net_request")]
+
+use serde_json::{Value, json};
+use webext_api::types::{ModifyHeaderInfo, RequestMethod, ResourceType, Rule, RuleAction, RuleCondition};
+
+// rules in the shape of Chrome's `declarativeNetRequest` documentation have to read into the model and write back unchanged
+fn round_trip(rule: &Value) -> Rule {
+	let parsed: Rule = serde_json::from_value(rule.clone()).unwrap();
+	assert_eq!(&serde_json::to_value(&parsed).unwrap(), rule);
+	parsed
+}
+
+#[test]
+fn block_rules_with_domain_and_resource_conditions() {
+	let rule = round_trip(&json!({
+		"id": 1,
+		"priority": 1,
+		"action": { "type": "block" },
+		"condition": { "urlFilter": "abc", "initiatorDomains": ["foo.com"], "resourceTypes": ["script"] },
+	}));
+	let built = Rule::build(1, RuleAction::block())
+		.condition(RuleCondition::build().url_filter("abc").initiator_domains(&["foo.com"]).resource_types(&[ResourceType::Script]).build())
+		.build();
+	assert_eq!(rule, built);
+}
+
+#[test]
+fn redirects_by_regex_substitution_and_transform() {
+	round_trip(&json!({
+		"id": 1,
+		"priority": 1,
+		"action": { "type": "redirect", "redirect": { "regexSubstitution": "https://\\1.xyz.com/" } },
+		"condition": { "regexFilter": "^https://www\\.(abc|def)\\.xyz\\.com/", "resourceTypes": ["main_frame"] },
+	}));
+	round_trip(&json!({
+		"id": 2,
+		"priority": 1,
+		"action": {
+			"type": "redirect",
+			"redirect": {
+				"transform": {
+					"scheme": "https",
+					"host": "new.example.com",
+					"queryTransform": { "removeParams": ["utm_source"], "addOrReplaceParams": [{ "key": "ref", "value": "ext", "replaceOnly": true }] },
+				},
+			},
+		},
+		"condition": { "urlFilter": "||original.example.com/path", "resourceTypes": ["main_frame", "sub_frame"] },
+	}));
+}
+
+#[test]
+fn header_modifications() {
+	let rule = round_trip(&json!({
+		"id": 3,
+		"priority": 2,
+		"action": {
+			"type": "modifyHeaders",
+			"requestHeaders": [{ "header": "user-agent", "operation": "set", "value": "dx-ext" }],
+			"responseHeaders": [{ "header": "set-cookie", "operation": "remove" }, { "header": "vary", "operation": "append", "value": "origin" }],
+		},
+		"condition": { "urlFilter": "|https://example.com", "resourceTypes": ["xmlhttprequest"], "requestMethods": ["post"], "domainType": "thirdParty" },
+	}));
+	assert_eq!(
+		rule.action,
+		RuleAction::modify_headers(
+			vec![ModifyHeaderInfo::set("user-agent", "dx-ext")],
+			vec![ModifyHeaderInfo::remove("set-cookie"), ModifyHeaderInfo::append("vary", "origin")]
+		)
+	);
+	assert_eq!(rule.condition.request_methods, Some(vec![RequestMethod::Post]));
+}
+
+#[test]
+fn scheme_upgrades_and_conditions_without_a_url_filter() {
+	let rule = round_trip(&json!({
+		"id": 4,
+		"priority": 1,
+		"action": { "type": "upgradeScheme" },
+		"condition": { "requestDomains": ["example.com"], "excludedTabIds": [-1] },
+	}));
+	assert_eq!(rule.condition.url_filter, None);
+	round_trip(&json!({ "id": 5, "priority": 3, "action": { "type": "allowAllRequests" }, "condition": { "resourceTypes": ["main_frame"] } }));
+}
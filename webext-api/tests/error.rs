@@ -0,0 +1,35 @@
+use webext_api::error::{ErrorKind, ExtensionError};
+
+fn kind(message: &str) -> ErrorKind {
+	ExtensionError::ApiError(message.to_owned()).kind()
+}
+
+#[test]
+fn browser_messages_map_to_their_kind() {
+	let cases = [
+		// Chrome
+		("The message port closed before a response was received.", ErrorKind::MessagePortClosed),
+		(
+			"A listener indicated an asynchronous response by returning true, but the message channel closed before a response was received",
+			ErrorKind::MessagePortClosed,
+		),
+		("Could not establish connection. Receiving end does not exist.", ErrorKind::NoReceivingEnd),
+		("QUOTA_BYTES quota exceeded", ErrorKind::QuotaExceeded),
+		("MAX_WRITE_OPERATIONS_PER_MINUTE quota exceeded", ErrorKind::QuotaExceeded),
+		("Cannot access contents of url \"https://example.com/\". Extension manifest must request permission to access this host.", ErrorKind::PermissionMissing),
+		("Extension context invalidated.", ErrorKind::ContextInvalidated),
+		("No tab with id: 42.", ErrorKind::TabNotFound),
+		// Firefox
+		("Promised response from onMessage listener went out of scope", ErrorKind::MessagePortClosed),
+		("Could not establish connection. Receiving end does not exist.", ErrorKind::NoReceivingEnd),
+		("QuotaExceededError: storage.sync API call exceeded its quota limitations.", ErrorKind::QuotaExceeded),
+		("Missing host permission for the tab", ErrorKind::PermissionMissing),
+		("can't access dead object", ErrorKind::ContextInvalidated),
+		("Invalid tab ID: 42", ErrorKind::TabNotFound),
+		// neither
+		("Invalid url pattern 'example.com'", ErrorKind::Other),
+	];
+	for (message, expected) in cases {
+		assert_eq!(kind(message), expected, "{message}");
+	}
+}
@@ -0,0 +1,162 @@
+// Runs inside the `tests/fixture` extension; see the `test-webext` recipe in the justfile.
+#![cfg(target_arch = "wasm32")]
+
+use js_sys::{Function, Map, Object, Promise, Reflect};
+use serde::{Deserialize, Serialize};
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+use webext_api::{
+	AlarmInfo, Browser,
+	error::{ErrorKind, ExtensionError},
+	init,
+};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Stored {
+	count: u32,
+	label: String,
+}
+
+#[wasm_bindgen_test]
+async fn storage_local_round_trip() {
+	let local = init().unwrap().storage().local();
+	let value = Stored { count: 3, label: "three".to_owned() };
+	local.set("round_trip", &value).await.unwrap();
+	assert_eq!(local.get::<Stored>("round_trip").await.unwrap(), Some(value));
+	assert_eq!(local.get::<Stored>("never_written").await.unwrap(), None);
+}
+
+#[derive(Serialize)]
+struct Echo<'a> {
+	echo: &'a str,
+}
+
+#[wasm_bindgen_test]
+async fn runtime_message_reaches_background() {
+	let reply: String = init().unwrap().runtime().send_message(&Echo { echo: "ping" }).await.unwrap();
+	assert_eq!(reply, "ping");
+}
+
+#[wasm_bindgen_test]
+async fn tabs_get_active_finds_runner() {
+	let tab = init().unwrap().tabs().get_active().await.unwrap();
+	assert!(tab.active);
+	assert!(tab.url.unwrap_or_default().ends_with("/runner.html"));
+}
+
+#[wasm_bindgen_test]
+async fn messages_nobody_receives_are_errors() {
+	let tabs = init().unwrap().tabs();
+	let runner = tabs.get_active().await.unwrap().id.unwrap();
+	// the runner tab has no content script
+	let err = tabs.send_message::<_, String>(runner, &Echo { echo: "nobody" }).await.unwrap_err();
+	assert_eq!(err.kind(), ErrorKind::NoReceivingEnd);
+}
+
+#[wasm_bindgen_test]
+async fn ports_nobody_accepts_say_why_they_closed() {
+	// the fixture's background doesn't listen for `onConnect`
+	let port = init().unwrap().runtime().connect(Some("nobody")).unwrap();
+	let closed = port.on_disconnect().unwrap().once().unwrap().await;
+	assert_eq!(closed.error().map(|err| err.kind()), Some(ErrorKind::NoReceivingEnd));
+}
+
+#[wasm_bindgen_test]
+async fn alarms_create_get_clear() {
+	let alarms = init().unwrap().alarms();
+	alarms.create("harness", AlarmInfo { delay_in_minutes: Some(5.0), period_in_minutes: None }).await.unwrap();
+	assert_eq!(alarms.get("harness").await.unwrap().map(|alarm| alarm.name).as_deref(), Some("harness"));
+	assert!(alarms.clear("harness").await.unwrap());
+	assert!(alarms.get("harness").await.unwrap().is_none());
+}
+
+#[wasm_bindgen_test]
+fn missing_namespaces_are_errors() {
+	let browser = init().unwrap();
+	// the fixture doesn't declare the `cookies` permission
+	assert!(matches!(browser.try_cookies(), Err(ExtensionError::ApiNotFound(name)) if name == "cookies"));
+	assert!(browser.try_storage().unwrap().try_session().is_ok());
+}
+
+#[wasm_bindgen_test]
+async fn global_browser_messages_background() {
+	let browser = Browser::global().unwrap();
+	let reply: String = browser.runtime().send_message(&Echo { echo: "first" }).await.unwrap();
+	assert_eq!(reply, "first");
+	let reply: String = Browser::global().unwrap().runtime().send_message(&Echo { echo: "second" }).await.unwrap();
+	assert_eq!(reply, "second");
+}
+
+// puts a `namespace` in place of the browser's whose `method` records the arguments before its callback and, like Chrome, calls
+// the callback with `result` if it gets one and returns a promise of it otherwise, so a test can see what a wrapper hands over
+fn fake_namespace(namespace: &str, method: &str, result: JsValue) -> Rc<RefCell<Vec<JsValue>>> {
+	let args = Rc::new(RefCell::new(Vec::new()));
+	let recorded = args.clone();
+	let fake = Closure::<dyn FnMut(JsValue, JsValue, JsValue) -> JsValue>::new(move |first: JsValue, second: JsValue, third: JsValue| {
+		let mut args = vec![first, second, third];
+		let callback = args.iter().position(JsValue::is_function).map(|position| args.split_off(position).remove(0));
+		*recorded.borrow_mut() = args;
+		match callback {
+			Some(callback) => {
+				let _ = callback.unchecked_into::<Function>().call1(&JsValue::UNDEFINED, &result);
+				JsValue::UNDEFINED
+			},
+			None => Promise::resolve(&result).into(),
+		}
+	});
+	let api = Object::new();
+	Reflect::set(&api, &method.into(), &fake.into_js_value()).unwrap();
+	for root in ["browser", "chrome"] {
+		if let Ok(root) = Reflect::get(&js_sys::global(), &root.into())
+			&& root.is_object()
+		{
+			Reflect::set(&root, &namespace.into(), &api).unwrap();
+		}
+	}
+	args
+}
+
+// a `Map` keeps its entries out of reach of a property lookup
+fn assert_plain_object(value: &JsValue, key: &str, expected: &str) {
+	assert!(value.is_object() && !value.is_instance_of::<Map>());
+	assert_eq!(Reflect::get(value, &key.into()).unwrap().as_string().as_deref(), Some(expected));
+}
+
+#[cfg(feature = "firefox")]
+#[wasm_bindgen_test]
+async fn menu_icons_are_plain_objects() {
+	use webext_api::ContextMenuConfig;
+
+	let args = fake_namespace("contextMenus", "create", JsValue::UNDEFINED);
+	let config = ContextMenuConfig::build("fake", "Fake").icons(&[(16, "icon.png")]).build();
+	init().unwrap().context_menus().create(config).await.unwrap();
+	assert_plain_object(&Reflect::get(&args.borrow()[0], &"icons".into()).unwrap(), "16", "icon.png");
+}
+
+#[wasm_bindgen_test]
+async fn gcm_maps_are_plain_objects() {
+	use webext_api::{GcmMessage, GcmTokenOptions};
+
+	let browser = init().unwrap();
+	let args = fake_namespace("gcm", "send", "fake-message".into());
+	let mut message = GcmMessage::new("sender@gcm.googleapis.com", "fake-message");
+	message.data.insert("kind".to_owned(), "ping".to_owned());
+	assert_eq!(browser.gcm().send(&message).await.unwrap(), "fake-message");
+	assert_plain_object(&Reflect::get(&args.borrow()[0], &"data".into()).unwrap(), "kind", "ping");
+
+	let args = fake_namespace("instanceID", "getToken", "fake-token".into());
+	let mut options = GcmTokenOptions::new("sender", "GCM");
+	options.options = Some([("audience".to_owned(), "fake".to_owned())].into());
+	assert_eq!(browser.instance_id().get_token(&options).await.unwrap(), "fake-token");
+	assert_plain_object(&Reflect::get(&args.borrow()[0], &"options".into()).unwrap(), "audience", "fake");
+}
+
+#[wasm_bindgen_test]
+async fn callback_results_are_not_lost() {
+	// on `chrome` the fake only answers through the callback and returns nothing, as callback-only methods do
+	fake_namespace("i18n", "getAcceptLanguages", serde_wasm_bindgen::to_value(&["en-US", "de"]).unwrap());
+	assert_eq!(init().unwrap().i18n().get_accept_languages().await.unwrap(), ["en-US", "de"]);
+}
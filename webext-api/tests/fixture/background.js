@@ -0,0 +1,13 @@
+const api = globalThis.browser ?? globalThis.chrome;
+
+// the suite runs in runner.html; open it as soon as the harness is loaded
+api.runtime.onInstalled.addListener(() => {
+  api.tabs.create({ url: api.runtime.getURL("runner.html") });
+});
+
+// lets the runtime messaging tests round-trip through a second extension context
+api.runtime.onMessage.addListener((message, _sender, sendResponse) => {
+  if (message && typeof message === "object" && "echo" in message) {
+    sendResponse(message.echo);
+  }
+});
@@ -0,0 +1,18 @@
+import init, { WasmBindgenTestContext } from "./pkg/extension.js";
+
+// mirrors what wasm-bindgen-test-runner does for `run_in_browser` suites, but from an extension page so the APIs under test exist
+const { reportUrl } = await (await fetch("./pkg/config.json")).json();
+const output = document.getElementById("output");
+
+let ok = false;
+try {
+  const wasm = await init();
+  const tests = Object.keys(wasm)
+    .filter((name) => name.startsWith("__wbgt_"))
+    .map((name) => wasm[name]);
+  ok = await new WasmBindgenTestContext(false).run(tests);
+} catch (error) {
+  output.textContent += `\nharness error: ${error}\n`;
+}
+
+await fetch(reportUrl, { method: "POST", body: JSON.stringify({ ok, output: output.textContent }) });
@@ -0,0 +1,54 @@
+use webext_api::{error::MatchPatternError, match_pattern::MatchPattern};
+
+fn pattern(pattern: &str) -> MatchPattern {
+	MatchPattern::parse(pattern).unwrap()
+}
+
+#[test]
+fn wildcard_subdomains_include_the_domain_itself() {
+	let pattern = pattern("*://*.example.com/*");
+	assert!(pattern.matches("https://example.com/"));
+	assert!(pattern.matches("http://docs.example.com/guide?page=2#intro"));
+	assert!(pattern.matches("wss://a.b.example.com:8443/socket"));
+	assert!(!pattern.matches("https://notexample.com/"));
+	assert!(!pattern.matches("ftp://example.com/file"));
+}
+
+#[test]
+fn paths_match_against_path_and_query() {
+	let pattern = pattern("https://example.com/search*q=rust*");
+	assert!(pattern.matches("https://example.com/search?q=rust+wasm"));
+	assert!(!pattern.matches("https://example.com/search?q=go"));
+	assert!(!pattern.matches("https://example.com/other?q=rust"));
+}
+
+#[test]
+fn ports_and_special_schemes() {
+	assert!(pattern("http://localhost:8080/*").matches("http://localhost:8080/app"));
+	assert!(!pattern("http://localhost:8080/*").matches("http://localhost:3000/app"));
+	assert!(pattern("http://localhost/*").matches("http://localhost:3000/app"));
+	assert!(pattern("https://example.com:443/*").matches("https://example.com/"));
+	assert!(pattern("file:///home/*").matches("file:///home/user/notes.html"));
+	assert!(pattern("<all_urls>").matches("file:///tmp/a.txt"));
+	assert!(!pattern("<all_urls>").matches("chrome://extensions/"));
+}
+
+#[test]
+fn invalid_patterns_are_rejected() {
+	assert_eq!(MatchPattern::parse("example.com/*"), Err(MatchPatternError::MissingSchemeSeparator));
+	assert_eq!(MatchPattern::parse("https://example.com"), Err(MatchPatternError::MissingPath));
+	assert_eq!(MatchPattern::parse("https://www.*.com/*"), Err(MatchPatternError::InvalidHost("www.*.com".to_owned())));
+	assert_eq!(MatchPattern::parse("https://example.com:http/*"), Err(MatchPatternError::InvalidPort("http".to_owned())));
+	assert_eq!(MatchPattern::parse("1http://example.com/*"), Err(MatchPatternError::InvalidScheme("1http".to_owned())));
+}
+
+#[test]
+fn site_patterns_cover_the_whole_host() {
+	let site = MatchPattern::for_site("https://User@Docs.Example.com:8443/guide?page=2#intro").unwrap();
+	assert_eq!(site.as_str(), "*://docs.example.com/*");
+	assert!(site.matches("http://docs.example.com/"));
+	assert!(!site.matches("https://example.com/"));
+	assert_eq!(MatchPattern::for_site("ftp://example.com/file").unwrap().as_str(), "ftp://example.com/*");
+	assert_eq!(MatchPattern::for_site("file:///home/user/notes.html"), None);
+	assert_eq!(MatchPattern::for_site("not a url"), None);
+}
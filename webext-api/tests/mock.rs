@@ -0,0 +1,48 @@
+#![cfg(feature = "mock")]
+
+use futures::executor::block_on;
+use serde_json::json;
+use webext_api::{
+	TabInfo,
+	backend::{BrowserBackend, RuntimeBackend, StorageBackend, TabsBackend},
+	error::ExtensionError,
+	mock::MockBrowser,
+};
+
+async fn remember_active_url<B: BrowserBackend>(browser: &B) -> Result<Option<String>, ExtensionError> {
+	let tab = browser.tabs().get_active().await?;
+	browser.local_storage().set("last_url", &tab.url).await?;
+	browser.local_storage().get("last_url").await
+}
+
+fn tab(id: u32, active: bool) -> TabInfo {
+	TabInfo { id: Some(id), url: Some(format!("https://example.com/{id}")), active, window_id: 1, ..Default::default() }
+}
+
+#[test]
+fn storage_round_trips_through_the_backend_traits() {
+	let browser = MockBrowser::new().with_tabs(vec![tab(1, false), tab(2, true)]);
+	let url = block_on(remember_active_url(&browser)).unwrap();
+	assert_eq!(url.as_deref(), Some("https://example.com/2"));
+	assert_eq!(browser.local().snapshot().get("last_url"), Some(&json!("https://example.com/2")));
+	assert!(browser.sync().snapshot().is_empty());
+}
+
+#[test]
+fn messages_reach_the_scripted_handler() {
+	let browser = MockBrowser::new().with_tabs(vec![tab(7, true)]);
+	browser.on_message(|tab_id, message| Ok(json!({ "tab": tab_id, "echo": message })));
+
+	let reply: serde_json::Value = block_on(browser.tabs().send_message(7, &"ping")).unwrap();
+	assert_eq!(reply, json!({ "tab": 7, "echo": "ping" }));
+	let reply: serde_json::Value = block_on(browser.runtime().send_message(&"pong")).unwrap();
+	assert_eq!(reply["tab"], json!(null));
+	assert_eq!(browser.sent_messages().len(), 2);
+}
+
+#[test]
+fn unanswered_and_misaddressed_messages_fail() {
+	let browser = MockBrowser::new();
+	assert!(matches!(block_on(browser.runtime().send_message::<_, ()>(&"hello")), Err(ExtensionError::ApiError(_))));
+	assert!(matches!(block_on(browser.tabs().send_message::<_, ()>(3, &"hello")), Err(ExtensionError::TabNotFound)));
+}
@@ -0,0 +1,25 @@
+use webext_api::{
+	error::{ErrorKind, ExtensionError},
+	retry::RetryPolicy,
+};
+
+fn api_error(message: &str) -> ExtensionError {
+	ExtensionError::ApiError(message.to_owned())
+}
+
+#[test]
+fn only_undelivered_messages_are_retried_by_default() {
+	let policy = RetryPolicy::default();
+	assert!(policy.retries(&api_error("Could not establish connection. Receiving end does not exist.")));
+	assert!(!policy.retries(&api_error("The message port closed before a response was received.")));
+	assert!(!policy.retries(&ExtensionError::Timeout("runtime.sendMessage".to_owned())));
+	assert!(!policy.retries(&ExtensionError::TabNotFound));
+}
+
+#[test]
+fn other_kinds_are_retried_once_opted_in() {
+	let policy = RetryPolicy::default().retry_on(ErrorKind::Timeout).retry_on(ErrorKind::MessagePortClosed);
+	assert!(policy.retries(&ExtensionError::Timeout("runtime.sendMessage".to_owned())));
+	assert!(policy.retries(&api_error("The message port closed before a response was received.")));
+	assert!(!policy.retries(&api_error("Extension context invalidated.")));
+}